@@ -0,0 +1,65 @@
+extern crate simplisp;
+
+use simplisp::*;
+use std::time::Duration;
+use std::time::Instant;
+
+const ITERATIONS: usize = 10000;
+
+// Compares dispatch and symbol lookup only. Built-in functions evaluate their own arguments through
+// the environment, so the arguments of the calls below run on the tree walker in both modes.
+
+unsafe fn add(arg: &(), environment: &mut Environment<()>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    let mut result = 0;
+    for object in args {
+        let value: i32 = try!(From::from(try!(environment.evaluate(arg, object))));
+        result += value;
+    }
+    Ok(ExecutionTreeObject::I32(result))
+}
+
+fn create_environment() -> Environment<()> {
+    let mut environment = Environment::new();
+    {
+        let global_frame = environment.get_global_frame();
+        global_frame.insert("add".to_string(), Symbol::BuiltInFunc(add));
+        global_frame.insert("speed".to_string(), Symbol::Object(ExecutionTreeObject::I32(3)));
+        global_frame.insert("position".to_string(), Symbol::Object(ExecutionTreeObject::I32(10)));
+    }
+    environment
+}
+
+fn print_result(name: &str, program_name: &str, duration: Duration) {
+    let nanoseconds = duration.as_secs() * 1_000_000_000 + duration.subsec_nanos() as u64;
+    println!("{:<16}{:<16}{:>12} ns/iter", program_name, name, nanoseconds / ITERATIONS as u64);
+}
+
+unsafe fn bench_program(program_name: &str, source: &str) {
+    let ast = AbstractSyntaxTree::new(source).unwrap();
+    let execution_tree = ExecutionTree::new(&ast).unwrap();
+
+    let mut environment = create_environment();
+    let root = ExecutionTreeObject::Node(execution_tree.get_root().clone());
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        environment.evaluate(&(), &root).unwrap();
+    }
+    print_result("tree-walking", program_name, start.elapsed());
+
+    let mut environment = create_environment();
+    let mut virtual_machine = VirtualMachine::new(Bytecode::compile(&execution_tree));
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        virtual_machine.run(&(), &mut environment).unwrap();
+    }
+    print_result("bytecode", program_name, start.elapsed());
+}
+
+fn main() {
+    unsafe {
+        bench_program("constants", "(1 2 3 4 5 6 7 8 9 10)");
+        bench_program("lookups", "((speed position) (position speed) (speed speed) (position position))");
+        bench_program("calls", "((add 1 2) (add 3 4) (add 5 6) (add 7 8))");
+        bench_program("nested-calls", "(add (add speed position) (add (add 1 2) (add 3 4)) (add position (add speed 5)))");
+    }
+}
@@ -0,0 +1,103 @@
+extern crate simplisp;
+
+use simplisp::*;
+use std::io;
+use std::io::BufReader;
+use std::io::Write;
+
+fn create_environment() -> Environment<()> {
+    let mut environment = Environment::new();
+    {
+        let global_frame = environment.get_global_frame();
+        builtin::control::register(global_frame);
+        builtin::definition::register(global_frame);
+        builtin::exception::register(global_frame);
+        builtin::macros::register(global_frame);
+        builtin::module::register(global_frame);
+    }
+    let debugger_console = DebuggerConsole::new(BufReader::new(io::stdin()), io::stdout());
+    environment.set_debugger_front_end(Box::new(debugger_console));
+    environment
+}
+
+fn run_command(environment: &mut Environment<()>, line: &str) -> bool {
+    let (command, rest) =
+        match line.find(' ') {
+            Some(index) => (&line[..index], line[index + 1..].trim()),
+            None => (line, ""),
+        };
+
+    match command {
+        ":break" => {
+            let breakpoint =
+                if rest.starts_with("line ") {
+                    match rest[5..].trim().parse::<usize>() {
+                        Ok(line) => Breakpoint::Line(None, line),
+                        Err(_) => {
+                            println!("invalid line: {}", rest);
+                            return true;
+                        },
+                    }
+                } else {
+                    Breakpoint::Symbol(rest.to_string())
+                };
+            environment.get_debugger().add_breakpoint(breakpoint);
+        },
+        ":delete" => {
+            environment.get_debugger().clear_breakpoints();
+        },
+        ":step" => {
+            environment.get_debugger().resume(DebuggerAction::StepInto, 0);
+        },
+        ":quit" => {
+            return false;
+        },
+        _ => {
+            println!("commands: :break SYMBOL, :break line N, :delete, :step, :quit");
+        },
+    }
+    true
+}
+
+fn main() {
+    let mut environment = create_environment();
+
+    loop {
+        print!("> ");
+        if io::stdout().flush().is_err() {
+            return;
+        }
+
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => {
+                return;
+            },
+            Ok(_) => {},
+        }
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with(':') {
+            if !run_command(&mut environment, line) {
+                return;
+            }
+            continue;
+        }
+
+        match unsafe { environment.parse_and_execute(&(), line) } {
+            Ok(result) => println!("{}", result),
+            Err(error) => {
+                println!("error: {}", error);
+                if let Some(forms) = error.get_lisp_backtrace() {
+                    for form in forms {
+                        println!("    in {}", form);
+                    }
+                }
+            },
+        }
+    }
+}
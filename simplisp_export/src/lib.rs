@@ -0,0 +1,326 @@
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::format_ident;
+use quote::quote;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::Attribute;
+use syn::Error;
+use syn::Expr;
+use syn::FnArg;
+use syn::Ident;
+use syn::ImplItem;
+use syn::Item;
+use syn::ItemFn;
+use syn::ItemImpl;
+use syn::ItemMod;
+use syn::Lit;
+use syn::Meta;
+use syn::MetaNameValue;
+use syn::ReturnType;
+use syn::Signature;
+use syn::Token;
+use syn::Type;
+use syn::Visibility;
+
+const LISP_ATTRIBUTE: &str = "lisp";
+
+struct Export {
+    doc: String,
+    glue: Ident,
+    lisp_name: String,
+}
+
+/// Exposes a function, the public functions of an impl block or the public functions of an inline
+/// module as simplisp builtins, along with a `register` function that inserts them into a frame.
+///
+/// An exported function gets a module of the same name holding its `register` function. That module
+/// refers to the function through `super`, so the function must be an item of a module; a function
+/// declared inside a block cannot be exported.
+#[proc_macro_attribute]
+pub fn export(attribute: TokenStream, item: TokenStream) -> TokenStream {
+    let result =
+        match syn::parse::<Item>(item) {
+            Ok(Item::Fn(item_fn)) => export_fn(attribute.into(), item_fn),
+            Ok(Item::Impl(item_impl)) => export_impl(attribute.into(), item_impl),
+            Ok(Item::Mod(item_mod)) => export_mod(attribute.into(), item_mod),
+            Ok(other) => Err(Error::new_spanned(other, "#[simplisp::export] applies to functions, impl blocks and inline modules")),
+            Err(error) => Err(error),
+        };
+
+    match result {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn export_fn(attribute: TokenStream2, mut item_fn: ItemFn) -> Result<TokenStream2, Error> {
+    let mut lisp_name = parse_lisp_name(attribute)?;
+    if let Some(name) = take_lisp_name(&mut item_fn.attrs)? {
+        lisp_name = Some(name);
+    }
+    let fn_name = item_fn.sig.ident.clone();
+    let lisp_name = lisp_name.unwrap_or_else(|| to_lisp_name(&fn_name));
+    let doc = get_doc(&item_fn.attrs);
+    let call = quote!(super::#fn_name);
+    let glue = format_ident!("builtin");
+    let glue_fn = create_glue(&item_fn.sig, &glue, &lisp_name, call)?;
+    let register = create_register(&[Export { doc, glue, lisp_name }], quote!());
+    let vis = &item_fn.vis;
+
+    Ok(quote! {
+        #item_fn
+
+        #[allow(dead_code)]
+        #vis mod #fn_name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #glue_fn
+
+            #register
+        }
+    })
+}
+
+fn export_impl(attribute: TokenStream2, mut item_impl: ItemImpl) -> Result<TokenStream2, Error> {
+    if !attribute.is_empty() {
+        return Err(Error::new_spanned(attribute, "#[simplisp::export] on an impl block takes no arguments"));
+    }
+
+    let mut exports = Vec::new();
+    let mut glue_fns = Vec::new();
+    for impl_item in &mut item_impl.items {
+        let impl_item_fn =
+            match impl_item {
+                ImplItem::Fn(impl_item_fn) => impl_item_fn,
+                _ => continue,
+            };
+        let lisp_name = take_lisp_name(&mut impl_item_fn.attrs)?;
+        if !is_exported(&impl_item_fn.vis, &impl_item_fn.sig) {
+            continue;
+        }
+
+        let fn_name = impl_item_fn.sig.ident.clone();
+        let lisp_name = lisp_name.unwrap_or_else(|| to_lisp_name(&fn_name));
+        let glue = format_ident!("__simplisp_export_{}", fn_name);
+        glue_fns.push(create_glue(&impl_item_fn.sig, &glue, &lisp_name, quote!(Self::#fn_name))?);
+        exports.push(Export { doc: get_doc(&impl_item_fn.attrs), glue, lisp_name });
+    }
+
+    let register = create_register(&exports, quote!(Self::));
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
+    let self_ty = &item_impl.self_ty;
+
+    Ok(quote! {
+        #item_impl
+
+        #[allow(dead_code)]
+        impl #impl_generics #self_ty #where_clause {
+            #(#glue_fns)*
+
+            #register
+        }
+    })
+}
+
+fn export_mod(attribute: TokenStream2, mut item_mod: ItemMod) -> Result<TokenStream2, Error> {
+    if !attribute.is_empty() {
+        return Err(Error::new_spanned(attribute, "#[simplisp::export] on a module takes no arguments"));
+    }
+
+    let items =
+        match item_mod.content {
+            Some((_, ref mut items)) => items,
+            None => {
+                return Err(Error::new_spanned(item_mod, "#[simplisp::export] requires an inline module"));
+            },
+        };
+
+    let mut exports = Vec::new();
+    let mut glue_fns = Vec::new();
+    for item in items.iter_mut() {
+        let item_fn =
+            match item {
+                Item::Fn(item_fn) => item_fn,
+                _ => continue,
+            };
+        let lisp_name = take_lisp_name(&mut item_fn.attrs)?;
+        if !is_exported(&item_fn.vis, &item_fn.sig) {
+            continue;
+        }
+
+        let fn_name = item_fn.sig.ident.clone();
+        let lisp_name = lisp_name.unwrap_or_else(|| to_lisp_name(&fn_name));
+        let glue = format_ident!("__simplisp_export_{}", fn_name);
+        glue_fns.push(create_glue(&item_fn.sig, &glue, &lisp_name, quote!(#fn_name))?);
+        exports.push(Export { doc: get_doc(&item_fn.attrs), glue, lisp_name });
+    }
+
+    let register = create_register(&exports, quote!());
+    for glue_fn in glue_fns {
+        items.push(Item::Verbatim(glue_fn));
+    }
+    items.push(Item::Verbatim(register));
+
+    Ok(quote!(#item_mod))
+}
+
+fn create_glue(sig: &Signature, glue: &Ident, lisp_name: &str, call: TokenStream2) -> Result<TokenStream2, Error> {
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new_spanned(&sig.generics, "exported functions cannot be generic"));
+    }
+    if sig.asyncness.is_some() {
+        return Err(Error::new_spanned(sig, "exported functions cannot be async"));
+    }
+
+    let mut argument_types: Vec<&Type> = Vec::new();
+    for input in &sig.inputs {
+        match input {
+            FnArg::Typed(pat_type) => argument_types.push(&pat_type.ty),
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new_spanned(receiver, "exported functions cannot take self"));
+            },
+        }
+    }
+    let arguments: Vec<Ident> = (0..argument_types.len()).map(|index| format_ident!("argument_{}", index)).collect();
+    let call_expression =
+        match sig.output {
+            ReturnType::Default => quote!({ #call(#(#arguments),*); () }),
+            ReturnType::Type(..) => quote!(#call(#(#arguments),*)),
+        };
+    let call_unsafe =
+        if sig.unsafety.is_some() {
+            quote!(unsafe { #call_expression })
+        } else {
+            call_expression
+        };
+
+    Ok(quote! {
+        pub unsafe fn #glue<TArg>(arg: &TArg, environment: &mut ::simplisp::Environment<TArg>, args: Vec<&::simplisp::ExecutionTreeObject>) -> ::simplisp::Result<::simplisp::ExecutionTreeObject> {
+            let min_count = 0usize #(+ <#argument_types as ::simplisp::NativeArgument>::get_min_count())*;
+            let mut max_count = Some(0usize);
+            #(
+                max_count =
+                    match (max_count, <#argument_types as ::simplisp::NativeArgument>::get_max_count()) {
+                        (Some(max_count), Some(count)) => Some(max_count + count),
+                        _ => None,
+                    };
+            )*
+            let objects = ::simplisp::NativeFunction::evaluate_arguments(arg, environment, #lisp_name, min_count, max_count, args)?;
+            #[allow(unused_mut, unused_variables)]
+            let mut optional_count = objects.len().saturating_sub(min_count);
+            #[allow(unused_mut, unused_variables)]
+            let mut objects = objects.into_iter();
+            #(
+                let #arguments = <#argument_types as ::simplisp::NativeArgument>::take(&mut objects, &mut optional_count)?;
+            )*
+            ::simplisp::NativeReturn::into_object(#call_unsafe)
+        }
+    })
+}
+
+fn create_register(exports: &[Export], prefix: TokenStream2) -> TokenStream2 {
+    let inserts =
+        exports.iter().map(|export| {
+            let doc = &export.doc;
+            let glue = &export.glue;
+            let lisp_name = &export.lisp_name;
+            let set_doc =
+                if doc.is_empty() {
+                    quote!()
+                } else {
+                    quote!(frame.set_doc(#lisp_name.to_string(), #doc.to_string());)
+                };
+            quote! {
+                frame.insert(#lisp_name.to_string(), ::simplisp::Symbol::BuiltInFunc(#prefix #glue::<TArg>));
+                #set_doc
+            }
+        });
+
+    quote! {
+        pub fn register<TArg>(frame: &mut ::simplisp::Frame<TArg>) {
+            #(#inserts)*
+        }
+    }
+}
+
+fn get_doc(attributes: &[Attribute]) -> String {
+    let mut lines = Vec::new();
+    for attribute in attributes {
+        if !attribute.path().is_ident("doc") {
+            continue;
+        }
+        if let Meta::NameValue(MetaNameValue { value: Expr::Lit(ref expr_lit), .. }) = attribute.meta {
+            if let Lit::Str(ref lit_str) = expr_lit.lit {
+                lines.push(lit_str.value().trim().to_string());
+            }
+        }
+    }
+    lines.join("\n").trim().to_string()
+}
+
+fn is_exported(vis: &Visibility, sig: &Signature) -> bool {
+    let is_public = !matches!(vis, Visibility::Inherited);
+    let has_receiver = sig.inputs.iter().any(|input| matches!(input, FnArg::Receiver(_)));
+    is_public && !has_receiver
+}
+
+fn parse_lisp_name(attribute: TokenStream2) -> Result<Option<String>, Error> {
+    if attribute.is_empty() {
+        return Ok(None);
+    }
+
+    let metas = Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse2(attribute)?;
+    let mut result = None;
+    for meta in metas {
+        if !meta.path.is_ident("name") {
+            return Err(Error::new_spanned(meta.path, "expected `name = \"...\"`"));
+        }
+        match meta.value {
+            Expr::Lit(ref expr_lit) => {
+                match expr_lit.lit {
+                    Lit::Str(ref lit_str) => result = Some(lit_str.value()),
+                    ref other => {
+                        return Err(Error::new_spanned(other, "expected a string literal"));
+                    },
+                }
+            },
+            ref other => {
+                return Err(Error::new_spanned(other, "expected a string literal"));
+            },
+        }
+    }
+    Ok(result)
+}
+
+fn take_lisp_name(attributes: &mut Vec<Attribute>) -> Result<Option<String>, Error> {
+    let mut result = None;
+    let mut error = None;
+    attributes.retain(|attribute| {
+        if !attribute.path().is_ident(LISP_ATTRIBUTE) {
+            return true;
+        }
+        match attribute.meta.require_list() {
+            Ok(list) => {
+                match parse_lisp_name(list.tokens.clone()) {
+                    Ok(name) => result = name,
+                    Err(parse_error) => error = Some(parse_error),
+                }
+            },
+            Err(list_error) => error = Some(list_error),
+        }
+        false
+    });
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(result),
+    }
+}
+
+fn to_lisp_name(ident: &Ident) -> String {
+    ident.to_string().trim_start_matches("r#").replace('_', "-")
+}
@@ -0,0 +1,678 @@
+use builtin::util;
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::OverflowPolicy;
+use lisp::Symbol;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::One;
+use num_traits::Signed;
+use num_traits::ToPrimitive;
+use num_traits::Zero;
+use std::cmp::Ordering;
+use std::mem;
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Add,
+    Divide,
+    Modulo,
+    Multiply,
+    Remainder,
+    Subtract,
+}
+
+// Promotion rules for mixed operands:
+// - Two values of the same type keep that type.
+// - Any F64 operand gives F64. F32 stays F32 with another F32 or a fixed width integer of at most 16 bits,
+//   otherwise it widens to F64. Floats are inexact, so a float mixed with a BigInt or Rational is a float.
+// - Otherwise any Rational operand gives a Rational, and any BigInt operand gives a BigInt.
+// - Fixed width integers of the same signedness widen to the larger type, preferring the fixed width type
+//   when an isize or usize has the same width.
+// - A signed and an unsigned integer give the signed type if it is wider, otherwise the signed type with
+//   twice the width of the unsigned one, capped at i64.
+// Fixed width results that do not fit the promoted type are handled by the environment's overflow policy,
+// where Promote turns them into a BigInt. Floats always follow IEEE semantics.
+#[derive(Clone, Copy, PartialEq)]
+enum NumericType {
+    BigInt,
+    F32,
+    F64,
+    I8,
+    I16,
+    I32,
+    I64,
+    ISize,
+    Rational,
+    U8,
+    U16,
+    U32,
+    U64,
+    USize,
+}
+
+impl NumericType {
+    fn from_name(name: &str) -> Option<NumericType> {
+        match name {
+            "bigint" => Some(NumericType::BigInt),
+            "f32" => Some(NumericType::F32),
+            "f64" => Some(NumericType::F64),
+            "i8" => Some(NumericType::I8),
+            "i16" => Some(NumericType::I16),
+            "i32" => Some(NumericType::I32),
+            "i64" => Some(NumericType::I64),
+            "isize" => Some(NumericType::ISize),
+            "rational" => Some(NumericType::Rational),
+            "u8" => Some(NumericType::U8),
+            "u16" => Some(NumericType::U16),
+            "u32" => Some(NumericType::U32),
+            "u64" => Some(NumericType::U64),
+            "usize" => Some(NumericType::USize),
+            _ => None,
+        }
+    }
+
+    fn from_object(object: &ExecutionTreeObject) -> Result<NumericType> {
+        match object {
+            &ExecutionTreeObject::BigInt(_) => Ok(NumericType::BigInt),
+            &ExecutionTreeObject::F32(_) => Ok(NumericType::F32),
+            &ExecutionTreeObject::F64(_) => Ok(NumericType::F64),
+            &ExecutionTreeObject::I8(_) => Ok(NumericType::I8),
+            &ExecutionTreeObject::I16(_) => Ok(NumericType::I16),
+            &ExecutionTreeObject::I32(_) => Ok(NumericType::I32),
+            &ExecutionTreeObject::I64(_) => Ok(NumericType::I64),
+            &ExecutionTreeObject::ISize(_) => Ok(NumericType::ISize),
+            &ExecutionTreeObject::Rational(_) => Ok(NumericType::Rational),
+            &ExecutionTreeObject::U8(_) => Ok(NumericType::U8),
+            &ExecutionTreeObject::U16(_) => Ok(NumericType::U16),
+            &ExecutionTreeObject::U32(_) => Ok(NumericType::U32),
+            &ExecutionTreeObject::U64(_) => Ok(NumericType::U64),
+            &ExecutionTreeObject::USize(_) => Ok(NumericType::USize),
+            other => Err(ErrorKind::InvalidExecutionTreeObjectConversion(other.enum_to_string().to_string(), "number".to_string()).into()),
+        }
+    }
+
+    fn get_bits(&self) -> usize {
+        match *self {
+            NumericType::I8 | NumericType::U8 => 8,
+            NumericType::I16 | NumericType::U16 => 16,
+            NumericType::F32 | NumericType::I32 | NumericType::U32 => 32,
+            NumericType::F64 | NumericType::I64 | NumericType::U64 => 64,
+            NumericType::ISize | NumericType::USize => mem::size_of::<usize>() * 8,
+            NumericType::BigInt | NumericType::Rational => usize::max_value(),
+        }
+    }
+
+    fn get_range(&self) -> (i128, i128) {
+        match *self {
+            NumericType::BigInt | NumericType::F32 | NumericType::F64 | NumericType::Rational => (i128::min_value(), i128::max_value()),
+            NumericType::I8 => (i8::min_value() as i128, i8::max_value() as i128),
+            NumericType::I16 => (i16::min_value() as i128, i16::max_value() as i128),
+            NumericType::I32 => (i32::min_value() as i128, i32::max_value() as i128),
+            NumericType::I64 => (i64::min_value() as i128, i64::max_value() as i128),
+            NumericType::ISize => (isize::min_value() as i128, isize::max_value() as i128),
+            NumericType::U8 => (0, u8::max_value() as i128),
+            NumericType::U16 => (0, u16::max_value() as i128),
+            NumericType::U32 => (0, u32::max_value() as i128),
+            NumericType::U64 => (0, u64::max_value() as i128),
+            NumericType::USize => (0, usize::max_value() as i128),
+        }
+    }
+
+    fn get_type_str(&self) -> &'static str {
+        match *self {
+            NumericType::BigInt => ExecutionTreeObject::big_int_str(),
+            NumericType::F32 => ExecutionTreeObject::f32_str(),
+            NumericType::F64 => ExecutionTreeObject::f64_str(),
+            NumericType::I8 => ExecutionTreeObject::i8_str(),
+            NumericType::I16 => ExecutionTreeObject::i16_str(),
+            NumericType::I32 => ExecutionTreeObject::i32_str(),
+            NumericType::I64 => ExecutionTreeObject::i64_str(),
+            NumericType::ISize => ExecutionTreeObject::isize_str(),
+            NumericType::Rational => ExecutionTreeObject::rational_str(),
+            NumericType::U8 => ExecutionTreeObject::u8_str(),
+            NumericType::U16 => ExecutionTreeObject::u16_str(),
+            NumericType::U32 => ExecutionTreeObject::u32_str(),
+            NumericType::U64 => ExecutionTreeObject::u64_str(),
+            NumericType::USize => ExecutionTreeObject::usize_str(),
+        }
+    }
+
+    fn is_fixed(&self) -> bool {
+        !self.is_float() && *self != NumericType::BigInt && *self != NumericType::Rational
+    }
+
+    fn is_float(&self) -> bool {
+        match *self {
+            NumericType::F32 | NumericType::F64 => true,
+            _ => false,
+        }
+    }
+
+    fn is_signed(&self) -> bool {
+        match *self {
+            NumericType::U8 | NumericType::U16 | NumericType::U32 | NumericType::U64 | NumericType::USize => false,
+            _ => true,
+        }
+    }
+
+    fn is_size(&self) -> bool {
+        match *self {
+            NumericType::ISize | NumericType::USize => true,
+            _ => false,
+        }
+    }
+
+    fn promote(&self, other: NumericType) -> NumericType {
+        let this = *self;
+        if this == other {
+            return this;
+        }
+
+        if this.is_float() || other.is_float() {
+            if this == NumericType::F64 || other == NumericType::F64 {
+                return NumericType::F64;
+            }
+            let integer = if this.is_float() { other } else { this };
+            return if integer.get_bits() <= 16 { NumericType::F32 } else { NumericType::F64 };
+        }
+
+        if this == NumericType::Rational || other == NumericType::Rational {
+            return NumericType::Rational;
+        }
+        if this == NumericType::BigInt || other == NumericType::BigInt {
+            return NumericType::BigInt;
+        }
+
+        if this.is_signed() == other.is_signed() {
+            if this.get_bits() == other.get_bits() {
+                if this.is_size() { other } else { this }
+            } else if this.get_bits() > other.get_bits() {
+                this
+            } else {
+                other
+            }
+        } else {
+            let (signed, unsigned) = if this.is_signed() { (this, other) } else { (other, this) };
+            if signed.get_bits() > unsigned.get_bits() {
+                signed
+            } else {
+                match unsigned.get_bits() {
+                    8 => NumericType::I16,
+                    16 => NumericType::I32,
+                    _ => NumericType::I64,
+                }
+            }
+        }
+    }
+}
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert("*".to_string(), Symbol::BuiltInFunc(multiply::<TArg>));
+    frame.insert("+".to_string(), Symbol::BuiltInFunc(add::<TArg>));
+    frame.insert("-".to_string(), Symbol::BuiltInFunc(subtract::<TArg>));
+    frame.insert("/".to_string(), Symbol::BuiltInFunc(divide::<TArg>));
+    frame.insert("abs".to_string(), Symbol::BuiltInFunc(abs::<TArg>));
+    frame.insert("as".to_string(), Symbol::BuiltInFunc(cast::<TArg>));
+    frame.insert("max".to_string(), Symbol::BuiltInFunc(max::<TArg>));
+    frame.insert("min".to_string(), Symbol::BuiltInFunc(min::<TArg>));
+    frame.insert("mod".to_string(), Symbol::BuiltInFunc(modulo::<TArg>));
+    frame.insert("rem".to_string(), Symbol::BuiltInFunc(remainder::<TArg>));
+}
+
+pub unsafe fn abs<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("abs", &args, 1, Some(1)));
+
+    let numbers = try!(evaluate_numbers(arg, environment, &args));
+    let numeric_type = try!(NumericType::from_object(&numbers[0]));
+    if numeric_type.is_float() {
+        Ok(from_float(to_float(&numbers[0]).abs(), numeric_type))
+    } else if numeric_type.is_fixed() {
+        from_integer("abs", to_integer(&numbers[0]).abs(), numeric_type, environment.get_overflow_policy())
+    } else {
+        Ok(from_rational(to_rational(&numbers[0]).abs(), numeric_type))
+    }
+}
+
+pub unsafe fn add<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    fold("+", Operation::Add, arg, environment, &args, 0)
+}
+
+pub unsafe fn cast<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("as", &args, 2, Some(2)));
+
+    let numeric_type =
+        match NumericType::from_name(try!(util::get_symbol("as", args[0]))) {
+            Some(numeric_type) => numeric_type,
+            None => {
+                return Err(util::invalid_form("as", args[0]));
+            },
+        };
+    let numbers = try!(evaluate_numbers(arg, environment, &args[1..]));
+
+    // An explicit cast names its result type, so it never promotes.
+    let policy =
+        match environment.get_overflow_policy() {
+            OverflowPolicy::Promote => OverflowPolicy::Checked,
+            policy => policy,
+        };
+    convert("as", &numbers[0], numeric_type, policy)
+}
+
+pub unsafe fn divide<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("/", &args, 1, None));
+
+    fold("/", Operation::Divide, arg, environment, &args, 1)
+}
+
+pub unsafe fn max<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    select("max", arg, environment, &args, Ordering::Greater)
+}
+
+pub unsafe fn min<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    select("min", arg, environment, &args, Ordering::Less)
+}
+
+pub unsafe fn modulo<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("mod", &args, 2, Some(2)));
+
+    let numbers = try!(evaluate_numbers(arg, environment, &args));
+    apply("mod", Operation::Modulo, environment.get_overflow_policy(), &numbers[0], &numbers[1])
+}
+
+pub unsafe fn multiply<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    fold("*", Operation::Multiply, arg, environment, &args, 1)
+}
+
+pub unsafe fn remainder<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("rem", &args, 2, Some(2)));
+
+    let numbers = try!(evaluate_numbers(arg, environment, &args));
+    apply("rem", Operation::Remainder, environment.get_overflow_policy(), &numbers[0], &numbers[1])
+}
+
+pub unsafe fn subtract<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("-", &args, 1, None));
+
+    fold("-", Operation::Subtract, arg, environment, &args, 0)
+}
+
+fn apply(function: &str, operation: Operation, policy: OverflowPolicy, left: &ExecutionTreeObject, right: &ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+    let numeric_type = try!(NumericType::from_object(left)).promote(try!(NumericType::from_object(right)));
+    if numeric_type.is_float() {
+        return Ok(from_float(apply_float(operation, to_float(left), to_float(right)), numeric_type));
+    }
+
+    let result =
+        if numeric_type.is_fixed() {
+            match apply_integer(operation, to_integer(left), to_integer(right)) {
+                Some(value) => Some(try!(from_integer(function, value, numeric_type, policy))),
+                None => {
+                    // The i128 operation overflowed or divided by zero, so redo it exactly and let the policy
+                    // decide what to do with a result outside the promoted type.
+                    match apply_rational(operation, to_rational(left), to_rational(right), true) {
+                        Some(value) => Some(try!(from_big_integer(function, value.to_integer(), numeric_type, policy))),
+                        None => None,
+                    }
+                },
+            }
+        } else {
+            let is_integer = numeric_type == NumericType::BigInt;
+            apply_rational(operation, to_rational(left), to_rational(right), is_integer).map(|value| from_rational(value, numeric_type))
+        };
+
+    match result {
+        Some(result) => Ok(result),
+        None => Err(ErrorKind::DivisionByZero(function.to_string()).into()),
+    }
+}
+
+fn apply_float(operation: Operation, left: f64, right: f64) -> f64 {
+    match operation {
+        Operation::Add => left + right,
+        Operation::Divide => left / right,
+        Operation::Modulo => {
+            let result = left % right;
+            if result != 0.0 && (result < 0.0) != (right < 0.0) { result + right } else { result }
+        },
+        Operation::Multiply => left * right,
+        Operation::Remainder => left % right,
+        Operation::Subtract => left - right,
+    }
+}
+
+// Returns None when the operation overflows an i128 or divides by zero.
+fn apply_integer(operation: Operation, left: i128, right: i128) -> Option<i128> {
+    match operation {
+        Operation::Add => left.checked_add(right),
+        Operation::Divide => left.checked_div(right),
+        Operation::Modulo => {
+            match left.checked_rem(right) {
+                Some(result) if result != 0 && (result < 0) != (right < 0) => result.checked_add(right),
+                other => other,
+            }
+        },
+        Operation::Multiply => left.checked_mul(right),
+        Operation::Remainder => left.checked_rem(right),
+        Operation::Subtract => left.checked_sub(right),
+    }
+}
+
+// BigInt arithmetic runs on rationals with a denominator of one, truncating after division.
+fn apply_rational(operation: Operation, left: BigRational, right: BigRational, is_integer: bool) -> Option<BigRational> {
+    match operation {
+        Operation::Add => Some(left + right),
+        Operation::Multiply => Some(left * right),
+        Operation::Subtract => Some(left - right),
+        _ if right.is_zero() => None,
+        Operation::Divide => {
+            let result = left / right;
+            if is_integer { Some(result.trunc()) } else { Some(result) }
+        },
+        Operation::Modulo => {
+            let quotient = (&left / &right).floor();
+            Some(left - right * quotient)
+        },
+        Operation::Remainder => {
+            let quotient = (&left / &right).trunc();
+            Some(left - right * quotient)
+        },
+    }
+}
+
+fn convert(function: &str, object: &ExecutionTreeObject, numeric_type: NumericType, policy: OverflowPolicy) -> Result<ExecutionTreeObject> {
+    let source_type = try!(NumericType::from_object(object));
+    if numeric_type.is_float() {
+        Ok(from_float(to_float(object), numeric_type))
+    } else if source_type.is_float() {
+        let value = to_float(object);
+        if numeric_type.is_fixed() {
+            let truncated = value.trunc();
+            let bits = numeric_type.get_bits() as i32;
+            let (min, max) =
+                if numeric_type.is_signed() {
+                    (-(2f64.powi(bits - 1)), 2f64.powi(bits - 1))
+                } else {
+                    (0f64, 2f64.powi(bits))
+                };
+            if policy == OverflowPolicy::Checked && !(truncated >= min && truncated < max) {
+                return Err(ErrorKind::ArithmeticOverflow(function.to_string(), numeric_type.get_type_str().to_string()).into());
+            }
+            from_integer(function, value as i128, numeric_type, policy)
+        } else {
+            match BigRational::from_float(value) {
+                Some(value) => Ok(from_rational(if numeric_type == NumericType::BigInt { value.trunc() } else { value }, numeric_type)),
+                None => Err(ErrorKind::ArithmeticOverflow(function.to_string(), numeric_type.get_type_str().to_string()).into()),
+            }
+        }
+    } else if source_type.is_fixed() {
+        from_integer(function, to_integer(object), numeric_type, policy)
+    } else if numeric_type.is_fixed() {
+        from_big_integer(function, to_rational(object).trunc().to_integer(), numeric_type, policy)
+    } else if numeric_type == NumericType::BigInt {
+        Ok(from_rational(to_rational(object).trunc(), numeric_type))
+    } else {
+        Ok(from_rational(to_rational(object), numeric_type))
+    }
+}
+
+unsafe fn evaluate_numbers<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: &[&ExecutionTreeObject]) -> Result<Vec<ExecutionTreeObject>> {
+    let mut result = Vec::with_capacity(args.len());
+    for object in args {
+        let number = try!(environment.evaluate(arg, object));
+        try!(NumericType::from_object(&number));
+        result.push(number);
+    }
+    Ok(result)
+}
+
+// A single argument is combined with the identity of its own type, so (- x) negates and (/ x) takes the
+// reciprocal.
+unsafe fn fold<TArg>(function: &str, operation: Operation, arg: &TArg, environment: &mut Environment<TArg>, args: &[&ExecutionTreeObject], identity: i32) -> Result<ExecutionTreeObject> {
+    let mut numbers = try!(evaluate_numbers(arg, environment, args));
+    let policy = environment.get_overflow_policy();
+    if numbers.is_empty() {
+        return Ok(ExecutionTreeObject::I32(identity));
+    }
+    if numbers.len() == 1 {
+        let numeric_type = try!(NumericType::from_object(&numbers[0]));
+        numbers.insert(0, try!(from_integer(function, identity as i128, numeric_type, policy)));
+    }
+
+    let mut numbers = numbers.into_iter();
+    let mut result =
+        match numbers.next() {
+            Some(first) => first,
+            None => ExecutionTreeObject::I32(identity),
+        };
+    for number in numbers {
+        result = try!(apply(function, operation, policy, &result, &number));
+    }
+    Ok(result)
+}
+
+// Narrows an integer that may not fit an i128 to a fixed width type. Saturating and wrapping only need
+// the value clamped or reduced to 128 bits, since every fixed width type is narrower.
+fn from_big_integer(function: &str, value: BigInt, numeric_type: NumericType, policy: OverflowPolicy) -> Result<ExecutionTreeObject> {
+    match value.to_i128() {
+        Some(value) => from_integer(function, value, numeric_type, policy),
+        None => {
+            match policy {
+                OverflowPolicy::Checked => Err(ErrorKind::ArithmeticOverflow(function.to_string(), numeric_type.get_type_str().to_string()).into()),
+                OverflowPolicy::Promote => Ok(ExecutionTreeObject::BigInt(value)),
+                OverflowPolicy::Saturating => {
+                    let value = if value.is_negative() { i128::min_value() } else { i128::max_value() };
+                    from_integer(function, value, numeric_type, policy)
+                },
+                OverflowPolicy::Wrapping => {
+                    let modulus = BigInt::one() << 128usize;
+                    let value = value.mod_floor(&modulus).to_u128().unwrap_or(0) as i128;
+                    from_integer(function, value, numeric_type, policy)
+                },
+            }
+        },
+    }
+}
+
+fn from_float(value: f64, numeric_type: NumericType) -> ExecutionTreeObject {
+    match numeric_type {
+        NumericType::F32 => ExecutionTreeObject::F32(value as f32),
+        _ => ExecutionTreeObject::F64(value),
+    }
+}
+
+fn from_integer(function: &str, value: i128, numeric_type: NumericType, policy: OverflowPolicy) -> Result<ExecutionTreeObject> {
+    let (min, max) = numeric_type.get_range();
+    let value =
+        if value < min || value > max {
+            match policy {
+                OverflowPolicy::Checked => {
+                    return Err(ErrorKind::ArithmeticOverflow(function.to_string(), numeric_type.get_type_str().to_string()).into());
+                },
+                OverflowPolicy::Promote => {
+                    return Ok(ExecutionTreeObject::BigInt(BigInt::from(value)));
+                },
+                OverflowPolicy::Saturating => if value < min { min } else { max },
+                OverflowPolicy::Wrapping => value,
+            }
+        } else {
+            value
+        };
+
+    let result =
+        match numeric_type {
+            NumericType::BigInt => ExecutionTreeObject::BigInt(BigInt::from(value)),
+            NumericType::F32 => ExecutionTreeObject::F32(value as f32),
+            NumericType::F64 => ExecutionTreeObject::F64(value as f64),
+            NumericType::I8 => ExecutionTreeObject::I8(value as i8),
+            NumericType::I16 => ExecutionTreeObject::I16(value as i16),
+            NumericType::I32 => ExecutionTreeObject::I32(value as i32),
+            NumericType::I64 => ExecutionTreeObject::I64(value as i64),
+            NumericType::ISize => ExecutionTreeObject::ISize(value as isize),
+            NumericType::Rational => ExecutionTreeObject::Rational(BigRational::from_integer(BigInt::from(value))),
+            NumericType::U8 => ExecutionTreeObject::U8(value as u8),
+            NumericType::U16 => ExecutionTreeObject::U16(value as u16),
+            NumericType::U32 => ExecutionTreeObject::U32(value as u32),
+            NumericType::U64 => ExecutionTreeObject::U64(value as u64),
+            NumericType::USize => ExecutionTreeObject::USize(value as usize),
+        };
+    Ok(result)
+}
+
+fn from_rational(value: BigRational, numeric_type: NumericType) -> ExecutionTreeObject {
+    match numeric_type {
+        NumericType::BigInt => ExecutionTreeObject::BigInt(value.to_integer()),
+        _ => ExecutionTreeObject::Rational(value),
+    }
+}
+
+unsafe fn select<TArg>(function: &str, arg: &TArg, environment: &mut Environment<TArg>, args: &[&ExecutionTreeObject], ordering: Ordering) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count(function, args, 1, None));
+
+    let numbers = try!(evaluate_numbers(arg, environment, args));
+    let mut numeric_type = try!(NumericType::from_object(&numbers[0]));
+    let mut selected = &numbers[0];
+    for number in &numbers[1..] {
+        numeric_type = numeric_type.promote(try!(NumericType::from_object(number)));
+        let comparison =
+            if numeric_type.is_float() {
+                to_float(number).partial_cmp(&to_float(selected)).unwrap_or(Ordering::Equal)
+            } else if numeric_type.is_fixed() {
+                to_integer(number).cmp(&to_integer(selected))
+            } else {
+                to_rational(number).cmp(&to_rational(selected))
+            };
+        if comparison == ordering {
+            selected = number;
+        }
+    }
+
+    convert(function, selected, numeric_type, environment.get_overflow_policy())
+}
+
+fn to_float(object: &ExecutionTreeObject) -> f64 {
+    match object {
+        &ExecutionTreeObject::BigInt(ref value) => value.to_f64().unwrap_or(0.0),
+        &ExecutionTreeObject::F32(value) => value as f64,
+        &ExecutionTreeObject::F64(value) => value,
+        &ExecutionTreeObject::Rational(ref value) => value.to_f64().unwrap_or(0.0),
+        other => to_integer(other) as f64,
+    }
+}
+
+fn to_integer(object: &ExecutionTreeObject) -> i128 {
+    match object {
+        &ExecutionTreeObject::I8(value) => value as i128,
+        &ExecutionTreeObject::I16(value) => value as i128,
+        &ExecutionTreeObject::I32(value) => value as i128,
+        &ExecutionTreeObject::I64(value) => value as i128,
+        &ExecutionTreeObject::ISize(value) => value as i128,
+        &ExecutionTreeObject::U8(value) => value as i128,
+        &ExecutionTreeObject::U16(value) => value as i128,
+        &ExecutionTreeObject::U32(value) => value as i128,
+        &ExecutionTreeObject::U64(value) => value as i128,
+        &ExecutionTreeObject::USize(value) => value as i128,
+        _ => 0,
+    }
+}
+
+fn to_rational(object: &ExecutionTreeObject) -> BigRational {
+    match object {
+        &ExecutionTreeObject::BigInt(ref value) => BigRational::from_integer(value.clone()),
+        &ExecutionTreeObject::Rational(ref value) => value.clone(),
+        other => BigRational::from_integer(BigInt::from(to_integer(other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use error::*;
+    use lisp::Environment;
+    use lisp::OverflowPolicy;
+
+    fn execute(source: &str, policy: OverflowPolicy) -> Result<String> {
+        let mut environment = Environment::<()>::new();
+        builtin::arithmetic::register(environment.get_global_frame());
+        environment.set_overflow_policy(policy);
+        unsafe { environment.parse_and_execute(&(), source) }
+    }
+
+    fn is_overflow(result: Result<String>) -> bool {
+        match result {
+            Err(error) => {
+                match *error.kind() {
+                    ErrorKind::ArithmeticOverflow(_, _) => true,
+                    _ => false,
+                }
+            },
+            Ok(_) => false,
+        }
+    }
+
+    #[test]
+    fn mixed_operands_promote() {
+        assert_eq!(execute("(+ 1i8 1i32)", OverflowPolicy::Checked).unwrap(), "2i32");
+        assert_eq!(execute("(+ 1u8 1u64)", OverflowPolicy::Checked).unwrap(), "2u64");
+        assert_eq!(execute("(+ 1i8 1u8)", OverflowPolicy::Checked).unwrap(), "2i16");
+        assert_eq!(execute("(+ 1i64 1u32)", OverflowPolicy::Checked).unwrap(), "2i64");
+        assert_eq!(execute("(+ 1i8 1u64)", OverflowPolicy::Checked).unwrap(), "2i64");
+        assert_eq!(execute("(+ 1i16 1.5f32)", OverflowPolicy::Checked).unwrap(), "2.5f32");
+        assert_eq!(execute("(+ 1i32 1.5f32)", OverflowPolicy::Checked).unwrap(), "2.5f64");
+    }
+
+    #[test]
+    fn results_outside_the_promoted_type_follow_the_policy() {
+        assert!(is_overflow(execute("(+ 127i8 1i8)", OverflowPolicy::Checked)));
+        assert_eq!(execute("(+ 127i8 1i8)", OverflowPolicy::Promote).unwrap(), "128n");
+        assert_eq!(execute("(+ 127i8 1i8)", OverflowPolicy::Saturating).unwrap(), "127i8");
+        assert_eq!(execute("(+ 127i8 1i8)", OverflowPolicy::Wrapping).unwrap(), "-128i8");
+    }
+
+    #[test]
+    fn products_beyond_i128_follow_the_policy() {
+        let source = "(* 18446744073709551615u64 18446744073709551615u64)";
+        assert!(is_overflow(execute(source, OverflowPolicy::Checked)));
+        assert_eq!(execute(source, OverflowPolicy::Promote).unwrap(), "340282366920938463426481119284349108225n");
+        assert_eq!(execute(source, OverflowPolicy::Saturating).unwrap(), "18446744073709551615u64");
+        assert_eq!(execute(source, OverflowPolicy::Wrapping).unwrap(), "1u64");
+    }
+
+    #[test]
+    fn promoted_results_keep_growing() {
+        let source = "(* 18446744073709551615u64 18446744073709551615u64 18446744073709551615u64)";
+        assert_eq!(execute(source, OverflowPolicy::Promote).unwrap(), "6277101735386680762814942322444851025767571854389858533375n");
+        assert_eq!(execute("(- -170141183460469231731687303715884105728n 1)", OverflowPolicy::Checked).unwrap(), "-170141183460469231731687303715884105729n");
+    }
+
+    #[test]
+    fn rationals_stay_exact() {
+        assert_eq!(execute("(+ 1/3 1/6)", OverflowPolicy::Checked).unwrap(), "1/2");
+        assert_eq!(execute("(* 2/3 3)", OverflowPolicy::Checked).unwrap(), "2/1");
+        assert_eq!(execute("(/ 7n 2n)", OverflowPolicy::Checked).unwrap(), "3n");
+        assert_eq!(execute("(+ 1/2 0.25)", OverflowPolicy::Checked).unwrap(), "0.75f64");
+    }
+
+    #[test]
+    fn signed_and_unsigned_extremes_follow_the_policy() {
+        let source = "(* -9223372036854775808i64 18446744073709551615u64)";
+        assert!(is_overflow(execute(source, OverflowPolicy::Checked)));
+        assert_eq!(execute(source, OverflowPolicy::Promote).unwrap(), "-170141183460469231722463931679029329920n");
+        assert_eq!(execute(source, OverflowPolicy::Saturating).unwrap(), "-9223372036854775808i64");
+        assert_eq!(execute(source, OverflowPolicy::Wrapping).unwrap(), "-9223372036854775808i64");
+    }
+
+    #[test]
+    fn division_by_zero_is_an_error() {
+        for source in &["(/ 1 0)", "(mod 1 0)", "(rem 1u64 0u64)"] {
+            let error = execute(source, OverflowPolicy::Promote).unwrap_err();
+            match *error.kind() {
+                ErrorKind::DivisionByZero(_) => {},
+                ref other => panic!("{}: {}", source, other),
+            }
+        }
+    }
+}
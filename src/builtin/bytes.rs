@@ -0,0 +1,140 @@
+use builtin::util;
+use encoding;
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::Symbol;
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert("base64-decode".to_string(), Symbol::BuiltInFunc(base64_decode::<TArg>));
+    frame.insert("base64-encode".to_string(), Symbol::BuiltInFunc(base64_encode::<TArg>));
+    frame.insert("bytes".to_string(), Symbol::BuiltInFunc(bytes::<TArg>));
+    frame.insert("bytes-concat".to_string(), Symbol::BuiltInFunc(bytes_concat::<TArg>));
+    frame.insert("bytes-length".to_string(), Symbol::BuiltInFunc(bytes_length::<TArg>));
+    frame.insert("bytes-ref".to_string(), Symbol::BuiltInFunc(bytes_ref::<TArg>));
+    frame.insert("bytes-slice".to_string(), Symbol::BuiltInFunc(bytes_slice::<TArg>));
+    frame.insert("hex-decode".to_string(), Symbol::BuiltInFunc(hex_decode::<TArg>));
+    frame.insert("hex-encode".to_string(), Symbol::BuiltInFunc(hex_encode::<TArg>));
+    frame.insert("utf8-decode".to_string(), Symbol::BuiltInFunc(utf8_decode::<TArg>));
+    frame.insert("utf8-encode".to_string(), Symbol::BuiltInFunc(utf8_encode::<TArg>));
+}
+
+pub unsafe fn base64_decode<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("base64-decode", &args, 1, Some(1)));
+
+    let text: String = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    Ok(ExecutionTreeObject::Bytes(try!(encoding::decode_base64(&text))))
+}
+
+pub unsafe fn base64_encode<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("base64-encode", &args, 1, Some(1)));
+
+    let bytes: Vec<u8> = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    Ok(ExecutionTreeObject::String(encoding::encode_base64(&bytes)))
+}
+
+pub unsafe fn bytes<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    let mut result = Vec::with_capacity(args.len());
+    for object in args {
+        let value = try!(evaluate_integer(arg, environment, object));
+        if value < 0 || value > u8::max_value() as i128 {
+            return Err(ErrorKind::ArithmeticOverflow("bytes".to_string(), ExecutionTreeObject::u8_str().to_string()).into());
+        }
+        result.push(value as u8);
+    }
+    Ok(ExecutionTreeObject::Bytes(result))
+}
+
+pub unsafe fn bytes_concat<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    let mut result = Vec::new();
+    for object in args {
+        let bytes: Vec<u8> = try!(From::from(try!(environment.evaluate(arg, object))));
+        result.extend_from_slice(&bytes);
+    }
+    Ok(ExecutionTreeObject::Bytes(result))
+}
+
+pub unsafe fn bytes_length<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("bytes-length", &args, 1, Some(1)));
+
+    let bytes: Vec<u8> = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    Ok(ExecutionTreeObject::USize(bytes.len()))
+}
+
+pub unsafe fn bytes_ref<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("bytes-ref", &args, 2, Some(2)));
+
+    let bytes: Vec<u8> = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    let index = try!(evaluate_integer(arg, environment, args[1]));
+    if index < 0 || index >= bytes.len() as i128 {
+        return Err(ErrorKind::IndexOutOfBounds("bytes-ref".to_string(), index, bytes.len()).into());
+    }
+    Ok(ExecutionTreeObject::U8(bytes[index as usize]))
+}
+
+pub unsafe fn bytes_slice<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("bytes-slice", &args, 2, Some(3)));
+
+    let bytes: Vec<u8> = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    let start = try!(evaluate_integer(arg, environment, args[1]));
+    let end =
+        match args.get(2) {
+            Some(object) => try!(evaluate_integer(arg, environment, object)),
+            None => bytes.len() as i128,
+        };
+    if end < 0 || end > bytes.len() as i128 {
+        return Err(ErrorKind::IndexOutOfBounds("bytes-slice".to_string(), end, bytes.len()).into());
+    }
+    if start < 0 || start > end {
+        return Err(ErrorKind::IndexOutOfBounds("bytes-slice".to_string(), start, end as usize).into());
+    }
+    Ok(ExecutionTreeObject::Bytes(bytes[(start as usize)..(end as usize)].to_vec()))
+}
+
+pub unsafe fn hex_decode<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("hex-decode", &args, 1, Some(1)));
+
+    let text: String = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    Ok(ExecutionTreeObject::Bytes(try!(encoding::decode_hex(&text))))
+}
+
+pub unsafe fn hex_encode<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("hex-encode", &args, 1, Some(1)));
+
+    let bytes: Vec<u8> = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    Ok(ExecutionTreeObject::String(encoding::encode_hex(&bytes)))
+}
+
+pub unsafe fn utf8_decode<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("utf8-decode", &args, 1, Some(1)));
+
+    let bytes: Vec<u8> = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(ExecutionTreeObject::String(text)),
+        Err(error) => Err(ErrorKind::InvalidEncoding(encoding::utf8_str().to_string(), encoding::escape_bytes(error.as_bytes())).into()),
+    }
+}
+
+pub unsafe fn utf8_encode<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("utf8-encode", &args, 1, Some(1)));
+
+    let text: String = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    Ok(ExecutionTreeObject::Bytes(text.into_bytes()))
+}
+
+unsafe fn evaluate_integer<TArg>(arg: &TArg, environment: &mut Environment<TArg>, object: &ExecutionTreeObject) -> Result<i128> {
+    match try!(environment.evaluate(arg, object)) {
+        ExecutionTreeObject::I8(value) => Ok(value as i128),
+        ExecutionTreeObject::I16(value) => Ok(value as i128),
+        ExecutionTreeObject::I32(value) => Ok(value as i128),
+        ExecutionTreeObject::I64(value) => Ok(value as i128),
+        ExecutionTreeObject::ISize(value) => Ok(value as i128),
+        ExecutionTreeObject::U8(value) => Ok(value as i128),
+        ExecutionTreeObject::U16(value) => Ok(value as i128),
+        ExecutionTreeObject::U32(value) => Ok(value as i128),
+        ExecutionTreeObject::U64(value) => Ok(value as i128),
+        ExecutionTreeObject::USize(value) => Ok(value as i128),
+        other => Err(ErrorKind::InvalidExecutionTreeObjectConversion(other.enum_to_string().to_string(), "integer".to_string()).into()),
+    }
+}
@@ -0,0 +1,159 @@
+use builtin::util;
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::Symbol;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::ToPrimitive;
+use std::cmp::Ordering;
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert("=".to_string(), Symbol::BuiltInFunc(numeric_equal::<TArg>));
+    frame.insert("compare".to_string(), Symbol::BuiltInFunc(compare::<TArg>));
+    frame.insert("eq?".to_string(), Symbol::BuiltInFunc(eq::<TArg>));
+    frame.insert("equal?".to_string(), Symbol::BuiltInFunc(equal::<TArg>));
+}
+
+pub unsafe fn compare<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("compare", &args, 2, Some(2)));
+
+    let left = try!(environment.evaluate(arg, args[0]));
+    let right = try!(environment.evaluate(arg, args[1]));
+    let result =
+        match left.cmp(&right) {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        };
+    Ok(ExecutionTreeObject::I32(result))
+}
+
+pub unsafe fn eq<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    evaluate_chain("eq?", arg, environment, args, is_identical)
+}
+
+pub unsafe fn equal<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    evaluate_chain("equal?", arg, environment, args, |left, right| left == right)
+}
+
+pub unsafe fn numeric_equal<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    evaluate_chain("=", arg, environment, args, |left, right| {
+        if let (Some(left), Some(right)) = (to_integer(left), to_integer(right)) {
+            return left == right;
+        }
+        if let (Some(left), Some(right)) = (to_rational(left), to_rational(right)) {
+            return left == right;
+        }
+        match (to_float(left), to_float(right)) {
+            (Some(left), Some(right)) => left == right,
+            _ => left == right,
+        }
+    })
+}
+
+unsafe fn evaluate_chain<TArg, F>(function: &str, arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>, predicate: F) -> Result<ExecutionTreeObject>
+    where F: Fn(&ExecutionTreeObject, &ExecutionTreeObject) -> bool {
+    try!(util::check_argument_count(function, &args, 2, None));
+
+    let mut previous = try!(environment.evaluate(arg, args[0]));
+    let mut result = true;
+    for object in &args[1..] {
+        let current = try!(environment.evaluate(arg, object));
+        if result && !predicate(&previous, &current) {
+            result = false;
+        }
+        previous = current;
+    }
+    Ok(ExecutionTreeObject::Bool(result))
+}
+
+// Nodes and maps are rebuilt on every evaluation, so only the empty ones share an identity. Foreign values
+// are identical when they wrap the same allocation.
+fn is_identical(left: &ExecutionTreeObject, right: &ExecutionTreeObject) -> bool {
+    match (left, right) {
+        (&ExecutionTreeObject::Foreign(ref left), &ExecutionTreeObject::Foreign(ref right)) => left.get_address() == right.get_address(),
+        (&ExecutionTreeObject::Map(ref left), &ExecutionTreeObject::Map(ref right)) => left.is_empty() && right.is_empty(),
+        (&ExecutionTreeObject::Node(ref left), &ExecutionTreeObject::Node(ref right)) => left.get_objects().is_empty() && right.get_objects().is_empty(),
+        (left, right) => left == right,
+    }
+}
+
+fn to_float(object: &ExecutionTreeObject) -> Option<f64> {
+    match object {
+        &ExecutionTreeObject::BigInt(ref value) => value.to_f64(),
+        &ExecutionTreeObject::F32(value) => Some(value as f64),
+        &ExecutionTreeObject::F64(value) => Some(value),
+        &ExecutionTreeObject::Rational(ref value) => value.to_f64(),
+        other => to_integer(other).map(|value| value as f64),
+    }
+}
+
+fn to_integer(object: &ExecutionTreeObject) -> Option<i128> {
+    match object {
+        &ExecutionTreeObject::I8(value) => Some(value as i128),
+        &ExecutionTreeObject::I16(value) => Some(value as i128),
+        &ExecutionTreeObject::I32(value) => Some(value as i128),
+        &ExecutionTreeObject::I64(value) => Some(value as i128),
+        &ExecutionTreeObject::ISize(value) => Some(value as i128),
+        &ExecutionTreeObject::U8(value) => Some(value as i128),
+        &ExecutionTreeObject::U16(value) => Some(value as i128),
+        &ExecutionTreeObject::U32(value) => Some(value as i128),
+        &ExecutionTreeObject::U64(value) => Some(value as i128),
+        &ExecutionTreeObject::USize(value) => Some(value as i128),
+        _ => None,
+    }
+}
+
+fn to_rational(object: &ExecutionTreeObject) -> Option<BigRational> {
+    match object {
+        &ExecutionTreeObject::BigInt(ref value) => Some(BigRational::from_integer(value.clone())),
+        &ExecutionTreeObject::Rational(ref value) => Some(value.clone()),
+        other => to_integer(other).map(|value| BigRational::from_integer(BigInt::from(value))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use lisp::Environment;
+
+    fn execute(source: &str) -> String {
+        let mut environment = Environment::<()>::new();
+        builtin::comparison::register(environment.get_global_frame());
+        builtin::macros::register(environment.get_global_frame());
+        builtin::map::register(environment.get_global_frame());
+        unsafe { environment.parse_and_execute(&(), source) }.unwrap()
+    }
+
+    #[test]
+    fn numeric_equality_ignores_types() {
+        assert_eq!(execute("(= 1i8 1u64 1)"), "true");
+        assert_eq!(execute("(= 1 1.0)"), "true");
+        assert_eq!(execute("(= 1/2 0.5)"), "true");
+        assert_eq!(execute("(= 1 2)"), "false");
+    }
+
+    #[test]
+    fn structural_equality_compares_types_and_contents() {
+        assert_eq!(execute("(equal? 1i8 1u64)"), "false");
+        assert_eq!(execute("(equal? (quote (1 \"a\")) (quote (1 \"a\")))"), "true");
+        assert_eq!(execute("(equal? \"a\" \"a\" \"b\")"), "false");
+    }
+
+    #[test]
+    fn identity_only_holds_for_atoms_and_empty_collections() {
+        assert_eq!(execute("(eq? \"a\" \"a\")"), "true");
+        assert_eq!(execute("(eq? (quote (1)) (quote (1)))"), "false");
+        assert_eq!(execute("(eq? () ())"), "true");
+        assert_eq!(execute("(eq? (hash-map) (hash-map))"), "true");
+    }
+
+    #[test]
+    fn compare_orders_values() {
+        assert_eq!(execute("(compare 1 2)"), "-1i32");
+        assert_eq!(execute("(compare \"b\" \"a\")"), "1i32");
+        assert_eq!(execute("(compare (quote (1 2)) (quote (1 2)))"), "0i32");
+    }
+}
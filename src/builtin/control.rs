@@ -0,0 +1,31 @@
+use builtin::util;
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::Symbol;
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert("begin".to_string(), Symbol::BuiltInFunc(begin::<TArg>));
+    frame.insert("if".to_string(), Symbol::BuiltInFunc(if_then_else::<TArg>));
+}
+
+pub unsafe fn begin<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    util::evaluate_sequence(arg, environment, &args)
+}
+
+pub unsafe fn if_then_else<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("if", &args, 2, Some(3)));
+
+    let condition: bool = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    if condition {
+        environment.record_branch(0);
+        environment.evaluate(arg, args[1])
+    } else {
+        environment.record_branch(1);
+        match args.get(2) {
+            Some(object) => environment.evaluate(arg, object),
+            None => Ok(ExecutionTreeObject::nil()),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use builtin::util;
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::Symbol;
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert("define".to_string(), Symbol::BuiltInFunc(define::<TArg>));
+}
+
+pub unsafe fn define<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("define", &args, 2, Some(2)));
+
+    let name = try!(util::get_symbol("define", args[0])).to_string();
+    let value = try!(environment.evaluate(arg, args[1]));
+    try!(environment.checked_define(name.clone(), Symbol::Object(value)));
+    Ok(ExecutionTreeObject::Symbol(name))
+}
@@ -0,0 +1,199 @@
+use builtin::util;
+use error::*;
+use lisp::Environment;
+use lisp::Exception;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::Symbol;
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert("error-kind".to_string(), Symbol::BuiltInFunc(error_kind::<TArg>));
+    frame.insert("error-message".to_string(), Symbol::BuiltInFunc(error_message::<TArg>));
+    frame.insert("error-tag".to_string(), Symbol::BuiltInFunc(error_tag::<TArg>));
+    frame.insert("error-value".to_string(), Symbol::BuiltInFunc(error_value::<TArg>));
+    frame.insert("rethrow".to_string(), Symbol::BuiltInFunc(rethrow::<TArg>));
+    frame.insert("throw".to_string(), Symbol::BuiltInFunc(throw::<TArg>));
+    frame.insert("try".to_string(), Symbol::BuiltInFunc(try_catch::<TArg>));
+    frame.insert("unwind-protect".to_string(), Symbol::BuiltInFunc(unwind_protect::<TArg>));
+}
+
+pub unsafe fn error_kind<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    let exception = try!(evaluate_exception("error-kind", arg, environment, &args));
+    Ok(ExecutionTreeObject::Symbol(exception.get_kind().to_string()))
+}
+
+pub unsafe fn error_message<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    let exception = try!(evaluate_exception("error-message", arg, environment, &args));
+    Ok(ExecutionTreeObject::String(exception.get_message().to_string()))
+}
+
+pub unsafe fn error_tag<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    let exception = try!(evaluate_exception("error-tag", arg, environment, &args));
+    Ok(ExecutionTreeObject::Symbol(exception.get_tag().to_string()))
+}
+
+pub unsafe fn error_value<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    let exception = try!(evaluate_exception("error-value", arg, environment, &args));
+    Ok(exception.get_value().clone())
+}
+
+pub unsafe fn rethrow<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    let exception = try!(evaluate_exception("rethrow", arg, environment, &args));
+    Err(exception.into_error())
+}
+
+pub unsafe fn throw<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("throw", &args, 1, Some(2)));
+
+    let tag =
+        match args[0] {
+            &ExecutionTreeObject::Symbol(ref symbol) => symbol.clone(),
+            object => {
+                match try!(environment.evaluate(arg, object)) {
+                    ExecutionTreeObject::Exception(exception) => {
+                        return Err(exception.into_error());
+                    },
+                    ExecutionTreeObject::String(string) => string,
+                    ExecutionTreeObject::Symbol(symbol) => symbol,
+                    object => {
+                        return Err(util::invalid_form("throw", &object));
+                    },
+                }
+            },
+        };
+
+    let value =
+        match args.get(1) {
+            Some(object) => try!(environment.evaluate(arg, object)),
+            None => ExecutionTreeObject::nil(),
+        };
+
+    Err(ErrorKind::Thrown(tag, value).into())
+}
+
+pub unsafe fn try_catch<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    let mut body = Vec::new();
+    let mut catch_clauses = Vec::new();
+    let mut finally_clause = None;
+
+    for object in args {
+        match util::get_form_head(object) {
+            Some("catch") => {
+                if finally_clause.is_some() {
+                    return Err(util::invalid_form("try", object));
+                }
+                catch_clauses.push(object);
+            },
+            Some("finally") => {
+                if finally_clause.is_some() {
+                    return Err(util::invalid_form("try", object));
+                }
+                finally_clause = Some(object);
+            },
+            _ => {
+                if !catch_clauses.is_empty() || finally_clause.is_some() {
+                    return Err(util::invalid_form("try", object));
+                }
+                body.push(object);
+            },
+        }
+    }
+
+    let mut result = util::evaluate_sequence(arg, environment, &body);
+
+    if let Err(error) = result {
+        let exception = Exception::from_error(&error);
+        result = Err(error);
+        for catch_clause in catch_clauses {
+            let clause_objects = clause_arguments(catch_clause);
+            if clause_objects.len() < 2 {
+                return Err(util::invalid_form("catch", catch_clause));
+            }
+            let selector = try!(util::get_symbol("catch", clause_objects[0]));
+            let name = try!(util::get_symbol("catch", clause_objects[1]));
+            if exception.is_match(selector) {
+                let mut frame = Frame::new();
+                frame.insert(name.to_string(), Symbol::Object(ExecutionTreeObject::Exception(exception)));
+                result = environment.with_frame(frame, |environment| {
+                    util::evaluate_sequence(arg, environment, &clause_objects[2..])
+                });
+                break;
+            }
+        }
+    }
+
+    if let Some(finally_clause) = finally_clause {
+        let cleanup = clause_arguments(finally_clause);
+        try!(util::evaluate_sequence(arg, environment, &cleanup));
+    }
+
+    result
+}
+
+pub unsafe fn unwind_protect<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("unwind-protect", &args, 1, None));
+
+    let result = environment.evaluate(arg, args[0]);
+    try!(util::evaluate_sequence(arg, environment, &args[1..]));
+    result
+}
+
+fn clause_arguments(clause: &ExecutionTreeObject) -> Vec<&ExecutionTreeObject> {
+    match clause {
+        &ExecutionTreeObject::Node(ref node) => node.get_objects().iter().skip(1).collect(),
+        _ => Vec::new(),
+    }
+}
+
+unsafe fn evaluate_exception<TArg>(function: &str, arg: &TArg, environment: &mut Environment<TArg>, args: &[&ExecutionTreeObject]) -> Result<Exception> {
+    try!(util::check_argument_count(function, args, 1, Some(1)));
+    let object = try!(environment.evaluate(arg, args[0]));
+    From::from(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use error::*;
+    use lisp::Environment;
+
+    fn new_environment() -> Environment<()> {
+        let mut environment = Environment::new();
+        builtin::control::register(environment.get_global_frame());
+        builtin::definition::register(environment.get_global_frame());
+        builtin::exception::register(environment.get_global_frame());
+        environment
+    }
+
+    #[test]
+    fn catch_binds_thrown_values() {
+        let mut environment = new_environment();
+        let result = unsafe { environment.parse_and_execute(&(), "(try (throw oops 5) (catch oops error (error-value error)))") };
+        assert_eq!(result.unwrap(), "5i32");
+    }
+
+    #[test]
+    fn resource_limits_cannot_be_caught() {
+        let mut environment = new_environment();
+        environment.set_max_call_depth(Some(8));
+        let source = format!("(try {}1{} (catch _ error (error-kind error)))", "(".repeat(16), ")".repeat(16));
+        let error = unsafe { environment.parse_and_execute(&(), &source) }.unwrap_err();
+        match error.kind() {
+            &ErrorKind::StackDepthExceeded(..) => { },
+            kind => panic!("unexpected error kind, {}", kind.enum_to_string()),
+        }
+    }
+
+    #[test]
+    fn rethrow_preserves_the_original_error() {
+        let mut environment = new_environment();
+        let error = unsafe { environment.parse_and_execute(&(), "(try (define x) (catch _ error (rethrow error)))") }.unwrap_err();
+        match error.kind() {
+            &ErrorKind::InvalidArgumentCount(ref function, _, 1) => assert_eq!(function, "define"),
+            kind => panic!("unexpected error kind, {}", kind.enum_to_string()),
+        }
+
+        let result = unsafe { environment.parse_and_execute(&(), "(try (try (define x) (catch _ error (rethrow error))) (catch _ error (error-kind error)))") };
+        assert_eq!(result.unwrap(), "InvalidArgumentCount");
+    }
+}
@@ -0,0 +1,25 @@
+use builtin::util;
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::Symbol;
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert("help".to_string(), Symbol::BuiltInFunc(help::<TArg>));
+}
+
+pub unsafe fn help<TArg>(_arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("help", &args, 1, Some(1)));
+
+    let name = try!(util::get_symbol("help", args[0]));
+    let doc =
+        match environment.get_doc(name) {
+            Some(doc) => doc.clone(),
+            None => {
+                try!(environment.get_symbol(name));
+                String::new()
+            },
+        };
+    Ok(ExecutionTreeObject::String(doc))
+}
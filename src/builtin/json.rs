@@ -0,0 +1,77 @@
+use builtin::util;
+use error::*;
+use json;
+use json::JsonFloatType;
+use json::JsonIntegerType;
+use json::JsonOptions;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::Symbol;
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert("json-parse".to_string(), Symbol::BuiltInFunc(json_parse::<TArg>));
+    frame.insert("json-stringify".to_string(), Symbol::BuiltInFunc(json_stringify::<TArg>));
+}
+
+pub unsafe fn json_parse<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("json-parse", &args, 1, None));
+
+    let text: String = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    let options = try!(get_options("json-parse", arg, environment, &args[1..]));
+    json::parse(&text, &options)
+}
+
+pub unsafe fn json_stringify<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("json-stringify", &args, 1, None));
+
+    let object = try!(environment.evaluate(arg, args[0]));
+    let options = try!(get_options("json-stringify", arg, environment, &args[1..]));
+    Ok(ExecutionTreeObject::String(try!(json::stringify(&object, &options))))
+}
+
+// Options follow the value as keyword pairs: ':integer' and ':float' take an unevaluated type name,
+// while ':preserve-suffixes' takes an expression evaluating to a bool.
+unsafe fn get_options<TArg>(function: &str, arg: &TArg, environment: &mut Environment<TArg>, args: &[&ExecutionTreeObject]) -> Result<JsonOptions> {
+    if args.len() % 2 != 0 {
+        return Err(util::invalid_form(function, args[args.len() - 1]));
+    }
+
+    let mut result = JsonOptions::new();
+    for pair in args.chunks(2) {
+        match try!(util::get_symbol(function, pair[0])) {
+            ":float" => {
+                let float_type =
+                    match try!(util::get_symbol(function, pair[1])) {
+                        "f32" => JsonFloatType::F32,
+                        "f64" => JsonFloatType::F64,
+                        "rational" => JsonFloatType::Rational,
+                        _ => {
+                            return Err(util::invalid_form(function, pair[1]));
+                        },
+                    };
+                result.set_float_type(float_type);
+            },
+            ":integer" => {
+                let integer_type =
+                    match try!(util::get_symbol(function, pair[1])) {
+                        "bigint" => JsonIntegerType::BigInt,
+                        "i32" => JsonIntegerType::I32,
+                        "i64" => JsonIntegerType::I64,
+                        _ => {
+                            return Err(util::invalid_form(function, pair[1]));
+                        },
+                    };
+                result.set_integer_type(integer_type);
+            },
+            ":preserve-suffixes" => {
+                let preserve_suffixes: bool = try!(From::from(try!(environment.evaluate(arg, pair[1]))));
+                result.set_preserve_suffixes(preserve_suffixes);
+            },
+            _ => {
+                return Err(util::invalid_form(function, pair[0]));
+            },
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,98 @@
+use builtin::util;
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeNode;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::MacroDefinition;
+use lisp::Symbol;
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert("defmacro".to_string(), Symbol::BuiltInFunc(defmacro::<TArg>));
+    frame.insert("gensym".to_string(), Symbol::BuiltInFunc(gensym::<TArg>));
+    frame.insert("macroexpand".to_string(), Symbol::BuiltInFunc(macroexpand::<TArg>));
+    frame.insert("macroexpand-1".to_string(), Symbol::BuiltInFunc(macroexpand_1::<TArg>));
+    frame.insert("quasiquote".to_string(), Symbol::BuiltInFunc(quasiquote::<TArg>));
+    frame.insert("quote".to_string(), Symbol::BuiltInFunc(quote::<TArg>));
+}
+
+pub unsafe fn defmacro<TArg>(_: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("defmacro", &args, 2, None));
+
+    let name = try!(util::get_symbol("defmacro", args[0])).to_string();
+    let body = args[2..].iter().map(|object| (*object).clone()).collect();
+    let definition = try!(MacroDefinition::parse(name.clone(), args[1], body));
+
+    try!(environment.checked_define(name.clone(), Symbol::Macro(definition)));
+    Ok(ExecutionTreeObject::Symbol(name))
+}
+
+pub unsafe fn gensym<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("gensym", &args, 0, Some(1)));
+
+    let prefix =
+        match args.first() {
+            Some(object) => try!(From::from(try!(environment.evaluate(arg, object)))),
+            None => "G".to_string(),
+        };
+    Ok(ExecutionTreeObject::Symbol(environment.gensym(&prefix)))
+}
+
+pub unsafe fn macroexpand<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("macroexpand", &args, 1, Some(1)));
+    environment.macroexpand(arg, args[0])
+}
+
+pub unsafe fn macroexpand_1<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("macroexpand-1", &args, 1, Some(1)));
+    match try!(environment.macroexpand_1(arg, args[0])) {
+        Some(expansion) => Ok(expansion),
+        None => Ok(args[0].clone()),
+    }
+}
+
+pub unsafe fn quasiquote<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("quasiquote", &args, 1, Some(1)));
+    expand_quasiquote(arg, environment, args[0])
+}
+
+pub unsafe fn quote<TArg>(_: &TArg, _: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("quote", &args, 1, Some(1)));
+    Ok(args[0].clone())
+}
+
+unsafe fn expand_quasiquote<TArg>(arg: &TArg, environment: &mut Environment<TArg>, template: &ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+    let objects =
+        match template {
+            &ExecutionTreeObject::Node(ref node) => node.get_objects(),
+            other => {
+                return Ok(other.clone());
+            },
+        };
+
+    if util::get_form_head(template) == Some("unquote") {
+        let unquoted = try!(get_unquoted("unquote", template));
+        return environment.evaluate(arg, unquoted);
+    }
+
+    let mut result = Vec::with_capacity(objects.len());
+    for object in objects {
+        if util::get_form_head(object) == Some("unquote-splicing") {
+            let unquoted = try!(get_unquoted("unquote-splicing", object));
+            let spliced: ExecutionTreeNode = try!(From::from(try!(environment.evaluate(arg, unquoted))));
+            result.extend(spliced.into_objects());
+        } else {
+            result.push(try!(expand_quasiquote(arg, environment, object)));
+        }
+    }
+    let node = ExecutionTreeNode::new(result);
+    environment.record_allocation(node.get_heap_size());
+    Ok(ExecutionTreeObject::Node(node))
+}
+
+fn get_unquoted<'a>(function: &str, form: &'a ExecutionTreeObject) -> Result<&'a ExecutionTreeObject> {
+    match form {
+        &ExecutionTreeObject::Node(ref node) if node.get_objects().len() == 2 => Ok(&node.get_objects()[1]),
+        form => Err(util::invalid_form(function, form)),
+    }
+}
@@ -0,0 +1,168 @@
+use builtin::util;
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeMap;
+use lisp::ExecutionTreeNode;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::Symbol;
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert("assoc".to_string(), Symbol::BuiltInFunc(assoc::<TArg>));
+    frame.insert("contains?".to_string(), Symbol::BuiltInFunc(contains::<TArg>));
+    frame.insert("dissoc".to_string(), Symbol::BuiltInFunc(dissoc::<TArg>));
+    frame.insert("get".to_string(), Symbol::BuiltInFunc(get::<TArg>));
+    frame.insert("hash-map".to_string(), Symbol::BuiltInFunc(hash_map::<TArg>));
+    frame.insert("keys".to_string(), Symbol::BuiltInFunc(keys::<TArg>));
+    frame.insert("merge".to_string(), Symbol::BuiltInFunc(merge::<TArg>));
+    frame.insert("vals".to_string(), Symbol::BuiltInFunc(vals::<TArg>));
+}
+
+pub unsafe fn assoc<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("assoc", &args, 3, None));
+    try!(check_pairs("assoc", &args, 1));
+
+    let mut map = try!(evaluate_map(arg, environment, args[0]));
+    for pair in args[1..].chunks(2) {
+        let key = try!(environment.evaluate(arg, pair[0]));
+        let value = try!(environment.evaluate(arg, pair[1]));
+        map.insert(key, value);
+    }
+    environment.record_allocation(map.get_heap_size());
+    Ok(ExecutionTreeObject::Map(map))
+}
+
+pub unsafe fn contains<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("contains?", &args, 2, Some(2)));
+
+    let map = try!(evaluate_map(arg, environment, args[0]));
+    let key = try!(environment.evaluate(arg, args[1]));
+    Ok(ExecutionTreeObject::Bool(map.contains_key(&key)))
+}
+
+pub unsafe fn dissoc<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("dissoc", &args, 1, None));
+
+    let mut map = try!(evaluate_map(arg, environment, args[0]));
+    for object in &args[1..] {
+        let key = try!(environment.evaluate(arg, object));
+        map.remove(&key);
+    }
+    Ok(ExecutionTreeObject::Map(map))
+}
+
+pub unsafe fn get<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("get", &args, 2, Some(3)));
+
+    let map = try!(evaluate_map(arg, environment, args[0]));
+    let key = try!(environment.evaluate(arg, args[1]));
+    match map.get(&key) {
+        Some(value) => Ok(value.clone()),
+        None => {
+            match args.get(2) {
+                Some(object) => environment.evaluate(arg, object),
+                None => Ok(ExecutionTreeObject::nil()),
+            }
+        },
+    }
+}
+
+pub unsafe fn hash_map<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(check_pairs("hash-map", &args, 0));
+
+    let mut map = ExecutionTreeMap::new();
+    for pair in args.chunks(2) {
+        let key = try!(environment.evaluate(arg, pair[0]));
+        let value = try!(environment.evaluate(arg, pair[1]));
+        map.insert(key, value);
+    }
+    environment.record_allocation(map.get_heap_size());
+    Ok(ExecutionTreeObject::Map(map))
+}
+
+pub unsafe fn keys<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("keys", &args, 1, Some(1)));
+
+    let map = try!(evaluate_map(arg, environment, args[0]));
+    let node = ExecutionTreeNode::new(map.keys());
+    environment.record_allocation(node.get_heap_size());
+    Ok(ExecutionTreeObject::Node(node))
+}
+
+pub unsafe fn merge<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    let mut result = ExecutionTreeMap::new();
+    for object in args {
+        let map = try!(evaluate_map(arg, environment, object));
+        for &(ref key, ref value) in map.iter() {
+            result.insert(key.clone(), value.clone());
+        }
+    }
+    environment.record_allocation(result.get_heap_size());
+    Ok(ExecutionTreeObject::Map(result))
+}
+
+pub unsafe fn vals<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("vals", &args, 1, Some(1)));
+
+    let map = try!(evaluate_map(arg, environment, args[0]));
+    let node = ExecutionTreeNode::new(map.values());
+    environment.record_allocation(node.get_heap_size());
+    Ok(ExecutionTreeObject::Node(node))
+}
+
+fn check_pairs(function: &str, args: &[&ExecutionTreeObject], offset: usize) -> Result<()> {
+    if (args.len() - offset) % 2 == 0 {
+        return Ok(());
+    }
+
+    let expected =
+        if offset % 2 == 0 {
+            "an even number of"
+        } else {
+            "an odd number of"
+        };
+    Err(ErrorKind::InvalidArgumentCount(function.to_string(), expected.to_string(), args.len()).into())
+}
+
+unsafe fn evaluate_map<TArg>(arg: &TArg, environment: &mut Environment<TArg>, object: &ExecutionTreeObject) -> Result<ExecutionTreeMap> {
+    match try!(environment.evaluate(arg, object)) {
+        ExecutionTreeObject::Node(ref node) if node.get_objects().is_empty() => Ok(ExecutionTreeMap::new()),
+        object => From::from(object),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use lisp::Environment;
+
+    fn execute(source: &str) -> String {
+        let mut environment = Environment::<()>::new();
+        builtin::map::register(environment.get_global_frame());
+        builtin::comparison::register(environment.get_global_frame());
+        builtin::macros::register(environment.get_global_frame());
+        unsafe { environment.parse_and_execute(&(), source) }.unwrap()
+    }
+
+    #[test]
+    fn maps_keep_insertion_order() {
+        assert_eq!(execute("(hash-map \"b\" 1 \"a\" 2)"), "{\"b\" 1i32 \"a\" 2i32}");
+        assert_eq!(execute("(keys (assoc (hash-map \"b\" 1) \"a\" 2 \"b\" 3))"), "(\"b\" \"a\")");
+        assert_eq!(execute("(vals (dissoc (hash-map \"a\" 1 \"b\" 2 \"c\" 3) \"b\"))"), "(1i32 3i32)");
+    }
+
+    #[test]
+    fn lookups_use_structural_keys() {
+        assert_eq!(execute("(get (hash-map (quote (1 2)) \"list\") (quote (1 2)))"), "\"list\"");
+        assert_eq!(execute("(get (hash-map 1 2) 3 \"default\")"), "\"default\"");
+        assert_eq!(execute("(contains? (hash-map 1i64 2) 1i32)"), "false");
+        assert_eq!(execute("(merge (hash-map 1 2) (hash-map 1 3 4 5))"), "{1i32 3i32 4i32 5i32}");
+    }
+
+    #[test]
+    fn maps_compare_without_regard_to_order() {
+        assert_eq!(execute("(equal? (hash-map 1 2 3 4) (hash-map 3 4 1 2))"), "true");
+        assert_eq!(execute("(equal? (hash-map 1 2) (hash-map 1 3))"), "false");
+        assert_eq!(execute("(get (hash-map (hash-map 1 2 3 4) \"nested\") (hash-map 3 4 1 2))"), "\"nested\"");
+    }
+}
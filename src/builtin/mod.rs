@@ -0,0 +1,25 @@
+pub mod arithmetic;
+
+pub mod bytes;
+
+pub mod comparison;
+
+pub mod control;
+
+pub mod definition;
+
+pub mod exception;
+
+pub mod help;
+
+pub mod json;
+
+pub mod macros;
+
+pub mod map;
+
+pub mod module;
+
+pub mod object;
+
+mod util;
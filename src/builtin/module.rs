@@ -0,0 +1,40 @@
+use builtin::util;
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::Symbol;
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert("export".to_string(), Symbol::BuiltInFunc(export::<TArg>));
+    frame.insert("import".to_string(), Symbol::BuiltInFunc(import::<TArg>));
+    frame.insert("require".to_string(), Symbol::BuiltInFunc(require::<TArg>));
+}
+
+pub unsafe fn export<TArg>(_: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    for object in args {
+        let symbol = try!(util::get_symbol("export", object));
+        try!(environment.export(symbol.to_string()));
+    }
+    Ok(ExecutionTreeObject::nil())
+}
+
+pub unsafe fn import<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("import", &args, 1, Some(1)));
+
+    let path: String = try!(From::from(try!(environment.evaluate(arg, args[0]))));
+    let alias = try!(environment.import_module(arg, &path));
+    Ok(ExecutionTreeObject::Symbol(alias))
+}
+
+pub unsafe fn require<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count("require", &args, 1, Some(1)));
+
+    let path =
+        match args[0] {
+            &ExecutionTreeObject::Symbol(ref symbol) => symbol.clone(),
+            object => try!(From::from(try!(environment.evaluate(arg, object)))),
+        };
+    let alias = try!(environment.load_module(arg, &path));
+    Ok(ExecutionTreeObject::Symbol(alias))
+}
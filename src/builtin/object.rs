@@ -0,0 +1,22 @@
+use builtin::util;
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::Symbol;
+
+pub fn register<TArg>(frame: &mut Frame<TArg>) {
+    frame.insert(".".to_string(), Symbol::BuiltInFunc(dot::<TArg>));
+}
+
+pub unsafe fn dot<TArg>(arg: &TArg, environment: &mut Environment<TArg>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+    try!(util::check_argument_count(".", &args, 2, None));
+
+    let object = try!(environment.evaluate(arg, args[0]));
+    let name = try!(util::get_symbol(".", args[1]));
+    if args.len() == 2 {
+        environment.get_property(&object, name)
+    } else {
+        environment.call_method(arg, &object, name, args[2..].to_vec())
+    }
+}
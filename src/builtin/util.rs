@@ -0,0 +1,59 @@
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+
+pub fn check_argument_count(function: &str, args: &[&ExecutionTreeObject], min: usize, max: Option<usize>) -> Result<()> {
+    let actual = args.len();
+    let is_valid =
+        match max {
+            Some(max) => actual >= min && actual <= max,
+            None => actual >= min,
+        };
+    if is_valid {
+        Ok(())
+    } else {
+        let expected =
+            match max {
+                Some(max) if max == min => min.to_string(),
+                Some(max) => format!("{} to {}", min, max),
+                None => format!("at least {}", min),
+            };
+        Err(ErrorKind::InvalidArgumentCount(function.to_string(), expected, actual).into())
+    }
+}
+
+pub unsafe fn evaluate_sequence<TArg>(arg: &TArg, environment: &mut Environment<TArg>, objects: &[&ExecutionTreeObject]) -> Result<ExecutionTreeObject> {
+    let mut result = ExecutionTreeObject::nil();
+    for object in objects {
+        result = try!(environment.evaluate(arg, object));
+    }
+    Ok(result)
+}
+
+pub fn get_form_head(object: &ExecutionTreeObject) -> Option<&str> {
+    match object {
+        &ExecutionTreeObject::Node(ref node) => {
+            match node.get_objects().first() {
+                Some(&ExecutionTreeObject::Symbol(ref symbol)) => Some(symbol),
+                _ => None,
+            }
+        },
+        _ => None,
+    }
+}
+
+pub fn get_symbol<'a>(function: &str, object: &'a ExecutionTreeObject) -> Result<&'a str> {
+    match object {
+        &ExecutionTreeObject::Symbol(ref symbol) => Ok(symbol),
+        object => Err(invalid_form(function, object)),
+    }
+}
+
+pub fn invalid_form(function: &str, object: &ExecutionTreeObject) -> Error {
+    let form =
+        match object.to_string() {
+            Ok(ok) => ok,
+            Err(_) => object.enum_to_string().to_string(),
+        };
+    ErrorKind::InvalidForm(function.to_string(), form).into()
+}
@@ -0,0 +1,85 @@
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DataLiteralType {
+    BigInt,
+    Bool,
+    Bytes,
+    Char,
+    Float,
+    Integer,
+    Keyword,
+    Rational,
+    String,
+    Symbol,
+}
+
+impl DataLiteralType {
+    pub fn all() -> Vec<DataLiteralType> {
+        vec![
+            DataLiteralType::BigInt,
+            DataLiteralType::Bool,
+            DataLiteralType::Bytes,
+            DataLiteralType::Char,
+            DataLiteralType::Float,
+            DataLiteralType::Integer,
+            DataLiteralType::Keyword,
+            DataLiteralType::Rational,
+            DataLiteralType::String,
+            DataLiteralType::Symbol,
+        ]
+    }
+
+    pub fn big_int_str() -> &'static str {
+        "DataLiteralType::BigInt"
+    }
+
+    pub fn bool_str() -> &'static str {
+        "DataLiteralType::Bool"
+    }
+
+    pub fn bytes_str() -> &'static str {
+        "DataLiteralType::Bytes"
+    }
+
+    pub fn char_str() -> &'static str {
+        "DataLiteralType::Char"
+    }
+
+    pub fn float_str() -> &'static str {
+        "DataLiteralType::Float"
+    }
+
+    pub fn integer_str() -> &'static str {
+        "DataLiteralType::Integer"
+    }
+
+    pub fn keyword_str() -> &'static str {
+        "DataLiteralType::Keyword"
+    }
+
+    pub fn rational_str() -> &'static str {
+        "DataLiteralType::Rational"
+    }
+
+    pub fn string_str() -> &'static str {
+        "DataLiteralType::String"
+    }
+
+    pub fn symbol_str() -> &'static str {
+        "DataLiteralType::Symbol"
+    }
+
+    pub fn enum_to_string(&self) -> &'static str {
+        match self {
+            &DataLiteralType::BigInt => Self::big_int_str(),
+            &DataLiteralType::Bool => Self::bool_str(),
+            &DataLiteralType::Bytes => Self::bytes_str(),
+            &DataLiteralType::Char => Self::char_str(),
+            &DataLiteralType::Float => Self::float_str(),
+            &DataLiteralType::Integer => Self::integer_str(),
+            &DataLiteralType::Keyword => Self::keyword_str(),
+            &DataLiteralType::Rational => Self::rational_str(),
+            &DataLiteralType::String => Self::string_str(),
+            &DataLiteralType::Symbol => Self::symbol_str(),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use data::DataLiteralType;
+
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+// Every literal type is allowed by default. A list whose head is one of the code forms, such as
+// '(quote x)' or '(define x 1)', is rejected, since it only has meaning when evaluated. Lists nested
+// deeper than the maximum depth are rejected before they are parsed any further.
+#[derive(Clone, Debug)]
+pub struct DataOptions {
+    allowed_types: Vec<DataLiteralType>,
+    code_forms: Vec<String>,
+    max_depth: usize,
+}
+
+impl DataOptions {
+    pub fn new() -> Self {
+        let code_forms =
+            vec!["begin", "define", "defmacro", "export", "if", "import", "quasiquote", "quote", "require", "throw", "try", "unquote", "unquote-splicing", "unwind-protect"];
+        DataOptions {
+            allowed_types: DataLiteralType::all(),
+            code_forms: code_forms.into_iter().map(|code_form| code_form.to_string()).collect(),
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    pub fn allow(&mut self, literal_type: DataLiteralType) {
+        if !self.is_allowed(literal_type) {
+            self.allowed_types.push(literal_type);
+        }
+    }
+
+    pub fn disallow(&mut self, literal_type: DataLiteralType) {
+        self.allowed_types.retain(|allowed_type| *allowed_type != literal_type);
+    }
+
+    pub fn get_allowed_types(&self) -> &Vec<DataLiteralType> {
+        &self.allowed_types
+    }
+
+    pub fn get_code_forms(&self) -> &Vec<String> {
+        &self.code_forms
+    }
+
+    pub fn get_max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn is_allowed(&self, literal_type: DataLiteralType) -> bool {
+        self.allowed_types.contains(&literal_type)
+    }
+
+    pub fn is_code_form(&self, symbol: &str) -> bool {
+        self.code_forms.iter().any(|code_form| code_form == symbol)
+    }
+
+    pub fn set_allowed_types(&mut self, allowed_types: Vec<DataLiteralType>) {
+        self.allowed_types = allowed_types;
+    }
+
+    pub fn set_code_forms(&mut self, code_forms: Vec<String>) {
+        self.code_forms = code_forms;
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+}
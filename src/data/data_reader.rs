@@ -0,0 +1,166 @@
+use data::DataLiteralType;
+use data::DataOptions;
+use data::DataSpans;
+use error::*;
+use lisp::AbstractSyntaxTree;
+use lisp::AbstractSyntaxTreeNode;
+use lisp::AbstractSyntaxTreeObject;
+use lisp::ExecutionTree;
+use lisp::ExecutionTreeNode;
+use lisp::ExecutionTreeObject;
+use lisp::Span;
+
+// Reads a single value from the text without an environment. The text is wrapped in parentheses
+// before it is parsed, so offsets in the syntax tree are one past their position in the text.
+pub struct DataReader<'a> {
+    line_starts: Vec<usize>,
+    options: &'a DataOptions,
+    text: &'a str,
+}
+
+impl <'a> DataReader<'a> {
+    pub fn new(text: &'a str, options: &'a DataOptions) -> Self {
+        DataReader {
+            line_starts: Span::line_starts(text),
+            options: options,
+            text: text,
+        }
+    }
+
+    pub fn read(&self) -> Result<ExecutionTreeObject> {
+        let (object, _) = try!(self.read_with_spans());
+        Ok(object)
+    }
+
+    pub fn read_with_spans(&self) -> Result<(ExecutionTreeObject, DataSpans)> {
+        let wrapped_text = format!("({})", self.text);
+        let root =
+            match AbstractSyntaxTree::parse_program_text_with_max_depth(&wrapped_text, self.options.get_max_depth()) {
+                Ok(root) => root,
+                Err(error) => {
+                    return Err(self.convert_syntax_error(error));
+                },
+            };
+        if root.get_end() != wrapped_text.len() {
+            let index = root.get_end() - 2;
+            return Err(self.error(&self.get_span(index, index + 1), "found an unmatched ')'".to_string()));
+        }
+
+        let mut spans = DataSpans::new();
+        let mut objects = try!(self.visit_node(&wrapped_text, &root, &mut Vec::new(), &mut spans)).into_objects();
+        if objects.len() != 1 {
+            let span = self.get_span(0, self.text.len());
+            return Err(self.error(&span, format!("expected exactly one value, but found, '{}'", objects.len())));
+        }
+        Ok((objects.remove(0), spans))
+    }
+
+    // Syntax errors refer to the wrapped text, so they are restated in terms of the original text.
+    fn convert_syntax_error(&self, error: Error) -> Error {
+        let end_span = self.get_span(self.text.len(), self.text.len());
+        match error.0 {
+            ErrorKind::BeginningStringInWord(character, index) => {
+                self.error(&self.get_span(index - 1, index), format!("the character, '{}', cannot begin a string inside a word", character))
+            },
+            ErrorKind::InvalidPreviousChar(character, index, _) => {
+                self.error(&self.get_span(index - 1, index), format!("the character, '{}', cannot follow the previous character", character))
+            },
+            ErrorKind::NestingDepthExceeded(max_depth, index) => {
+                self.error(&self.get_span(index - 1, index), format!("the list is nested deeper than the maximum depth, '{}'", max_depth))
+            },
+            ErrorKind::NoClosingDoubleQuote(_) => self.error(&end_span, "expected a closing '\"'".to_string()),
+            ErrorKind::NoClosingParenthesis(_) => self.error(&end_span, "expected a closing ')'".to_string()),
+            ErrorKind::NoClosingSingleQuote(_) => self.error(&end_span, "expected a closing '\''".to_string()),
+            _ => error,
+        }
+    }
+
+    fn error(&self, span: &Span, message: String) -> Error {
+        ErrorKind::InvalidData(span.to_string(), message).into()
+    }
+
+    fn get_literal_type(object: &ExecutionTreeObject) -> Option<DataLiteralType> {
+        match object {
+            &ExecutionTreeObject::BigInt(_) => Some(DataLiteralType::BigInt),
+            &ExecutionTreeObject::Bool(_) => Some(DataLiteralType::Bool),
+            &ExecutionTreeObject::Bytes(_) => Some(DataLiteralType::Bytes),
+            &ExecutionTreeObject::Char(_) => Some(DataLiteralType::Char),
+            &ExecutionTreeObject::F32(_) | &ExecutionTreeObject::F64(_) => Some(DataLiteralType::Float),
+            &ExecutionTreeObject::I8(_) | &ExecutionTreeObject::I16(_) | &ExecutionTreeObject::I32(_) |
+            &ExecutionTreeObject::I64(_) | &ExecutionTreeObject::ISize(_) | &ExecutionTreeObject::U8(_) |
+            &ExecutionTreeObject::U16(_) | &ExecutionTreeObject::U32(_) | &ExecutionTreeObject::U64(_) |
+            &ExecutionTreeObject::USize(_) => Some(DataLiteralType::Integer),
+            &ExecutionTreeObject::Rational(_) => Some(DataLiteralType::Rational),
+            &ExecutionTreeObject::String(_) => Some(DataLiteralType::String),
+            &ExecutionTreeObject::Symbol(ref symbol) => {
+                if symbol.starts_with(':') && symbol.len() > 1 {
+                    Some(DataLiteralType::Keyword)
+                } else {
+                    Some(DataLiteralType::Symbol)
+                }
+            },
+            _ => None,
+        }
+    }
+
+    fn get_span(&self, start: usize, end: usize) -> Span {
+        Span::from_offsets(start, end, &self.line_starts)
+    }
+
+    // Tokens are slices of the wrapped text, so their offsets come from their addresses.
+    fn get_token_span(&self, wrapped_text: &str, token: &str) -> Span {
+        let start = token.as_ptr() as usize - wrapped_text.as_ptr() as usize - 1;
+        self.get_span(start, start + token.len())
+    }
+
+    fn visit_literal(&self, token: &str, span: &Span) -> Result<ExecutionTreeObject> {
+        let object =
+            match ExecutionTree::parse_atom(token) {
+                Ok(object) => object,
+                Err(_) => {
+                    return Err(self.error(span, format!("the literal, '{}', cannot be read", token)));
+                },
+            };
+        match Self::get_literal_type(&object) {
+            Some(literal_type) if self.options.is_allowed(literal_type) => Ok(object),
+            Some(literal_type) => Err(self.error(span, format!("the literal, '{}', of type, '{}', is not allowed", token, literal_type.enum_to_string()))),
+            None => Err(self.error(span, format!("the literal, '{}', is not a data literal", token))),
+        }
+    }
+
+    // The indices start at the wrapping root, whose only item is the value being read, so that first
+    // index is left out of the recorded keys.
+    fn visit_node(&self, wrapped_text: &str, node: &AbstractSyntaxTreeNode, indices: &mut Vec<usize>, spans: &mut DataSpans) -> Result<ExecutionTreeNode> {
+        // The wrapping parentheses have no position in the text, so the root is given the span of the whole text.
+        let span =
+            if node.get_start() == 0 {
+                self.get_span(0, self.text.len())
+            } else {
+                self.get_span(node.get_start() - 1, node.get_end() - 1)
+            };
+
+        if let Some(&AbstractSyntaxTreeObject::String(head)) = node.get_objects().first() {
+            if node.get_start() != 0 && self.options.is_code_form(head) {
+                return Err(self.error(&span, format!("the form, '{}', is code and is not allowed in data", head)));
+            }
+        }
+
+        let mut objects = Vec::with_capacity(node.get_objects().len());
+        for (index, object) in node.get_objects().iter().enumerate() {
+            indices.push(index);
+            match *object {
+                AbstractSyntaxTreeObject::Node(ref inner_node) => {
+                    let inner_node = try!(self.visit_node(wrapped_text, inner_node, indices, spans));
+                    objects.push(ExecutionTreeObject::Node(inner_node));
+                },
+                AbstractSyntaxTreeObject::String(token) => {
+                    let token_span = self.get_token_span(wrapped_text, token);
+                    objects.push(try!(self.visit_literal(token, &token_span)));
+                    spans.insert(indices[1..].to_vec(), token_span);
+                },
+            }
+            indices.pop();
+        }
+        Ok(ExecutionTreeNode::with_span(objects, span))
+    }
+}
@@ -0,0 +1,25 @@
+use lisp::Span;
+use std::collections::BTreeMap;
+
+// Atoms carry no span of their own, so the reader records where each one was found, keyed by the
+// indices leading to it from the value that was read. Lists keep their spans on the node instead.
+#[derive(Clone, Debug)]
+pub struct DataSpans {
+    spans: BTreeMap<Vec<usize>, Span>,
+}
+
+impl DataSpans {
+    pub fn new() -> Self {
+        DataSpans {
+            spans: BTreeMap::new(),
+        }
+    }
+
+    pub fn get_span(&self, indices: &[usize]) -> Option<&Span> {
+        self.spans.get(indices)
+    }
+
+    pub fn insert(&mut self, indices: Vec<usize>, span: Span) {
+        self.spans.insert(indices, span);
+    }
+}
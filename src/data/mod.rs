@@ -0,0 +1,78 @@
+mod data_literal_type;
+
+mod data_options;
+
+mod data_reader;
+
+mod data_spans;
+
+pub use data::data_literal_type::DataLiteralType;
+
+pub use data::data_options::DEFAULT_MAX_DEPTH;
+
+pub use data::data_options::DataOptions;
+
+pub use data::data_reader::DataReader;
+
+pub use data::data_spans::DataSpans;
+
+use error::*;
+use lisp::ExecutionTreeObject;
+
+pub fn read(text: &str, options: &DataOptions) -> Result<ExecutionTreeObject> {
+    DataReader::new(text, options).read()
+}
+
+pub fn read_with_spans(text: &str, options: &DataOptions) -> Result<(ExecutionTreeObject, DataSpans)> {
+    DataReader::new(text, options).read_with_spans()
+}
+
+#[cfg(test)]
+mod tests {
+    use data;
+    use data::DataLiteralType;
+    use data::DataOptions;
+    use error::*;
+    use lisp::ExecutionTreeObject;
+
+    fn get_location(result: Result<ExecutionTreeObject>) -> String {
+        match result {
+            Err(Error(ErrorKind::InvalidData(location, _), _)) => location,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn reads_a_single_value() {
+        let options = DataOptions::new();
+        let object = data::read("(:name \"a\"\n :ports (80 443u16) :ratio 1/2)", &options).unwrap();
+        assert_eq!(object.to_string().unwrap(), "(:name \"a\" :ports (80i32 443u16) :ratio 1/2)");
+        assert_eq!(get_location(data::read("1 2", &options)), "1:1");
+        assert_eq!(get_location(data::read("(1))", &options)), "1:4");
+    }
+
+    #[test]
+    fn code_forms_are_rejected() {
+        let options = DataOptions::new();
+        assert_eq!(get_location(data::read("(:a\n  (quote x))", &options)), "2:3");
+    }
+
+    #[test]
+    fn literals_are_reported_at_their_own_position() {
+        let mut options = DataOptions::new();
+        options.disallow(DataLiteralType::Float);
+        assert_eq!(get_location(data::read("(:a 1\n :b 2.5)", &options)), "2:5");
+        assert_eq!(get_location(data::read("(:a \"x\" :b 5q)", &options)), "1:12");
+    }
+
+    #[test]
+    fn nesting_deeper_than_the_maximum_depth_is_rejected() {
+        let mut options = DataOptions::new();
+        options.set_max_depth(2);
+        assert!(data::read("((1))", &options).is_ok());
+        assert_eq!(get_location(data::read("(1 ((2)))", &options)), "1:5");
+
+        let text = "(".repeat(200000);
+        assert!(data::read(&text, &DataOptions::new()).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+use error::*;
+
+const BASE64_ALPHABET: &'static [u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const HEX_DIGITS: &'static [u8] = b"0123456789abcdef";
+
+pub fn base64_str() -> &'static str {
+    "base64"
+}
+
+pub fn hex_str() -> &'static str {
+    "hex"
+}
+
+pub fn utf8_str() -> &'static str {
+    "utf-8"
+}
+
+pub fn decode_base64(text: &str) -> Result<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    if text.len() % 4 == 1 {
+        return Err(ErrorKind::InvalidEncoding(base64_str().to_string(), text.to_string()).into());
+    }
+
+    let mut result = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in text.bytes() {
+        let value =
+            match BASE64_ALPHABET.iter().position(|item| *item == byte) {
+                Some(value) => value as u32,
+                None => {
+                    return Err(ErrorKind::InvalidEncoding(base64_str().to_string(), text.to_string()).into());
+                },
+            };
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(result)
+}
+
+pub fn decode_hex(text: &str) -> Result<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return Err(ErrorKind::InvalidEncoding(hex_str().to_string(), text.to_string()).into());
+    }
+
+    let mut result = Vec::with_capacity(text.len() / 2);
+    for pair in text.as_bytes().chunks(2) {
+        match ((pair[0] as char).to_digit(16), (pair[1] as char).to_digit(16)) {
+            (Some(high), Some(low)) => result.push((high * 16 + low) as u8),
+            _ => {
+                return Err(ErrorKind::InvalidEncoding(hex_str().to_string(), text.to_string()).into());
+            },
+        }
+    }
+    Ok(result)
+}
+
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let buffer = chunk.iter().enumerate().fold(0u32, |buffer, (index, byte)| buffer | (*byte as u32) << (16 - index * 8));
+        for index in 0..4 {
+            if index <= chunk.len() {
+                result.push(BASE64_ALPHABET[((buffer >> (18 - index * 6)) & 0x3f) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        result.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        result.push(HEX_DIGITS[(byte & 0x0f) as usize] as char);
+    }
+    result
+}
+
+pub fn escape_bytes(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len() + 3);
+    result.push_str("b\"");
+    for byte in bytes {
+        match *byte {
+            b'\n' => result.push_str("\\n"),
+            b'\r' => result.push_str("\\r"),
+            b'\t' => result.push_str("\\t"),
+            b'\\' => result.push_str("\\\\"),
+            b'"' => result.push_str("\\x22"),
+            0x20..=0x7e => result.push(*byte as char),
+            byte => {
+                result.push_str("\\x");
+                result.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+                result.push(HEX_DIGITS[(byte & 0x0f) as usize] as char);
+            },
+        }
+    }
+    result.push('"');
+    result
+}
+
+pub fn unescape_bytes(text: &str) -> Result<Vec<u8>> {
+    let mut result = Vec::with_capacity(text.len());
+
+    let mut chars = text.chars();
+    while let Some(character) = chars.next() {
+        if character == '\\' {
+            match chars.next() {
+                Some('n') => result.push(b'\n'),
+                Some('r') => result.push(b'\r'),
+                Some('t') => result.push(b'\t'),
+                Some('\\') => result.push(b'\\'),
+                Some('0') => result.push(0),
+                Some('x') => {
+                    let digits: String = chars.by_ref().take(2).collect();
+                    match u8::from_str_radix(&digits, 16) {
+                        Ok(byte) if digits.len() == 2 && digits.chars().all(|digit| digit.is_digit(16)) => result.push(byte),
+                        _ => {
+                            return Err(ErrorKind::InvalidEscapeSequence('x').into());
+                        },
+                    }
+                },
+                Some(escaped_character) => {
+                    return Err(ErrorKind::InvalidEscapeSequence(escaped_character).into());
+                },
+                None => {
+                    return Err(ErrorKind::EmptyEscapeSequence.into());
+                },
+            }
+        } else {
+            let mut buffer = [0; 4];
+            result.extend_from_slice(character.encode_utf8(&mut buffer).as_bytes());
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding;
+
+    #[test]
+    fn base64_round_trips() {
+        for &(bytes, text) in &[(&b""[..], ""), (&b"f"[..], "Zg=="), (&b"fo"[..], "Zm8="), (&b"foo"[..], "Zm9v"), (&b"\xff\x00"[..], "/wA=")] {
+            assert_eq!(encoding::encode_base64(bytes), text);
+            assert_eq!(encoding::decode_base64(text).unwrap(), bytes);
+        }
+        assert_eq!(encoding::decode_base64("Zg").unwrap(), b"f");
+    }
+
+    #[test]
+    fn base64_rejects_invalid_text() {
+        assert!(encoding::decode_base64("Z").is_err());
+        assert!(encoding::decode_base64("Zm9v!").is_err());
+        assert!(encoding::decode_base64("Zm 9v").is_err());
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        assert_eq!(encoding::encode_hex(b"\x00\x7f\xff"), "007fff");
+        assert_eq!(encoding::decode_hex("007FfF").unwrap(), b"\x00\x7f\xff");
+        assert!(encoding::decode_hex("abc").is_err());
+        assert!(encoding::decode_hex("zz").is_err());
+    }
+
+    #[test]
+    fn hex_rejects_non_ascii_text() {
+        assert!(encoding::decode_hex("é").is_err());
+        assert!(encoding::decode_hex("0é0").is_err());
+    }
+
+    #[test]
+    fn escaped_bytes_round_trip() {
+        let bytes = b"a\"\\\n\x00\xff";
+        let escaped = encoding::escape_bytes(bytes);
+        assert_eq!(escaped, "b\"a\\x22\\\\\\n\\x00\\xff\"");
+        assert_eq!(encoding::unescape_bytes(&escaped[2..(escaped.len() - 1)]).unwrap(), &bytes[..]);
+        assert!(encoding::unescape_bytes("\\x4").is_err());
+        assert!(encoding::unescape_bytes("\\q").is_err());
+        assert!(encoding::unescape_bytes("\\").is_err());
+    }
+}
@@ -0,0 +1,43 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+const REPORTED_FORM_COUNT: usize = 32;
+
+// Kept in the cause slot of an error's state, so the error keeps its own kind while it carries the
+// head of each enclosing form.
+#[derive(Debug)]
+pub struct LispBacktrace {
+    forms: Vec<String>,
+}
+
+impl LispBacktrace {
+    pub fn new(forms: Vec<String>) -> Self {
+        LispBacktrace {
+            forms: forms,
+        }
+    }
+
+    pub fn get_forms(&self) -> &Vec<String> {
+        &self.forms
+    }
+
+    pub fn push_form(&mut self, form: String) {
+        if self.forms.len() < REPORTED_FORM_COUNT {
+            self.forms.push(form);
+        }
+    }
+}
+
+impl Display for LispBacktrace {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "Lisp backtrace, most recent form first:\n    {}", self.forms.join("\n    "))
+    }
+}
+
+impl Error for LispBacktrace {
+    fn description(&self) -> &str {
+        "Lisp backtrace of the forms being evaluated."
+    }
+}
@@ -1,12 +1,20 @@
+mod lisp_backtrace;
+
 mod wrap_err;
 
 mod wrap_error;
 
+mod wrapped_message;
+
 pub use error::wrap_err::WrapErr;
 
 pub use error::wrap_error::WrapError;
 
+use error::lisp_backtrace::LispBacktrace;
+use error::wrapped_message::WrappedMessage;
+use lisp::ExecutionTreeObject;
 use lisp::LastCharType;
+use lisp::Span;
 use std::error::Error as StdError;
 
 error_chain! {
@@ -17,6 +25,18 @@ error_chain! {
     foreign_links { }
 
     errors {
+        ArithmeticOverflow(function: String, type_name: String) {
+            description("Error evaluating arithmetic. The result does not fit in the result type.")
+            display(
+                "{}{}{}{}{}",
+                "Error evaluating arithmetic. The result of, '",
+                function,
+                "', does not fit in the type, '",
+                type_name,
+                "'.",
+            )
+        }
+
         BeginningStringInWord(character: char, index: usize) {
             description("Error parsing lisp. Attempted to begin a string while parsing another word.")
             display(
@@ -29,6 +49,26 @@ error_chain! {
             )
         }
 
+        CircularModuleImport(path: String) {
+            description("Error loading module. The module imports itself.")
+            display(
+                "{}{}{}",
+                "Error loading module. The module, '",
+                path,
+                "', imports itself.",
+            )
+        }
+
+        DivisionByZero(function: String) {
+            description("Error evaluating arithmetic. An integer was divided by zero.")
+            display(
+                "{}{}{}",
+                "Error evaluating arithmetic. The function, '",
+                function,
+                "', divided an integer by zero.",
+            )
+        }
+
         EmptyEscapeSequence {
             description("Error escaping character. A character must follow a, '\\'.")
             display(
@@ -55,6 +95,68 @@ error_chain! {
             )
         }
 
+        ExportOutsideModule(symbol: String) {
+            description("Error exporting symbol. Symbols can only be exported from a module.")
+            display(
+                "{}{}{}",
+                "Error exporting symbol. The symbol, '",
+                symbol,
+                "', can only be exported from a module.",
+            )
+        }
+
+        InvalidArgumentCount(function: String, expected: String, actual: usize) {
+            description("Error calling lisp function. The function received the wrong number of arguments.")
+            display(
+                "{}{}{}{}{}{}{}",
+                "Error calling lisp function. The function, '",
+                function,
+                "', expects, '",
+                expected,
+                "', arguments but received, '",
+                actual,
+                "'.",
+            )
+        }
+
+        IndexOutOfBounds(function: String, index: i128, length: usize) {
+            description("Error calling lisp function. The index is out of bounds.")
+            display(
+                "{}{}{}{}{}{}{}",
+                "Error calling lisp function. The function, '",
+                function,
+                "', received the index, '",
+                index,
+                "', which is out of bounds for the length, '",
+                length,
+                "'.",
+            )
+        }
+
+        InvalidData(location: String, message: String) {
+            description("Error reading data. The text is not a valid data document.")
+            display(
+                "{}{}{}{}{}",
+                "Error reading data. At, '",
+                location,
+                "', ",
+                message,
+                ".",
+            )
+        }
+
+        InvalidEncoding(encoding: String, text: String) {
+            description("Error decoding value. The input is not valid for the encoding.")
+            display(
+                "{}{}{}{}{}",
+                "Error decoding value. The input, '",
+                text,
+                "', is not valid, '",
+                encoding,
+                "'.",
+            )
+        }
+
         InvalidEscapeSequence(escaped_character: char) {
             description("Error escaping character. The character cannot be escaped.")
             display(
@@ -77,6 +179,40 @@ error_chain! {
             )
         }
 
+        InvalidForm(function: String, form: String) {
+            description("Error evaluating lisp. The form is invalid.")
+            display(
+                "{}{}{}{}{}",
+                "Error evaluating lisp. The form, '",
+                form,
+                "', is invalid for, '",
+                function,
+                "'.",
+            )
+        }
+
+        InvalidJson(index: usize, message: String) {
+            description("Error parsing JSON. The text is not valid JSON.")
+            display(
+                "{}{}{}{}{}",
+                "Error parsing JSON. At index, '",
+                index,
+                "', ",
+                message,
+                ".",
+            )
+        }
+
+        InvalidModulePath(path: String) {
+            description("Error loading module. The module path leaves the module root.")
+            display(
+                "{}{}{}",
+                "Error loading module. The module path, '",
+                path,
+                "', must be relative and cannot leave the module root.",
+            )
+        }
+
         InvalidPreviousChar(character: char, index: usize, previous_char_type: LastCharType) {
             description("Error parsing lisp. The current character cannot follow the previous character.")
             display(
@@ -91,6 +227,54 @@ error_chain! {
             )
         }
 
+        InvalidSchema(path: String, message: String) {
+            description("Error compiling schema. The schema is not valid.")
+            display(
+                "{}{}{}{}{}",
+                "Error compiling schema. At the path, '",
+                path,
+                "', ",
+                message,
+                ".",
+            )
+        }
+
+        MemoryLimitExceeded(used: usize, limit: usize) {
+            description("Error allocating lisp value. The memory limit has been exceeded.")
+            display(
+                "{}{}{}{}{}",
+                "Error allocating lisp value. The approximate memory in use, '",
+                used,
+                "' bytes, exceeds the memory limit, '",
+                limit,
+                "' bytes.",
+            )
+        }
+
+        ModuleAliasConflict(alias: String, path: String, existing_path: String) {
+            description("Error loading module. Two modules share the same alias.")
+            display(
+                "{}{}{}{}{}{}{}",
+                "Error loading module. The module, '",
+                path,
+                "', has the alias, '",
+                alias,
+                "', which is already used by the module, '",
+                existing_path,
+                "'.",
+            )
+        }
+
+        ModuleNotFound(path: String) {
+            description("Error loading module. The module cannot be found.")
+            display(
+                "{}{}{}",
+                "Error loading module. The module, '",
+                path,
+                "', cannot be found.",
+            )
+        }
+
         MultipleCharactersInSingleQuotes(token: String) {
             description("Error parsing lisp. Multiple characters found in single quotes in execution tree.")
             display(
@@ -101,6 +285,18 @@ error_chain! {
             )
         }
 
+        NestingDepthExceeded(max_depth: usize, index: usize) {
+            description("Error parsing lisp. The maximum nesting depth was exceeded.")
+            display(
+                "{}{}{}{}{}",
+                "Error parsing lisp. The list at index, '",
+                index,
+                "', is nested deeper than the maximum depth, '",
+                max_depth,
+                "'.",
+            )
+        }
+
         NoCharacterInSingleQuotes(token: String) {
             description("Error parsing lisp. No character found in single quotes in execution tree.")
             display(
@@ -189,6 +385,28 @@ error_chain! {
             )
         }
 
+        Serialization(path: String, message: String) {
+            description("Error converting between lisp and Rust values.")
+            display(
+                "{}{}{}{}",
+                "Error converting between lisp and Rust values. At the path, '$",
+                path,
+                "', ",
+                message,
+            )
+        }
+
+        StackDepthExceeded(depth: usize, frames: Vec<String>) {
+            description("Error pushing frame. The maximum call depth has been exceeded.")
+            display(
+                "{}{}{}{}",
+                "Error pushing frame. The maximum call depth, '",
+                depth,
+                "', has been exceeded. Most recent frames:\n",
+                frames.join("\n"),
+            )
+        }
+
         SymbolNotOnStack(symbol: String) {
             description("Error locating symbol. The symbol cannot be found on the stack.")
             display(
@@ -198,5 +416,201 @@ error_chain! {
                 "', cannot be found on the stack.",
             )
         }
+
+        Thrown(tag: String, value: ExecutionTreeObject) {
+            description("Error evaluating lisp. A value was thrown and not caught.")
+            display(
+                "{}{}{}{}{}",
+                "Error evaluating lisp. The tag, '",
+                tag,
+                "', was thrown with value, '",
+                value.to_string().unwrap_or_else(|_| value.enum_to_string().to_string()),
+                "', and not caught.",
+            )
+        }
+
+        UndefinedExport(path: String, symbol: String) {
+            description("Error loading module. An exported symbol is not defined.")
+            display(
+                "{}{}{}{}{}",
+                "Error loading module. The module, '",
+                path,
+                "', exports the symbol, '",
+                symbol,
+                "', which is not defined.",
+            )
+        }
+
+        UndefinedMethod(type_name: String, method: String) {
+            description("Error calling method. The object does not define the method.")
+            display(
+                "{}{}{}{}{}",
+                "Error calling method. The object of type, '",
+                type_name,
+                "', does not define the method, '",
+                method,
+                "'.",
+            )
+        }
+
+        UndefinedProperty(type_name: String, property: String) {
+            description("Error reading property. The object does not define the property.")
+            display(
+                "{}{}{}{}{}",
+                "Error reading property. The object of type, '",
+                type_name,
+                "', does not define the property, '",
+                property,
+                "'.",
+            )
+        }
+    }
+}
+
+// A wrapped error cannot be cloned, so a clone keeps only its description and message.
+impl Clone for ErrorKind {
+    fn clone(&self) -> Self {
+        match self {
+            &ErrorKind::Msg(ref message) => ErrorKind::Msg(message.clone()),
+            &ErrorKind::ArithmeticOverflow(ref function, ref type_name) => ErrorKind::ArithmeticOverflow(function.clone(), type_name.clone()),
+            &ErrorKind::BeginningStringInWord(ref character, ref index) => ErrorKind::BeginningStringInWord(character.clone(), index.clone()),
+            &ErrorKind::CircularModuleImport(ref path) => ErrorKind::CircularModuleImport(path.clone()),
+            &ErrorKind::DivisionByZero(ref function) => ErrorKind::DivisionByZero(function.clone()),
+            &ErrorKind::EmptyEscapeSequence => ErrorKind::EmptyEscapeSequence,
+            &ErrorKind::EmptyStringDetected(ref contents) => ErrorKind::EmptyStringDetected(contents.clone()),
+            &ErrorKind::ErrorWrapper(ref cause) => ErrorKind::ErrorWrapper(Box::new(WrappedMessage::new(cause.description().to_string(), cause.to_string()))),
+            &ErrorKind::ExportOutsideModule(ref symbol) => ErrorKind::ExportOutsideModule(symbol.clone()),
+            &ErrorKind::InvalidArgumentCount(ref function, ref expected, ref actual) => ErrorKind::InvalidArgumentCount(function.clone(), expected.clone(), actual.clone()),
+            &ErrorKind::IndexOutOfBounds(ref function, ref index, ref length) => ErrorKind::IndexOutOfBounds(function.clone(), index.clone(), length.clone()),
+            &ErrorKind::InvalidData(ref location, ref message) => ErrorKind::InvalidData(location.clone(), message.clone()),
+            &ErrorKind::InvalidEncoding(ref encoding, ref text) => ErrorKind::InvalidEncoding(encoding.clone(), text.clone()),
+            &ErrorKind::InvalidEscapeSequence(ref escaped_character) => ErrorKind::InvalidEscapeSequence(escaped_character.clone()),
+            &ErrorKind::InvalidExecutionTreeObjectConversion(ref actual, ref expected) => ErrorKind::InvalidExecutionTreeObjectConversion(actual.clone(), expected.clone()),
+            &ErrorKind::InvalidForm(ref function, ref form) => ErrorKind::InvalidForm(function.clone(), form.clone()),
+            &ErrorKind::InvalidJson(ref index, ref message) => ErrorKind::InvalidJson(index.clone(), message.clone()),
+            &ErrorKind::InvalidModulePath(ref path) => ErrorKind::InvalidModulePath(path.clone()),
+            &ErrorKind::InvalidPreviousChar(ref character, ref index, ref previous_char_type) => ErrorKind::InvalidPreviousChar(character.clone(), index.clone(), previous_char_type.clone()),
+            &ErrorKind::InvalidSchema(ref path, ref message) => ErrorKind::InvalidSchema(path.clone(), message.clone()),
+            &ErrorKind::MemoryLimitExceeded(ref used, ref limit) => ErrorKind::MemoryLimitExceeded(used.clone(), limit.clone()),
+            &ErrorKind::ModuleAliasConflict(ref alias, ref path, ref existing_path) => ErrorKind::ModuleAliasConflict(alias.clone(), path.clone(), existing_path.clone()),
+            &ErrorKind::ModuleNotFound(ref path) => ErrorKind::ModuleNotFound(path.clone()),
+            &ErrorKind::MultipleCharactersInSingleQuotes(ref token) => ErrorKind::MultipleCharactersInSingleQuotes(token.clone()),
+            &ErrorKind::NestingDepthExceeded(ref max_depth, ref index) => ErrorKind::NestingDepthExceeded(max_depth.clone(), index.clone()),
+            &ErrorKind::NoCharacterInSingleQuotes(ref token) => ErrorKind::NoCharacterInSingleQuotes(token.clone()),
+            &ErrorKind::NoClosingDoubleQuoteInExecutionTree(ref token) => ErrorKind::NoClosingDoubleQuoteInExecutionTree(token.clone()),
+            &ErrorKind::NoClosingDoubleQuote(ref text) => ErrorKind::NoClosingDoubleQuote(text.clone()),
+            &ErrorKind::NoClosingParenthesis(ref text) => ErrorKind::NoClosingParenthesis(text.clone()),
+            &ErrorKind::NoClosingSingleQuoteInExecutionTree(ref token) => ErrorKind::NoClosingSingleQuoteInExecutionTree(token.clone()),
+            &ErrorKind::NoClosingSingleQuote(ref text) => ErrorKind::NoClosingSingleQuote(text.clone()),
+            &ErrorKind::NoLastChar(ref contents) => ErrorKind::NoLastChar(contents.clone()),
+            &ErrorKind::NoProgramStartParenthesis(ref text) => ErrorKind::NoProgramStartParenthesis(text.clone()),
+            &ErrorKind::NumericTokenCannotBeParsed(ref expected_type, ref token) => ErrorKind::NumericTokenCannotBeParsed(expected_type.clone(), token.clone()),
+            &ErrorKind::Serialization(ref path, ref message) => ErrorKind::Serialization(path.clone(), message.clone()),
+            &ErrorKind::StackDepthExceeded(ref depth, ref frames) => ErrorKind::StackDepthExceeded(depth.clone(), frames.clone()),
+            &ErrorKind::SymbolNotOnStack(ref symbol) => ErrorKind::SymbolNotOnStack(symbol.clone()),
+            &ErrorKind::Thrown(ref tag, ref value) => ErrorKind::Thrown(tag.clone(), value.clone()),
+            &ErrorKind::UndefinedExport(ref path, ref symbol) => ErrorKind::UndefinedExport(path.clone(), symbol.clone()),
+            &ErrorKind::UndefinedMethod(ref type_name, ref method) => ErrorKind::UndefinedMethod(type_name.clone(), method.clone()),
+            &ErrorKind::UndefinedProperty(ref type_name, ref property) => ErrorKind::UndefinedProperty(type_name.clone(), property.clone()),
+        }
+    }
+}
+
+impl Clone for Error {
+    fn clone(&self) -> Self {
+        let lisp_backtrace =
+            match self.get_lisp_backtrace() {
+                Some(forms) => Some(Box::new(LispBacktrace::new(forms.clone())) as Box<StdError + Send>),
+                None => None,
+            };
+        Error(self.0.clone(), (lisp_backtrace, (self.1).1.clone()))
+    }
+}
+
+impl ErrorKind {
+    pub fn enum_to_string(&self) -> &'static str {
+        match self {
+            &ErrorKind::Msg(_) => "ErrorKind::Msg",
+            &ErrorKind::ArithmeticOverflow(..) => "ErrorKind::ArithmeticOverflow",
+            &ErrorKind::BeginningStringInWord(..) => "ErrorKind::BeginningStringInWord",
+            &ErrorKind::CircularModuleImport(..) => "ErrorKind::CircularModuleImport",
+            &ErrorKind::DivisionByZero(..) => "ErrorKind::DivisionByZero",
+            &ErrorKind::EmptyEscapeSequence => "ErrorKind::EmptyEscapeSequence",
+            &ErrorKind::EmptyStringDetected(..) => "ErrorKind::EmptyStringDetected",
+            &ErrorKind::ErrorWrapper(..) => "ErrorKind::ErrorWrapper",
+            &ErrorKind::ExportOutsideModule(..) => "ErrorKind::ExportOutsideModule",
+            &ErrorKind::InvalidArgumentCount(..) => "ErrorKind::InvalidArgumentCount",
+            &ErrorKind::IndexOutOfBounds(..) => "ErrorKind::IndexOutOfBounds",
+            &ErrorKind::InvalidData(..) => "ErrorKind::InvalidData",
+            &ErrorKind::InvalidEncoding(..) => "ErrorKind::InvalidEncoding",
+            &ErrorKind::InvalidEscapeSequence(..) => "ErrorKind::InvalidEscapeSequence",
+            &ErrorKind::InvalidExecutionTreeObjectConversion(..) => "ErrorKind::InvalidExecutionTreeObjectConversion",
+            &ErrorKind::InvalidForm(..) => "ErrorKind::InvalidForm",
+            &ErrorKind::InvalidJson(..) => "ErrorKind::InvalidJson",
+            &ErrorKind::InvalidModulePath(..) => "ErrorKind::InvalidModulePath",
+            &ErrorKind::InvalidPreviousChar(..) => "ErrorKind::InvalidPreviousChar",
+            &ErrorKind::InvalidSchema(..) => "ErrorKind::InvalidSchema",
+            &ErrorKind::MemoryLimitExceeded(..) => "ErrorKind::MemoryLimitExceeded",
+            &ErrorKind::ModuleAliasConflict(..) => "ErrorKind::ModuleAliasConflict",
+            &ErrorKind::ModuleNotFound(..) => "ErrorKind::ModuleNotFound",
+            &ErrorKind::MultipleCharactersInSingleQuotes(..) => "ErrorKind::MultipleCharactersInSingleQuotes",
+            &ErrorKind::NestingDepthExceeded(..) => "ErrorKind::NestingDepthExceeded",
+            &ErrorKind::NoCharacterInSingleQuotes(..) => "ErrorKind::NoCharacterInSingleQuotes",
+            &ErrorKind::NoClosingDoubleQuoteInExecutionTree(..) => "ErrorKind::NoClosingDoubleQuoteInExecutionTree",
+            &ErrorKind::NoClosingDoubleQuote(..) => "ErrorKind::NoClosingDoubleQuote",
+            &ErrorKind::NoClosingParenthesis(..) => "ErrorKind::NoClosingParenthesis",
+            &ErrorKind::NoClosingSingleQuoteInExecutionTree(..) => "ErrorKind::NoClosingSingleQuoteInExecutionTree",
+            &ErrorKind::NoClosingSingleQuote(..) => "ErrorKind::NoClosingSingleQuote",
+            &ErrorKind::NoLastChar(..) => "ErrorKind::NoLastChar",
+            &ErrorKind::NoProgramStartParenthesis(..) => "ErrorKind::NoProgramStartParenthesis",
+            &ErrorKind::NumericTokenCannotBeParsed(..) => "ErrorKind::NumericTokenCannotBeParsed",
+            &ErrorKind::Serialization(..) => "ErrorKind::Serialization",
+            &ErrorKind::StackDepthExceeded(..) => "ErrorKind::StackDepthExceeded",
+            &ErrorKind::SymbolNotOnStack(..) => "ErrorKind::SymbolNotOnStack",
+            &ErrorKind::Thrown(..) => "ErrorKind::Thrown",
+            &ErrorKind::UndefinedExport(..) => "ErrorKind::UndefinedExport",
+            &ErrorKind::UndefinedMethod(..) => "ErrorKind::UndefinedMethod",
+            &ErrorKind::UndefinedProperty(..) => "ErrorKind::UndefinedProperty",
+        }
+    }
+}
+
+impl Error {
+    pub fn get_lisp_backtrace(&self) -> Option<&Vec<String>> {
+        match (self.1).0 {
+            Some(ref cause) => cause.downcast_ref::<LispBacktrace>().map(|lisp_backtrace| lisp_backtrace.get_forms()),
+            None => None,
+        }
+    }
+
+    pub fn push_lisp_backtrace_form(mut self, form: String, span: Option<&Span>) -> Self {
+        let form =
+            match span {
+                Some(span) => format!("{} at {}", form, span.to_string()),
+                None => form,
+            };
+        match (self.1).0 {
+            Some(ref mut cause) => {
+                if let Some(lisp_backtrace) = cause.downcast_mut::<LispBacktrace>() {
+                    lisp_backtrace.push_form(form);
+                }
+            },
+            None => {
+                (self.1).0 = Some(Box::new(LispBacktrace::new(vec![form])));
+            },
+        }
+        self
+    }
+
+    pub fn push_serialization_path(self, segment: String) -> Self {
+        match self {
+            Error(ErrorKind::Serialization(path, message), state) => {
+                Error(ErrorKind::Serialization(segment + &path, message), state)
+            },
+            error => {
+                let backtrace = (error.1).1.clone();
+                Error(ErrorKind::Serialization(segment, error.to_string()), (None, backtrace))
+            },
+        }
     }
 }
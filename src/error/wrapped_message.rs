@@ -0,0 +1,31 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+#[derive(Debug)]
+pub struct WrappedMessage {
+    description: String,
+    message: String,
+}
+
+impl WrappedMessage {
+    pub fn new(description: String, message: String) -> Self {
+        WrappedMessage {
+            description: description,
+            message: message,
+        }
+    }
+}
+
+impl Display for WrappedMessage {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.message)
+    }
+}
+
+impl Error for WrappedMessage {
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
@@ -0,0 +1,70 @@
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JsonFloatType {
+    F32,
+    F64,
+    Rational,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JsonIntegerType {
+    BigInt,
+    I32,
+    I64,
+}
+
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+// Controls how JSON numbers map onto numeric variants. Integers that do not fit the chosen integer type
+// are read as a BigInt. When suffixes are preserved, numbers are written as strings carrying their
+// literal suffix, such as "5i32" or "1/3", and any string spelling such a literal is read back as a number.
+// Arrays and objects nested deeper than the maximum depth are rejected rather than overflowing the stack.
+#[derive(Clone, Debug)]
+pub struct JsonOptions {
+    float_type: JsonFloatType,
+    integer_type: JsonIntegerType,
+    max_depth: usize,
+    preserve_suffixes: bool,
+}
+
+impl JsonOptions {
+    pub fn new() -> Self {
+        JsonOptions {
+            float_type: JsonFloatType::F64,
+            integer_type: JsonIntegerType::I64,
+            max_depth: DEFAULT_MAX_DEPTH,
+            preserve_suffixes: false,
+        }
+    }
+
+    pub fn get_float_type(&self) -> JsonFloatType {
+        self.float_type
+    }
+
+    pub fn get_integer_type(&self) -> JsonIntegerType {
+        self.integer_type
+    }
+
+    pub fn get_max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn get_preserve_suffixes(&self) -> bool {
+        self.preserve_suffixes
+    }
+
+    pub fn set_float_type(&mut self, float_type: JsonFloatType) {
+        self.float_type = float_type;
+    }
+
+    pub fn set_integer_type(&mut self, integer_type: JsonIntegerType) {
+        self.integer_type = integer_type;
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    pub fn set_preserve_suffixes(&mut self, preserve_suffixes: bool) {
+        self.preserve_suffixes = preserve_suffixes;
+    }
+}
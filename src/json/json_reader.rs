@@ -0,0 +1,389 @@
+use error::*;
+use json::JsonFloatType;
+use json::JsonIntegerType;
+use json::JsonOptions;
+use lisp::ExecutionTree;
+use lisp::ExecutionTreeMap;
+use lisp::ExecutionTreeNode;
+use lisp::ExecutionTreeObject;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::pow;
+
+const MAX_RATIONAL_EXPONENT: usize = 4096;
+
+pub struct JsonReader<'a> {
+    depth: usize,
+    index: usize,
+    options: &'a JsonOptions,
+    text: &'a str,
+}
+
+impl <'a> JsonReader<'a> {
+    pub fn new(text: &'a str, options: &'a JsonOptions) -> Self {
+        JsonReader {
+            depth: 0,
+            index: 0,
+            options: options,
+            text: text,
+        }
+    }
+
+    pub fn read(&mut self) -> Result<ExecutionTreeObject> {
+        let result = try!(self.read_value());
+        self.skip_whitespace();
+        if self.index < self.text.len() {
+            return Err(self.error("expected the end of the text"));
+        }
+        Ok(result)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        ErrorKind::InvalidJson(self.index, message.to_string()).into()
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        if self.depth >= self.options.get_max_depth() {
+            return Err(self.error("the maximum nesting depth was exceeded"));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        if self.peek() == Some(byte) {
+            self.index += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.index).cloned()
+    }
+
+    fn read_array(&mut self) -> Result<ExecutionTreeObject> {
+        try!(self.expect(b'['));
+
+        let mut objects = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.index += 1;
+            return Ok(ExecutionTreeObject::Node(ExecutionTreeNode::new(objects)));
+        }
+
+        loop {
+            objects.push(try!(self.read_value()));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.index += 1,
+                Some(b']') => {
+                    self.index += 1;
+                    return Ok(ExecutionTreeObject::Node(ExecutionTreeNode::new(objects)));
+                },
+                _ => {
+                    return Err(self.error("expected ',' or ']'"));
+                },
+            }
+        }
+    }
+
+    fn read_digits(&mut self) -> usize {
+        let start = self.index;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.index += 1;
+        }
+        self.index - start
+    }
+
+    fn read_float(&self, token: &str) -> Result<ExecutionTreeObject> {
+        let result =
+            match self.options.get_float_type() {
+                JsonFloatType::F32 => token.parse::<f32>().ok().filter(|value| value.is_finite()).map(ExecutionTreeObject::F32),
+                JsonFloatType::F64 => token.parse::<f64>().ok().filter(|value| value.is_finite()).map(ExecutionTreeObject::F64),
+                JsonFloatType::Rational => Self::read_rational(token).map(ExecutionTreeObject::Rational),
+            };
+        match result {
+            Some(result) => Ok(result),
+            None => Err(self.error(&format!("the number, '{}', cannot be represented", token))),
+        }
+    }
+
+    fn read_integer(&self, token: &str) -> Result<ExecutionTreeObject> {
+        let result =
+            match self.options.get_integer_type() {
+                JsonIntegerType::BigInt => None,
+                JsonIntegerType::I32 => token.parse().ok().map(ExecutionTreeObject::I32),
+                JsonIntegerType::I64 => token.parse().ok().map(ExecutionTreeObject::I64),
+            };
+        match result {
+            Some(result) => Ok(result),
+            None => {
+                match token.parse() {
+                    Ok(value) => Ok(ExecutionTreeObject::BigInt(value)),
+                    Err(_) => Err(self.error(&format!("the number, '{}', cannot be represented", token))),
+                }
+            },
+        }
+    }
+
+    fn read_literal(&mut self, literal: &str, object: ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        if self.text[self.index..].starts_with(literal) {
+            self.index += literal.len();
+            Ok(object)
+        } else {
+            Err(self.error("expected a value"))
+        }
+    }
+
+    fn read_number(&mut self) -> Result<ExecutionTreeObject> {
+        let start = self.index;
+        if self.peek() == Some(b'-') {
+            self.index += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.index += 1,
+            Some(b'1'..=b'9') => {
+                self.read_digits();
+            },
+            _ => {
+                return Err(self.error("expected a digit"));
+            },
+        }
+
+        let mut is_integer = true;
+        if self.peek() == Some(b'.') {
+            self.index += 1;
+            is_integer = false;
+            if self.read_digits() == 0 {
+                return Err(self.error("expected a digit after the decimal point"));
+            }
+        }
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            self.index += 1;
+            is_integer = false;
+            if let Some(b'+') | Some(b'-') = self.peek() {
+                self.index += 1;
+            }
+            if self.read_digits() == 0 {
+                return Err(self.error("expected a digit in the exponent"));
+            }
+        }
+
+        let token = &self.text[start..self.index];
+        if is_integer {
+            self.read_integer(token)
+        } else {
+            self.read_float(token)
+        }
+    }
+
+    fn read_object(&mut self) -> Result<ExecutionTreeObject> {
+        try!(self.expect(b'{'));
+
+        let mut map = ExecutionTreeMap::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.index += 1;
+            return Ok(ExecutionTreeObject::Map(map));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = try!(self.read_string());
+            self.skip_whitespace();
+            try!(self.expect(b':'));
+            let value = try!(self.read_value());
+            map.insert(ExecutionTreeObject::String(key), value);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.index += 1,
+                Some(b'}') => {
+                    self.index += 1;
+                    return Ok(ExecutionTreeObject::Map(map));
+                },
+                _ => {
+                    return Err(self.error("expected ',' or '}'"));
+                },
+            }
+        }
+    }
+
+    fn read_rational(token: &str) -> Option<BigRational> {
+        let (mantissa, exponent) =
+            match token.find(|character| character == 'e' || character == 'E') {
+                Some(index) => {
+                    match token[(index + 1)..].trim_start_matches('+').parse::<i64>() {
+                        Ok(exponent) => (&token[..index], exponent),
+                        Err(_) => {
+                            return None;
+                        },
+                    }
+                },
+                None => (token, 0),
+            };
+        let (integer, fraction) =
+            match mantissa.find('.') {
+                Some(index) => (&mantissa[..index], &mantissa[(index + 1)..]),
+                None => (mantissa, ""),
+            };
+
+        let digits: BigInt =
+            match format!("{}{}", integer, fraction).parse() {
+                Ok(digits) => digits,
+                Err(_) => {
+                    return None;
+                },
+            };
+        let scale = exponent - fraction.len() as i64;
+        if scale.unsigned_abs() as usize > MAX_RATIONAL_EXPONENT {
+            return None;
+        }
+        let power = pow(BigInt::from(10), scale.unsigned_abs() as usize);
+        if scale >= 0 {
+            Some(BigRational::from_integer(digits * power))
+        } else {
+            Some(BigRational::new(digits, power))
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        try!(self.expect(b'"'));
+
+        let mut result = String::new();
+        loop {
+            let character =
+                match self.text[self.index..].chars().next() {
+                    Some(character) => character,
+                    None => {
+                        return Err(self.error("expected a closing '\"'"));
+                    },
+                };
+            self.index += character.len_utf8();
+            match character {
+                '"' => {
+                    return Ok(result);
+                },
+                '\\' => {
+                    let escaped = try!(self.read_escape());
+                    result.push(escaped);
+                },
+                '\u{0}'..='\u{1f}' => {
+                    return Err(self.error("control characters must be escaped"));
+                },
+                character => result.push(character),
+            }
+        }
+    }
+
+    fn read_escape(&mut self) -> Result<char> {
+        let escaped = self.peek();
+        self.index += 1;
+        let result =
+            match escaped {
+                Some(b'"') => '"',
+                Some(b'\\') => '\\',
+                Some(b'/') => '/',
+                Some(b'b') => '\u{8}',
+                Some(b'f') => '\u{c}',
+                Some(b'n') => '\n',
+                Some(b'r') => '\r',
+                Some(b't') => '\t',
+                Some(b'u') => {
+                    let high = try!(self.read_hex_code());
+                    if high >= 0xd800 && high < 0xdc00 {
+                        if !self.text[self.index..].starts_with("\\u") {
+                            return Err(self.error("expected a low surrogate"));
+                        }
+                        self.index += 2;
+                        let low = try!(self.read_hex_code());
+                        if low < 0xdc00 || low >= 0xe000 {
+                            return Err(self.error("expected a low surrogate"));
+                        }
+                        let code = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                        match ::std::char::from_u32(code) {
+                            Some(character) => character,
+                            None => {
+                                return Err(self.error("invalid unicode escape"));
+                            },
+                        }
+                    } else {
+                        match ::std::char::from_u32(high) {
+                            Some(character) => character,
+                            None => {
+                                return Err(self.error("invalid unicode escape"));
+                            },
+                        }
+                    }
+                },
+                _ => {
+                    return Err(self.error("invalid escape sequence"));
+                },
+            };
+        Ok(result)
+    }
+
+    fn read_hex_code(&mut self) -> Result<u32> {
+        let digits = self.text.get(self.index..(self.index + 4));
+        match digits.and_then(|digits| u32::from_str_radix(digits, 16).ok()) {
+            Some(code) if digits.map_or(false, |digits| digits.chars().all(|digit| digit.is_digit(16))) => {
+                self.index += 4;
+                Ok(code)
+            },
+            _ => Err(self.error("expected four hex digits")),
+        }
+    }
+
+    fn read_value(&mut self) -> Result<ExecutionTreeObject> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => {
+                try!(self.enter());
+                let result = self.read_object();
+                self.depth -= 1;
+                result
+            },
+            Some(b'[') => {
+                try!(self.enter());
+                let result = self.read_array();
+                self.depth -= 1;
+                result
+            },
+            Some(b'"') => {
+                let string = try!(self.read_string());
+                Ok(self.convert_string(string))
+            },
+            Some(b't') => self.read_literal("true", ExecutionTreeObject::Bool(true)),
+            Some(b'f') => self.read_literal("false", ExecutionTreeObject::Bool(false)),
+            Some(b'n') => self.read_literal("null", ExecutionTreeObject::nil()),
+            Some(b'-') | Some(b'0'..=b'9') => self.read_number(),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.index += 1;
+        }
+    }
+
+    // A string only becomes a number when it is exactly the literal the writer would produce for it.
+    fn convert_string(&self, string: String) -> ExecutionTreeObject {
+        if self.options.get_preserve_suffixes() {
+            if let Ok(object) = ExecutionTree::parse_atom(&string) {
+                let is_number =
+                    match object {
+                        ExecutionTreeObject::Bool(_) | ExecutionTreeObject::Bytes(_) | ExecutionTreeObject::Char(_) |
+                        ExecutionTreeObject::Node(_) | ExecutionTreeObject::String(_) | ExecutionTreeObject::Symbol(_) => false,
+                        _ => true,
+                    };
+                if is_number && object.to_string().ok().as_ref() == Some(&string) {
+                    return object;
+                }
+            }
+        }
+        ExecutionTreeObject::String(string)
+    }
+}
@@ -0,0 +1,153 @@
+use encoding;
+use error::*;
+use json::JsonOptions;
+use lisp::ExecutionTreeObject;
+use num_traits::Float;
+use num_traits::ToPrimitive;
+use std::fmt::Debug;
+
+pub struct JsonWriter<'a> {
+    options: &'a JsonOptions,
+}
+
+impl <'a> JsonWriter<'a> {
+    pub fn new(options: &'a JsonOptions) -> Self {
+        JsonWriter {
+            options: options,
+        }
+    }
+
+    pub fn write(&self, object: &ExecutionTreeObject) -> Result<String> {
+        let mut result = String::new();
+        try!(self.write_object(object, &mut result));
+        Ok(result)
+    }
+
+    pub fn write_string(string: &str, result: &mut String) {
+        result.push('"');
+        for character in string.chars() {
+            match character {
+                '"' => result.push_str("\\\""),
+                '\\' => result.push_str("\\\\"),
+                '\n' => result.push_str("\\n"),
+                '\r' => result.push_str("\\r"),
+                '\t' => result.push_str("\\t"),
+                '\u{0}'..='\u{1f}' => result.push_str(&format!("\\u{:04x}", character as u32)),
+                character => result.push(character),
+            }
+        }
+        result.push('"');
+    }
+
+    fn get_key(&self, key: &ExecutionTreeObject) -> Result<String> {
+        let result =
+            match key {
+                &ExecutionTreeObject::Char(ref some) => some.to_string(),
+                &ExecutionTreeObject::String(ref some) => some.clone(),
+                &ExecutionTreeObject::Symbol(ref some) => some.trim_start_matches(':').to_string(),
+                &ExecutionTreeObject::BigInt(ref some) => some.to_string(),
+                &ExecutionTreeObject::I8(ref some) => some.to_string(),
+                &ExecutionTreeObject::I16(ref some) => some.to_string(),
+                &ExecutionTreeObject::I32(ref some) => some.to_string(),
+                &ExecutionTreeObject::I64(ref some) => some.to_string(),
+                &ExecutionTreeObject::ISize(ref some) => some.to_string(),
+                &ExecutionTreeObject::U8(ref some) => some.to_string(),
+                &ExecutionTreeObject::U16(ref some) => some.to_string(),
+                &ExecutionTreeObject::U32(ref some) => some.to_string(),
+                &ExecutionTreeObject::U64(ref some) => some.to_string(),
+                &ExecutionTreeObject::USize(ref some) => some.to_string(),
+                _ => {
+                    return Err(ErrorKind::InvalidExecutionTreeObjectConversion(key.enum_to_string().to_string(), "JSON key".to_string()).into());
+                },
+            };
+        Ok(result)
+    }
+
+    fn write_float<T>(&self, value: T, object: &ExecutionTreeObject, result: &mut String) -> Result<()>
+        where T: Debug + Float {
+        if !value.is_finite() {
+            return Err(ErrorKind::InvalidExecutionTreeObjectConversion(try!(object.to_string()), "JSON number".to_string()).into());
+        }
+        result.push_str(&format!("{:?}", value));
+        Ok(())
+    }
+
+    fn write_object(&self, object: &ExecutionTreeObject, result: &mut String) -> Result<()> {
+        if self.options.get_preserve_suffixes() {
+            match object {
+                &ExecutionTreeObject::Bool(_) | &ExecutionTreeObject::Bytes(_) | &ExecutionTreeObject::Char(_) |
+                &ExecutionTreeObject::Exception(_) | &ExecutionTreeObject::Foreign(_) | &ExecutionTreeObject::Map(_) |
+                &ExecutionTreeObject::Node(_) | &ExecutionTreeObject::String(_) | &ExecutionTreeObject::Symbol(_) => { },
+                _ => {
+                    Self::write_string(&try!(object.to_string()), result);
+                    return Ok(());
+                },
+            }
+        }
+
+        match object {
+            &ExecutionTreeObject::BigInt(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::Bool(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::Bytes(ref some) => Self::write_string(&encoding::encode_base64(some), result),
+            &ExecutionTreeObject::Char(ref some) => Self::write_string(&some.to_string(), result),
+            &ExecutionTreeObject::F32(ref some) => try!(self.write_float(*some, object, result)),
+            &ExecutionTreeObject::F64(ref some) => try!(self.write_float(*some, object, result)),
+            &ExecutionTreeObject::I8(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::I16(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::I32(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::I64(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::ISize(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::Map(ref some) => {
+                result.push('{');
+                for (index, entry) in some.sorted_entries().into_iter().enumerate() {
+                    if index != 0 {
+                        result.push(',');
+                    }
+                    Self::write_string(&try!(self.get_key(&entry.0)), result);
+                    result.push(':');
+                    try!(self.write_object(&entry.1, result));
+                }
+                result.push('}');
+            },
+            &ExecutionTreeObject::Node(ref some) => {
+                let objects = some.get_objects();
+                if some.is_keyword_list() {
+                    result.push('{');
+                    for (index, pair) in objects.chunks(2).enumerate() {
+                        if index != 0 {
+                            result.push(',');
+                        }
+                        Self::write_string(&try!(self.get_key(&pair[0])), result);
+                        result.push(':');
+                        try!(self.write_object(&pair[1], result));
+                    }
+                    result.push('}');
+                } else {
+                    result.push('[');
+                    for (index, object) in objects.iter().enumerate() {
+                        if index != 0 {
+                            result.push(',');
+                        }
+                        try!(self.write_object(object, result));
+                    }
+                    result.push(']');
+                }
+            },
+            &ExecutionTreeObject::Rational(ref some) => {
+                let value = some.to_f64().unwrap_or(::std::f64::NAN);
+                try!(self.write_float(value, object, result));
+            },
+            &ExecutionTreeObject::String(ref some) => Self::write_string(some, result),
+            &ExecutionTreeObject::Symbol(ref some) => Self::write_string(some, result),
+            &ExecutionTreeObject::U8(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::U16(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::U32(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::U64(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::USize(ref some) => result.push_str(&some.to_string()),
+            &ExecutionTreeObject::Exception(_) | &ExecutionTreeObject::Foreign(_) => {
+                return Err(ErrorKind::InvalidExecutionTreeObjectConversion(object.enum_to_string().to_string(), "JSON".to_string()).into());
+            },
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+mod json_options;
+
+mod json_reader;
+
+mod json_writer;
+
+pub use json::json_options::DEFAULT_MAX_DEPTH;
+
+pub use json::json_options::JsonFloatType;
+
+pub use json::json_options::JsonIntegerType;
+
+pub use json::json_options::JsonOptions;
+
+pub use json::json_reader::JsonReader;
+
+pub use json::json_writer::JsonWriter;
+
+use error::*;
+use lisp::ExecutionTreeObject;
+
+// Lisp has no separate null, so both null and [] read as (), and () is written back as [].
+pub fn parse(text: &str, options: &JsonOptions) -> Result<ExecutionTreeObject> {
+    JsonReader::new(text, options).read()
+}
+
+pub fn stringify(object: &ExecutionTreeObject, options: &JsonOptions) -> Result<String> {
+    JsonWriter::new(options).write(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use error::*;
+    use json;
+    use json::JsonFloatType;
+    use json::JsonIntegerType;
+    use json::JsonOptions;
+    use lisp::ExecutionTreeObject;
+
+    fn round_trip(text: &str, options: &JsonOptions) -> String {
+        let object = json::parse(text, options).unwrap();
+        json::stringify(&object, options).unwrap()
+    }
+
+    fn get_index(result: Result<ExecutionTreeObject>) -> usize {
+        match result {
+            Err(Error(ErrorKind::InvalidJson(index, _), _)) => index,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_values() {
+        let options = JsonOptions::new();
+        let object = json::parse(" {\"a\": [1, -2.5, true, \"x\"], \"b\": {}} ", &options).unwrap();
+        assert_eq!(object.to_string().unwrap(), "{\"a\" (1i64 -2.5f64 true \"x\") \"b\" {}}");
+        assert_eq!(round_trip("{\"b\":{},\"a\":[1,-2.5,true,\"x\"]}", &options), "{\"a\":[1,-2.5,true,\"x\"],\"b\":{}}");
+    }
+
+    #[test]
+    fn parses_numbers_into_the_configured_types() {
+        let mut options = JsonOptions::new();
+        options.set_integer_type(JsonIntegerType::I32);
+        options.set_float_type(JsonFloatType::Rational);
+        assert_eq!(json::parse("5", &options).unwrap(), ExecutionTreeObject::I32(5));
+        assert_eq!(json::parse("5000000000", &options).unwrap().to_string().unwrap(), "5000000000n");
+        assert_eq!(json::parse("1.25e1", &options).unwrap().to_string().unwrap(), "25/2");
+        assert_eq!(get_index(json::parse("1e99999", &options)), 7);
+
+        options.set_float_type(JsonFloatType::F32);
+        assert_eq!(json::parse("0.1", &options).unwrap(), ExecutionTreeObject::F32(0.1));
+    }
+
+    #[test]
+    fn writes_f32_without_widening() {
+        let options = JsonOptions::new();
+        assert_eq!(json::stringify(&ExecutionTreeObject::F32(0.1), &options).unwrap(), "0.1");
+        assert!(json::stringify(&ExecutionTreeObject::F32(::std::f32::NAN), &options).is_err());
+    }
+
+    #[test]
+    fn strings_round_trip_escapes() {
+        let options = JsonOptions::new();
+        let object = json::parse("\"a\\\"\\\\\\n\\u0001\\u00e9\\ud83d\\ude00\"", &options).unwrap();
+        assert_eq!(object, ExecutionTreeObject::String("a\"\\\n\u{1}\u{e9}\u{1f600}".to_string()));
+        assert_eq!(json::stringify(&object, &options).unwrap(), "\"a\\\"\\\\\\n\\u0001\u{e9}\u{1f600}\"");
+        assert_eq!(get_index(json::parse("\"\\ud83d\"", &options)), 7);
+        assert_eq!(get_index(json::parse("\"\\q\"", &options)), 3);
+        assert_eq!(get_index(json::parse("\"a", &options)), 2);
+    }
+
+    #[test]
+    fn null_and_empty_arrays_both_read_as_nil() {
+        let options = JsonOptions::new();
+        assert_eq!(json::parse("null", &options).unwrap(), ExecutionTreeObject::nil());
+        assert_eq!(json::parse("[]", &options).unwrap(), ExecutionTreeObject::nil());
+        assert_eq!(round_trip("null", &options), "[]");
+    }
+
+    #[test]
+    fn nesting_deeper_than_the_maximum_depth_is_rejected() {
+        let mut options = JsonOptions::new();
+        options.set_max_depth(3);
+        assert_eq!(round_trip("[[{\"a\":1}]]", &options), "[[{\"a\":1}]]");
+        assert_eq!(get_index(json::parse("[[[[1]]]]", &options)), 3);
+
+        let text = "[".repeat(200000);
+        assert_eq!(get_index(json::parse(&text, &JsonOptions::new())), json::DEFAULT_MAX_DEPTH);
+    }
+
+    #[test]
+    fn suffixes_are_preserved_in_strings() {
+        let mut options = JsonOptions::new();
+        options.set_preserve_suffixes(true);
+        assert_eq!(round_trip("[\"5i32\",\"1/3\",\"5\",\"05i32\"]", &options), "[\"5i32\",\"1/3\",\"5\",\"05i32\"]");
+        assert_eq!(json::parse("\"5u8\"", &options).unwrap(), ExecutionTreeObject::U8(5));
+        assert_eq!(json::parse("\"05i32\"", &options).unwrap(), ExecutionTreeObject::String("05i32".to_string()));
+    }
+}
@@ -1,14 +1,71 @@
+//! A simple lisp interpreter.
+//!
+//! # Errors
+//!
+//! Runtime errors raised while evaluating a form keep their own `ErrorKind`, so callers can match on
+//! `Error::kind`. The head of each enclosing form and, when the form came from parsed source, its
+//! `line:column` are recorded next to the error and returned by `Error::get_lisp_backtrace`:
+//!
+//! ```
+//! use simplisp::*;
+//!
+//! let mut environment = Environment::<()>::new();
+//! builtin::control::register(environment.get_global_frame());
+//! builtin::definition::register(environment.get_global_frame());
+//! let error = unsafe { environment.parse_and_execute(&(), "(begin (define x))") }.unwrap_err();
+//! match error.kind() {
+//!     &ErrorKind::InvalidArgumentCount(..) => { },
+//!     kind => panic!("unexpected error kind, {}", kind.enum_to_string()),
+//! }
+//! assert_eq!(error.get_lisp_backtrace().unwrap(), &vec!["define at 1:8".to_string(), "begin at 1:1".to_string()]);
+//! ```
+
 #![recursion_limit="200"]
 
 #[macro_use]
 extern crate error_chain;
 
+extern crate num_bigint;
+
+extern crate num_integer;
+
+extern crate num_rational;
+
+extern crate num_traits;
+
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+#[cfg(feature = "export")]
+extern crate simplisp_export;
+
+pub mod builtin;
+
+pub mod data;
+
+pub mod encoding;
+
 pub mod error;
 
+pub mod json;
+
 pub mod lisp;
 
+pub mod schema;
+
+#[cfg(feature = "serde")]
+pub mod serialization;
+
 pub mod string;
 
 pub use error::*;
 
 pub use lisp::*;
+
+pub use num_bigint::BigInt;
+
+pub use num_rational::BigRational;
+
+#[cfg(feature = "export")]
+pub use simplisp_export::export;
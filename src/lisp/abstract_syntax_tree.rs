@@ -4,6 +4,7 @@ use lisp::AbstractSyntaxTreeObject;
 use lisp::LastCharType;
 
 pub struct AbstractSyntaxTree<'a> {
+    program_text: &'a str,
     root: AbstractSyntaxTreeNode<'a>,
 }
 
@@ -11,16 +12,25 @@ impl <'a> AbstractSyntaxTree<'a> {
     pub fn new(program_text: &'a str) -> Result<Self> {
         let result =
             AbstractSyntaxTree {
+                program_text: program_text,
                 root: try!(Self::parse_program_text(program_text)),
             };
         Ok(result)
     }
 
+    pub fn get_program_text(&self) -> &'a str {
+        self.program_text
+    }
+
     pub fn get_root(&self) -> &AbstractSyntaxTreeNode<'a> {
         &self.root
     }
 
-    fn parse<TIterator>(enumerated_text: &mut TIterator, program_text: &'a str) -> Result<AbstractSyntaxTreeNode<'a>>
+    fn is_string_prefix(word: &str) -> bool {
+        word == "b" || word == "#x"
+    }
+
+    fn parse<TIterator>(start_index: usize, enumerated_text: &mut TIterator, program_text: &'a str, depth: usize, max_depth: usize) -> Result<AbstractSyntaxTreeNode<'a>>
         where TIterator: Iterator<Item = (usize, char)> {
         let mut objects = Vec::new();
 
@@ -37,7 +47,11 @@ impl <'a> AbstractSyntaxTree<'a> {
                         _ => { },
                     }
 
-                    let inner_node = try!(Self::parse(enumerated_text, program_text));
+                    if depth >= max_depth {
+                        return Err(ErrorKind::NestingDepthExceeded(max_depth, index).into());
+                    }
+
+                    let inner_node = try!(Self::parse(index, enumerated_text, program_text, depth + 1, max_depth));
                     let node_object = AbstractSyntaxTreeObject::Node(inner_node);
                     objects.push(node_object);
 
@@ -45,7 +59,7 @@ impl <'a> AbstractSyntaxTree<'a> {
                 },
                 ')' => {
                     Self::try_end_current_word(&mut current_word_start, index, &mut objects, program_text);
-                    return Ok(AbstractSyntaxTreeNode::new(objects));
+                    return Ok(AbstractSyntaxTreeNode::with_offsets(objects, start_index, index + 1));
                 },
                 character => {
                     if character.is_whitespace() {
@@ -61,8 +75,15 @@ impl <'a> AbstractSyntaxTree<'a> {
                         }
 
                         match current_word_start {
-                            Some(_) => {
+                            Some(word_start) => {
                                 match character {
+                                    '"' if Self::is_string_prefix(&program_text[word_start..index]) => {
+                                        let string_object = try!(Self::parse_double_quoted_text(word_start, enumerated_text, program_text));
+                                        objects.push(string_object);
+                                        current_word_start = None;
+                                        last_char_type = LastCharType::Quote;
+                                        continue;
+                                    },
                                     '"' | '\'' => {
                                         return Err(ErrorKind::BeginningStringInWord(character, index).into());
                                     },
@@ -109,7 +130,7 @@ impl <'a> AbstractSyntaxTree<'a> {
                 let string_object = AbstractSyntaxTreeObject::String(word);
                 return Ok(string_object);
             } else if character == '\\' {
-                is_escaped = true;
+                is_escaped = !is_escaped;
             } else {
                 is_escaped = false;
             }
@@ -119,12 +140,17 @@ impl <'a> AbstractSyntaxTree<'a> {
     }
 
     pub fn parse_program_text(program_text: &'a str) -> Result<AbstractSyntaxTreeNode<'a>> {
+        Self::parse_program_text_with_max_depth(program_text, usize::max_value())
+    }
+
+    // The root list is at depth zero, so a max depth of one allows lists directly inside the root only.
+    pub fn parse_program_text_with_max_depth(program_text: &'a str, max_depth: usize) -> Result<AbstractSyntaxTreeNode<'a>> {
         if program_text.as_bytes().get(0) != Some(&b'(') {
             Err(ErrorKind::NoProgramStartParenthesis(program_text.to_string()).into())
         } else {
             let mut enumerated_text = program_text.char_indices();
             enumerated_text.next();
-            let root = try!(Self::parse(&mut enumerated_text, program_text));
+            let root = try!(Self::parse(0, &mut enumerated_text, program_text, 0, max_depth));
             Ok(root)
         }
     }
@@ -140,7 +166,7 @@ impl <'a> AbstractSyntaxTree<'a> {
                 let string_object = AbstractSyntaxTreeObject::String(word);
                 return Ok(string_object);
             } else if character == '\\' {
-                is_escaped = true;
+                is_escaped = !is_escaped;
             } else {
                 is_escaped = false;
             }
@@ -161,3 +187,27 @@ impl <'a> AbstractSyntaxTree<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use lisp::AbstractSyntaxTree;
+    use lisp::AbstractSyntaxTreeObject;
+
+    fn words(program_text: &str) -> Vec<&str> {
+        let ast = AbstractSyntaxTree::new(program_text).unwrap();
+        ast.get_root().get_objects().iter().map(|object| {
+            match object {
+                &AbstractSyntaxTreeObject::String(word) => word,
+                &AbstractSyntaxTreeObject::Node(_) => "#<node>",
+            }
+        }).collect()
+    }
+
+    #[test]
+    fn escaped_backslash_does_not_escape_the_closing_quote() {
+        assert_eq!(words("(\"a\\\\\" x)"), vec!["\"a\\\\\"", "x"]);
+        assert_eq!(words("('a\\\\' x)"), vec!["'a\\\\'", "x"]);
+        assert_eq!(words("(\"a\\\"\" x)"), vec!["\"a\\\"\"", "x"]);
+        assert!(AbstractSyntaxTree::new("(\"a\\\")").is_err());
+    }
+}
@@ -2,17 +2,33 @@ use lisp::AbstractSyntaxTreeObject;
 
 #[derive(Debug)]
 pub struct AbstractSyntaxTreeNode<'a> {
+    end: usize,
     objects: Vec<AbstractSyntaxTreeObject<'a>>,
+    start: usize,
 }
 
 impl <'a> AbstractSyntaxTreeNode<'a> {
     pub fn new(objects: Vec<AbstractSyntaxTreeObject<'a>>) -> Self {
+        Self::with_offsets(objects, 0, 0)
+    }
+
+    pub fn with_offsets(objects: Vec<AbstractSyntaxTreeObject<'a>>, start: usize, end: usize) -> Self {
         AbstractSyntaxTreeNode {
+            end: end,
             objects: objects,
+            start: start,
         }
     }
 
+    pub fn get_end(&self) -> usize {
+        self.end
+    }
+
     pub fn get_objects(&self) -> &Vec<AbstractSyntaxTreeObject<'a>> {
         &self.objects
     }
+
+    pub fn get_start(&self) -> usize {
+        self.start
+    }
 }
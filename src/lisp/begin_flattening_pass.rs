@@ -0,0 +1,58 @@
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::OptimizationPass;
+use lisp::Optimizer;
+
+pub struct BeginFlatteningPass;
+
+impl <TArg> OptimizationPass<TArg> for BeginFlatteningPass {
+    unsafe fn rewrite(&self, _: &TArg, environment: &mut Environment<TArg>, optimizer: &Optimizer<TArg>, object: ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        let is_begin = |object: &ExecutionTreeObject, environment: &mut Environment<TArg>| {
+            match optimizer.get_form_head(object) {
+                Some(symbol) => symbol == optimizer.get_begin_symbol() && optimizer.is_builtin(environment, symbol),
+                None => false,
+            }
+        };
+        if !is_begin(&object, environment) {
+            return Ok(object);
+        }
+
+        let (mut objects, span) =
+            match object {
+                ExecutionTreeObject::Node(node) => {
+                    let span = node.get_span().cloned();
+                    (node.into_objects().into_iter(), span)
+                },
+                other => {
+                    return Ok(other);
+                },
+            };
+
+        let mut result = Vec::new();
+        if let Some(head) = objects.next() {
+            result.push(head);
+        }
+        for object in objects {
+            let is_inner_begin =
+                is_begin(&object, environment) &&
+                match &object {
+                    &ExecutionTreeObject::Node(ref node) => node.get_objects().len() > 1,
+                    _ => false,
+                };
+            if is_inner_begin {
+                if let ExecutionTreeObject::Node(node) = object {
+                    result.extend(node.into_objects().into_iter().skip(1));
+                }
+            } else {
+                result.push(object);
+            }
+        }
+
+        if result.len() == 2 {
+            Ok(result.swap_remove(1))
+        } else {
+            Ok(ExecutionTreeObject::Node(optimizer.create_node(result, span)))
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use lisp::ExecutionTreeObject;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Breakpoint {
+    // A line of the source with the given id, or of any source when there is no id.
+    Line(Option<usize>, usize),
+    Symbol(String),
+}
+
+impl Breakpoint {
+    pub fn is_match(&self, object: &ExecutionTreeObject) -> bool {
+        let node =
+            match object {
+                &ExecutionTreeObject::Node(ref node) => node,
+                _ => {
+                    return false;
+                },
+            };
+
+        match self {
+            &Breakpoint::Line(source_id, line) => {
+                match node.get_span() {
+                    Some(span) => span.get_line() == line && source_id.map_or(true, |source_id| source_id == span.get_source_id()),
+                    None => false,
+                }
+            },
+            &Breakpoint::Symbol(ref symbol) => {
+                match node.get_objects().first() {
+                    Some(&ExecutionTreeObject::Symbol(ref head)) => head == symbol,
+                    _ => false,
+                }
+            },
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            &Breakpoint::Line(Some(source_id), line) => format!("line {} of source {}", line, source_id),
+            &Breakpoint::Line(None, line) => format!("line {}", line),
+            &Breakpoint::Symbol(ref symbol) => format!("symbol {}", symbol),
+        }
+    }
+}
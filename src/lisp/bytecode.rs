@@ -0,0 +1,146 @@
+use lisp::ExecutionTree;
+use lisp::ExecutionTreeObject;
+use lisp::Instruction;
+use std::collections::BTreeMap;
+
+// Built-in functions receive their argument forms unevaluated, so a call to one keeps its arguments
+// as forms and they are evaluated by the environment. Only lists and symbol lookups around those
+// calls are compiled to instructions, which saves the recursion over those lists and resolves their
+// symbols to slots cached from the global frame. Symbols bound in any other frame are looked up
+// through the environment as before. Every form the tree walker would evaluate is still traced through
+// the environment, so coverage, the debugger, evaluation hooks, the profiler and the maximum call
+// depth see the same forms in both.
+pub struct Bytecode {
+    constants: Vec<ExecutionTreeObject>,
+    forms: Vec<Vec<ExecutionTreeObject>>,
+    instructions: Vec<Instruction>,
+    symbol_slots: BTreeMap<String, usize>,
+    symbols: Vec<String>,
+    traced_objects: Vec<ExecutionTreeObject>,
+}
+
+impl Bytecode {
+    pub fn new() -> Self {
+        Bytecode {
+            constants: Vec::new(),
+            forms: Vec::new(),
+            instructions: Vec::new(),
+            symbol_slots: BTreeMap::new(),
+            symbols: Vec::new(),
+            traced_objects: Vec::new(),
+        }
+    }
+
+    pub fn compile(execution_tree: &ExecutionTree) -> Self {
+        let root = ExecutionTreeObject::Node(execution_tree.get_root().clone());
+        Self::compile_object(&root)
+    }
+
+    pub fn compile_object(object: &ExecutionTreeObject) -> Self {
+        let mut result = Self::new();
+        result.visit_object(object);
+        result
+    }
+
+    pub fn get_constants(&self) -> &Vec<ExecutionTreeObject> {
+        &self.constants
+    }
+
+    pub fn get_forms(&self) -> &Vec<Vec<ExecutionTreeObject>> {
+        &self.forms
+    }
+
+    pub fn get_instructions(&self) -> &Vec<Instruction> {
+        &self.instructions
+    }
+
+    pub fn get_symbols(&self) -> &Vec<String> {
+        &self.symbols
+    }
+
+    pub fn get_traced_objects(&self) -> &Vec<ExecutionTreeObject> {
+        &self.traced_objects
+    }
+
+    fn push_constant(&mut self, object: ExecutionTreeObject) -> usize {
+        self.constants.push(object);
+        self.constants.len() - 1
+    }
+
+    fn push_forms(&mut self, objects: Vec<ExecutionTreeObject>) -> usize {
+        self.forms.push(objects);
+        self.forms.len() - 1
+    }
+
+    fn push_symbol(&mut self, symbol: &str) -> usize {
+        if let Some(slot) = self.symbol_slots.get(symbol) {
+            return *slot;
+        }
+
+        let slot = self.symbols.len();
+        self.symbols.push(symbol.to_string());
+        self.symbol_slots.insert(symbol.to_string(), slot);
+        slot
+    }
+
+    fn push_traced_object(&mut self, object: &ExecutionTreeObject) -> usize {
+        self.traced_objects.push(object.clone());
+        self.traced_objects.len() - 1
+    }
+
+    fn visit_object(&mut self, object: &ExecutionTreeObject) {
+        match object {
+            &ExecutionTreeObject::Node(ref node) => {
+                let trace = self.push_traced_object(object);
+                self.instructions.push(Instruction::Trace(trace));
+                let objects = node.get_objects();
+                match objects.len() {
+                    0 => {
+                        let constant = self.push_constant(ExecutionTreeObject::nil());
+                        self.instructions.push(Instruction::Constant(constant));
+                    },
+                    1 => {
+                        self.visit_object(&objects[0]);
+                    },
+                    len => {
+                        match &objects[0] {
+                            &ExecutionTreeObject::Symbol(ref symbol) => {
+                                let slot = self.push_symbol(symbol);
+                                let forms = self.push_forms(objects[1..].to_vec());
+                                let call_index = self.instructions.len();
+                                self.instructions.push(Instruction::CallSymbol(slot, forms, trace, 0));
+                                for object in &objects[1..] {
+                                    self.visit_object(object);
+                                }
+                                self.instructions.push(Instruction::MakeList(len));
+                                let skip = self.instructions.len() - call_index - 1;
+                                self.instructions[call_index] = Instruction::CallSymbol(slot, forms, trace, skip);
+                            },
+                            _ => {
+                                for object in objects {
+                                    self.visit_object(object);
+                                }
+                                self.instructions.push(Instruction::MakeList(len));
+                            },
+                        }
+                    },
+                }
+                self.instructions.push(Instruction::ExitTrace(trace));
+            },
+            &ExecutionTreeObject::Symbol(ref symbol) => {
+                let trace = self.push_traced_object(object);
+                self.instructions.push(Instruction::Trace(trace));
+                let slot = self.push_symbol(symbol);
+                self.instructions.push(Instruction::LoadSymbol(slot));
+                self.instructions.push(Instruction::ExitTrace(trace));
+            },
+            other => {
+                let trace = self.push_traced_object(object);
+                self.instructions.push(Instruction::Trace(trace));
+                let constant = self.push_constant(other.clone());
+                self.instructions.push(Instruction::Constant(constant));
+                self.instructions.push(Instruction::ExitTrace(trace));
+            },
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::OptimizationPass;
+use lisp::Optimizer;
+
+pub struct ConstantFoldingPass;
+
+impl <TArg> OptimizationPass<TArg> for ConstantFoldingPass {
+    unsafe fn rewrite(&self, arg: &TArg, environment: &mut Environment<TArg>, optimizer: &Optimizer<TArg>, object: ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        let is_foldable =
+            match optimizer.get_form_head(&object) {
+                Some(symbol) => {
+                    environment.is_pure(symbol) &&
+                    optimizer.is_builtin(environment, symbol)
+                },
+                None => false,
+            };
+
+        let is_foldable =
+            is_foldable &&
+            match &object {
+                &ExecutionTreeObject::Node(ref node) => node.get_objects()[1..].iter().all(|object| optimizer.is_literal(object)),
+                _ => false,
+            };
+
+        if is_foldable {
+            if let Ok(result) = environment.evaluate_untraced(arg, &object) {
+                if optimizer.is_literal(&result) {
+                    return Ok(result);
+                }
+            }
+        }
+
+        Ok(object)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use lisp::AbstractSyntaxTree;
+    use lisp::Environment;
+    use lisp::EvaluationHook;
+    use lisp::ExecutionTree;
+    use lisp::ExecutionTreeObject;
+    use lisp::Optimizer;
+    use lisp::Profiler;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct CountingHook {
+        forms: Rc<Cell<usize>>,
+    }
+
+    impl EvaluationHook for CountingHook {
+        fn on_form_enter(&mut self, _: &ExecutionTreeObject) {
+            self.forms.set(self.forms.get() + 1);
+        }
+    }
+
+    #[test]
+    fn folding_does_not_trace_evaluation() {
+        let forms = Rc::new(Cell::new(0));
+        let mut environment = Environment::<()>::new();
+        builtin::arithmetic::register(environment.get_global_frame());
+        environment.mark_pure("+".to_string());
+        environment.add_evaluation_hook(Box::new(CountingHook { forms: forms.clone() }));
+        environment.set_profiler(Some(Profiler::new()));
+
+        let ast = AbstractSyntaxTree::new("(+ 1 (+ 2 3))").unwrap();
+        let execution_tree = ExecutionTree::new(&ast).unwrap();
+        let optimizer = Optimizer::with_default_passes();
+        let execution_tree = unsafe { optimizer.optimize(&(), &mut environment, execution_tree) }.unwrap();
+
+        assert_eq!(ExecutionTreeObject::Node(execution_tree.get_root().clone()).to_string().unwrap(), "(6i32)");
+        assert_eq!(forms.get(), 0);
+        assert!(environment.get_profiler().unwrap().get_entry("+").is_none());
+    }
+}
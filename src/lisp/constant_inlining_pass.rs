@@ -0,0 +1,28 @@
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::OptimizationPass;
+use lisp::Optimizer;
+use lisp::Symbol;
+
+pub struct ConstantInliningPass;
+
+impl <TArg> OptimizationPass<TArg> for ConstantInliningPass {
+    unsafe fn rewrite(&self, _: &TArg, environment: &mut Environment<TArg>, optimizer: &Optimizer<TArg>, object: ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        let value =
+            match &object {
+                &ExecutionTreeObject::Symbol(ref symbol) if environment.is_constant(symbol) => {
+                    match environment.get_global_frame().try_get(symbol) {
+                        Some(Symbol::Object(value)) => Some(value),
+                        _ => None,
+                    }
+                },
+                _ => None,
+            };
+
+        match value {
+            Some(value) if optimizer.is_literal(&value) => Ok(value),
+            _ => Ok(object),
+        }
+    }
+}
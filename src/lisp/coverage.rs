@@ -0,0 +1,273 @@
+use lisp::ExecutionTree;
+use lisp::ExecutionTreeNode;
+use lisp::ExecutionTreeObject;
+use lisp::Span;
+use std::collections::BTreeMap;
+
+// Forms are keyed by the source id and offsets of their spans, and forms that were not registered
+// from this source, such as those of imported modules, are not counted.
+pub struct Coverage {
+    branch_counts: BTreeMap<(usize, usize, usize), (Span, Vec<usize>)>,
+    branch_forms: Vec<Option<(usize, usize, usize)>>,
+    form_counts: BTreeMap<(usize, usize, usize), (Span, usize)>,
+    source_name: String,
+    source_text: String,
+}
+
+impl Coverage {
+    pub fn new(source_name: String, source_text: String) -> Self {
+        Coverage {
+            branch_counts: BTreeMap::new(),
+            branch_forms: Vec::new(),
+            form_counts: BTreeMap::new(),
+            source_name: source_name,
+            source_text: source_text,
+        }
+    }
+
+    // Called before a form is evaluated. Returns whether the form is a registered branch form, in
+    // which case exit_branch_form must be called after it is evaluated.
+    pub fn enter_branch_form(&mut self, node: &ExecutionTreeNode) -> bool {
+        if let Some(span) = node.get_span() {
+            let key = Self::get_key(span);
+            if self.branch_counts.contains_key(&key) {
+                self.branch_forms.push(Some(key));
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn exit_branch_form(&mut self) {
+        self.branch_forms.pop();
+    }
+
+    pub fn get_branch_count(&self, span: &Span, branch: usize) -> Option<usize> {
+        match self.branch_counts.get(&Self::get_key(span)) {
+            Some(&(_, ref counts)) => counts.get(branch).cloned(),
+            None => None,
+        }
+    }
+
+    pub fn get_form_count(&self, span: &Span) -> Option<usize> {
+        match self.form_counts.get(&Self::get_key(span)) {
+            Some(&(_, count)) => Some(count),
+            None => None,
+        }
+    }
+
+    pub fn get_line_counts(&self) -> BTreeMap<usize, usize> {
+        let mut result = BTreeMap::new();
+        for &(ref span, count) in self.form_counts.values() {
+            let line_count = result.entry(span.get_line()).or_insert(0);
+            if count > *line_count {
+                *line_count = count;
+            }
+        }
+        result
+    }
+
+    pub fn get_source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    pub fn get_source_text(&self) -> &str {
+        &self.source_text
+    }
+
+    pub fn if_str() -> &'static str {
+        "if"
+    }
+
+    pub fn quote_str() -> &'static str {
+        "quote"
+    }
+
+    pub fn record(&mut self, object: &ExecutionTreeObject) {
+        if let &ExecutionTreeObject::Node(ref node) = object {
+            if let Some(span) = node.get_span() {
+                if let Some(entry) = self.form_counts.get_mut(&Self::get_key(span)) {
+                    entry.1 += 1;
+                }
+            }
+        }
+    }
+
+    // Records the branch taken by the innermost branch form being evaluated. Only the first branch
+    // recorded for each evaluation of the form counts.
+    pub fn record_branch(&mut self, branch: usize) {
+        let key =
+            match self.branch_forms.last_mut() {
+                Some(key) => key.take(),
+                None => None,
+            };
+        if let Some(key) = key {
+            if let Some(&mut (_, ref mut counts)) = self.branch_counts.get_mut(&key) {
+                if let Some(count) = counts.get_mut(branch) {
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    pub fn register_tree(&mut self, execution_tree: &ExecutionTree) {
+        self.register_node(execution_tree.get_root());
+    }
+
+    pub fn to_annotated_report(&self) -> String {
+        let line_counts = self.get_line_counts();
+        let uncovered_lines = self.get_uncovered_lines();
+        let mut result = String::new();
+        for (index, line) in self.source_text.lines().enumerate() {
+            let line_number = index + 1;
+            let annotation =
+                match line_counts.get(&line_number) {
+                    Some(&0) => "#####".to_string(),
+                    Some(&count) if uncovered_lines.contains_key(&line_number) => format!("{}*", count),
+                    Some(&count) => count.to_string(),
+                    None => "-".to_string(),
+                };
+            result.push_str(&format!("{:>9}:{:>5}:{}\n", annotation, line_number, line));
+        }
+        result
+    }
+
+    pub fn to_lcov(&self) -> String {
+        let line_counts = self.get_line_counts();
+        let mut result = String::new();
+        result.push_str("TN:\n");
+        result.push_str(&format!("SF:{}\n", self.source_name));
+        let mut branches_found = 0;
+        let mut branches_hit = 0;
+        for (block, (key, &(ref span, ref counts))) in self.branch_counts.iter().enumerate() {
+            let is_form_hit =
+                match self.form_counts.get(key) {
+                    Some(&(_, count)) => count != 0,
+                    None => false,
+                };
+            for (branch, count) in counts.iter().enumerate() {
+                branches_found += 1;
+                if *count != 0 {
+                    branches_hit += 1;
+                }
+                let taken =
+                    if is_form_hit {
+                        count.to_string()
+                    } else {
+                        "-".to_string()
+                    };
+                result.push_str(&format!("BRDA:{},{},{},{}\n", span.get_line(), block, branch, taken));
+            }
+        }
+        result.push_str(&format!("BRF:{}\n", branches_found));
+        result.push_str(&format!("BRH:{}\n", branches_hit));
+        let mut lines_hit = 0;
+        for (line, count) in &line_counts {
+            if *count != 0 {
+                lines_hit += 1;
+            }
+            result.push_str(&format!("DA:{},{}\n", line, count));
+        }
+        result.push_str(&format!("LF:{}\n", line_counts.len()));
+        result.push_str(&format!("LH:{}\n", lines_hit));
+        result.push_str("end_of_record\n");
+        result
+    }
+
+    fn get_key(span: &Span) -> (usize, usize, usize) {
+        (span.get_source_id(), span.get_start(), span.get_end())
+    }
+
+    fn get_uncovered_lines(&self) -> BTreeMap<usize, usize> {
+        let mut result = BTreeMap::new();
+        for &(ref span, count) in self.form_counts.values() {
+            if count == 0 {
+                *result.entry(span.get_line()).or_insert(0) += 1;
+            }
+        }
+        result
+    }
+
+    fn register_node(&mut self, node: &ExecutionTreeNode) {
+        let objects = node.get_objects();
+        if let Some(span) = node.get_span() {
+            self.form_counts.entry(Self::get_key(span)).or_insert((*span, 0));
+
+            if let Some(&ExecutionTreeObject::Symbol(ref head)) = objects.first() {
+                if head == Self::if_str() && (objects.len() == 3 || objects.len() == 4) {
+                    self.branch_counts.entry(Self::get_key(span)).or_insert((*span, vec![0, 0]));
+                }
+            }
+        }
+
+        if let Some(&ExecutionTreeObject::Symbol(ref head)) = objects.first() {
+            if head == Self::quote_str() {
+                return;
+            }
+        }
+
+        for object in objects {
+            if let &ExecutionTreeObject::Node(ref child) = object {
+                self.register_node(child);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use lisp::AbstractSyntaxTree;
+    use lisp::Coverage;
+    use lisp::Environment;
+    use lisp::ExecutionTree;
+
+    fn parse(source: &str) -> ExecutionTree {
+        let ast = AbstractSyntaxTree::new(source).unwrap();
+        ExecutionTree::new(&ast).unwrap()
+    }
+
+    fn new_environment(source: &str, execution_tree: &ExecutionTree) -> Environment<()> {
+        let mut coverage = Coverage::new("test.lisp".to_string(), source.to_string());
+        coverage.register_tree(execution_tree);
+        let mut environment = Environment::new();
+        builtin::control::register(environment.get_global_frame());
+        environment.set_coverage(Some(coverage));
+        environment
+    }
+
+    #[test]
+    fn records_branches_of_atom_if_forms() {
+        let source = "(begin\n  (if true 1 2)\n  (if false 1 2)\n  (if false 1))";
+        let execution_tree = parse(source);
+        let mut environment = new_environment(source, &execution_tree);
+        unsafe { environment.execute(&(), execution_tree) }.unwrap();
+
+        let lcov = environment.get_coverage().unwrap().to_lcov();
+        let branch_lines: Vec<&str> = lcov.lines().filter(|line| line.starts_with("BR")).collect();
+        assert_eq!(branch_lines, vec![
+            "BRDA:2,0,0,1", "BRDA:2,0,1,0",
+            "BRDA:3,1,0,0", "BRDA:3,1,1,1",
+            "BRDA:4,2,0,0", "BRDA:4,2,1,1",
+            "BRF:6", "BRH:3",
+        ]);
+    }
+
+    #[test]
+    fn forms_from_other_sources_are_not_counted() {
+        let source = "(if true 1 2)";
+        let registered_tree = parse(source);
+        let mut environment = new_environment(source, &registered_tree);
+        unsafe { environment.execute(&(), parse(source)) }.unwrap();
+
+        let coverage = environment.get_coverage().unwrap();
+        let span = *registered_tree.get_root().get_span().unwrap();
+        assert_eq!(coverage.get_form_count(&span), Some(0));
+        assert_eq!(coverage.get_branch_count(&span, 0), Some(0));
+
+        unsafe { environment.execute(&(), registered_tree) }.unwrap();
+        let coverage = environment.get_coverage().unwrap();
+        assert_eq!(coverage.get_form_count(&span), Some(1));
+        assert_eq!(coverage.get_branch_count(&span, 0), Some(1));
+    }
+}
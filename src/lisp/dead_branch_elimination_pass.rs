@@ -0,0 +1,45 @@
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::OptimizationPass;
+use lisp::Optimizer;
+
+pub struct DeadBranchEliminationPass;
+
+impl <TArg> OptimizationPass<TArg> for DeadBranchEliminationPass {
+    unsafe fn rewrite(&self, _: &TArg, environment: &mut Environment<TArg>, optimizer: &Optimizer<TArg>, object: ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        let is_if =
+            match optimizer.get_form_head(&object) {
+                Some(symbol) => symbol == optimizer.get_if_symbol() && optimizer.is_builtin(environment, symbol),
+                None => false,
+            };
+        if !is_if {
+            return Ok(object);
+        }
+
+        let (mut objects, span) =
+            match object {
+                ExecutionTreeObject::Node(node) => {
+                    let span = node.get_span().cloned();
+                    (node.into_objects(), span)
+                },
+                other => {
+                    return Ok(other);
+                },
+            };
+
+        let objects_len = objects.len();
+        let condition =
+            match objects.get(1) {
+                Some(&ExecutionTreeObject::Bool(condition)) if objects_len == 3 || objects_len == 4 => Some(condition),
+                _ => None,
+            };
+
+        match condition {
+            Some(true) => Ok(objects.swap_remove(2)),
+            Some(false) if objects_len == 4 => Ok(objects.swap_remove(3)),
+            Some(false) => Ok(ExecutionTreeObject::nil()),
+            None => Ok(ExecutionTreeObject::Node(optimizer.create_node(objects, span))),
+        }
+    }
+}
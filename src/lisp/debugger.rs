@@ -0,0 +1,201 @@
+use lisp::Breakpoint;
+use lisp::DebuggerAction;
+use lisp::ExecutionTreeObject;
+use lisp::PauseReason;
+
+pub struct Debugger {
+    action: DebuggerAction,
+    action_depth: usize,
+    breakpoints: Vec<Breakpoint>,
+    entered_line: Option<(usize, usize)>,
+}
+
+impl Debugger {
+    pub fn new() -> Self {
+        Debugger {
+            action: DebuggerAction::Continue,
+            action_depth: 0,
+            breakpoints: Vec::new(),
+            entered_line: None,
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    // Forgets the line evaluation last entered, so that a new top-level evaluation entering the same
+    // line triggers its line breakpoints again.
+    pub fn clear_entered_line(&mut self) {
+        self.entered_line = None;
+    }
+
+    pub fn get_action(&self) -> DebuggerAction {
+        self.action
+    }
+
+    pub fn get_breakpoints(&self) -> &Vec<Breakpoint> {
+        &self.breakpoints
+    }
+
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        let len = self.breakpoints.len();
+        self.breakpoints.retain(|item| item != breakpoint);
+        self.breakpoints.len() != len
+    }
+
+    pub fn resume(&mut self, action: DebuggerAction, depth: usize) {
+        self.action = action;
+        self.action_depth = depth;
+    }
+
+    pub fn try_pause(&mut self, object: &ExecutionTreeObject, depth: usize) -> Option<PauseReason> {
+        let is_form =
+            match object {
+                &ExecutionTreeObject::Node(ref node) => !node.get_objects().is_empty(),
+                _ => false,
+            };
+        if !is_form {
+            return None;
+        }
+
+        // Line breakpoints trigger when evaluation enters the line, not again for each form nested on it.
+        let line =
+            match object {
+                &ExecutionTreeObject::Node(ref node) => node.get_span().map(|span| (span.get_source_id(), span.get_line())),
+                _ => None,
+            };
+        let is_line_entry = line.is_some() && line != self.entered_line;
+        if line.is_some() {
+            self.entered_line = line;
+        }
+
+        for breakpoint in &self.breakpoints {
+            let is_enabled =
+                match breakpoint {
+                    &Breakpoint::Line(..) => is_line_entry,
+                    &Breakpoint::Symbol(_) => true,
+                };
+            if is_enabled && breakpoint.is_match(object) {
+                return Some(PauseReason::Breakpoint(breakpoint.clone()));
+            }
+        }
+
+        let is_step =
+            match self.action {
+                DebuggerAction::Continue => false,
+                DebuggerAction::StepInto => true,
+                DebuggerAction::StepOut => depth < self.action_depth,
+                DebuggerAction::StepOver => depth <= self.action_depth,
+            };
+        if is_step {
+            Some(PauseReason::Step)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use error::*;
+    use lisp::AbstractSyntaxTree;
+    use lisp::Breakpoint;
+    use lisp::Bytecode;
+    use lisp::Debugger;
+    use lisp::DebuggerAction;
+    use lisp::DebuggerFrontEnd;
+    use lisp::Environment;
+    use lisp::ExecutionTree;
+    use lisp::ExecutionTreeObject;
+    use lisp::PauseReason;
+    use lisp::VirtualMachine;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct CountingFrontEnd {
+        pauses: Rc<Cell<usize>>,
+    }
+
+    impl DebuggerFrontEnd<()> for CountingFrontEnd {
+        unsafe fn on_pause(&mut self, _: &(), _: &mut Environment<()>, _: &ExecutionTreeObject, _: &PauseReason) -> Result<DebuggerAction> {
+            self.pauses.set(self.pauses.get() + 1);
+            Ok(DebuggerAction::Continue)
+        }
+    }
+
+    fn new_environment(breakpoint: Breakpoint) -> (Environment<()>, Rc<Cell<usize>>) {
+        let pauses = Rc::new(Cell::new(0));
+        let mut environment = Environment::new();
+        builtin::arithmetic::register(environment.get_global_frame());
+        environment.set_debugger_front_end(Box::new(CountingFrontEnd { pauses: pauses.clone() }));
+        environment.get_debugger().add_breakpoint(breakpoint);
+        (environment, pauses)
+    }
+
+    fn parse(source: &str) -> ExecutionTree {
+        let ast = AbstractSyntaxTree::new(source).unwrap();
+        ExecutionTree::new(&ast).unwrap()
+    }
+
+    #[test]
+    fn line_breakpoints_trigger_once_per_line_entry() {
+        let ast = AbstractSyntaxTree::new("(+ (+ 1 2)\n   (+ 3 4))").unwrap();
+        let root = ExecutionTree::new(&ast).unwrap().into_root();
+        let forms: Vec<ExecutionTreeObject> = vec![
+            ExecutionTreeObject::Node(root.clone()),
+            root.get_objects()[1].clone(),
+            root.get_objects()[2].clone(),
+            ExecutionTreeObject::Node(root.clone()),
+        ];
+
+        let mut debugger = Debugger::new();
+        debugger.add_breakpoint(Breakpoint::Line(None, 1));
+        let pauses: Vec<bool> = forms.iter().map(|form| debugger.try_pause(form, 0).is_some()).collect();
+        assert_eq!(pauses, vec![true, false, false, true]);
+    }
+
+    #[test]
+    fn line_breakpoints_trigger_on_every_run() {
+        let source = "(+ 1 (+ 2 3))";
+        let (mut environment, pauses) = new_environment(Breakpoint::Line(None, 1));
+        for _ in 0..3 {
+            assert_eq!(unsafe { environment.parse_and_execute(&(), source) }.unwrap(), "6i32");
+        }
+        assert_eq!(pauses.get(), 3);
+
+        let execution_tree = parse(source);
+        let (mut environment, pauses) = new_environment(Breakpoint::Line(None, 1));
+        for _ in 0..3 {
+            assert_eq!(unsafe { environment.execute(&(), ExecutionTree::from_root(execution_tree.get_root().clone())) }.unwrap(), "6i32");
+        }
+        assert_eq!(pauses.get(), 3);
+
+        let (mut environment, pauses) = new_environment(Breakpoint::Line(None, 1));
+        let mut virtual_machine = VirtualMachine::new(Bytecode::compile(&execution_tree));
+        for _ in 0..3 {
+            assert_eq!(unsafe { virtual_machine.execute(&(), &mut environment) }.unwrap(), "6i32");
+        }
+        assert_eq!(pauses.get(), 3);
+    }
+
+    #[test]
+    fn line_breakpoints_match_their_source() {
+        let first = parse("(+ 1 2)");
+        let second = parse("(+ 1 2)");
+        let (mut environment, pauses) = new_environment(Breakpoint::Line(Some(second.get_source_id()), 1));
+        unsafe {
+            environment.execute(&(), first).unwrap();
+            assert_eq!(pauses.get(), 0);
+            environment.execute(&(), second).unwrap();
+            assert_eq!(pauses.get(), 1);
+        }
+    }
+}
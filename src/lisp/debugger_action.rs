@@ -0,0 +1,7 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DebuggerAction {
+    Continue,
+    StepInto,
+    StepOut,
+    StepOver,
+}
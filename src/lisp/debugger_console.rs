@@ -0,0 +1,315 @@
+use error::*;
+use lisp::AbstractSyntaxTree;
+use lisp::Breakpoint;
+use lisp::DebuggerAction;
+use lisp::DebuggerFrontEnd;
+use lisp::Environment;
+use lisp::ExecutionTree;
+use lisp::ExecutionTreeObject;
+use lisp::PauseReason;
+use lisp::Symbol;
+use std::io::BufRead;
+use std::io::Write;
+
+pub struct DebuggerConsole<TInput, TOutput> {
+    input: TInput,
+    output: TOutput,
+    selected_frame: Option<usize>,
+}
+
+impl <TInput, TOutput> DebuggerConsole<TInput, TOutput>
+    where TInput: BufRead, TOutput: Write {
+    pub fn new(input: TInput, output: TOutput) -> Self {
+        DebuggerConsole {
+            input: input,
+            output: output,
+            selected_frame: None,
+        }
+    }
+
+    pub fn into_inner(self) -> (TInput, TOutput) {
+        (self.input, self.output)
+    }
+
+    fn binding_to_string<TArg>(key: &str, value: &Symbol<TArg>) -> String {
+        let value_string =
+            match value {
+                &Symbol::Object(ref object) => {
+                    match object.to_string() {
+                        Ok(ok) => ok,
+                        Err(_) => object.enum_to_string().to_string(),
+                    }
+                },
+                &Symbol::BuiltInFunc(_) => "#<builtin>".to_string(),
+                &Symbol::Macro(_) => "#<macro>".to_string(),
+                &Symbol::NativeFunc(_) => "#<native>".to_string(),
+            };
+        format!("{} = {}", key, value_string)
+    }
+
+    unsafe fn evaluate_text<TArg>(&self, arg: &TArg, environment: &mut Environment<TArg>, text: &str) -> Result<ExecutionTreeObject> {
+        let object =
+            if text.starts_with('(') {
+                let ast = try!(AbstractSyntaxTree::new(text));
+                let execution_tree = try!(ExecutionTree::new(&ast));
+                ExecutionTreeObject::Node(execution_tree.into_root())
+            } else {
+                let program_text = format!("({})", text);
+                let ast = try!(AbstractSyntaxTree::new(&program_text));
+                let execution_tree = try!(ExecutionTree::new(&ast));
+                match execution_tree.into_root().into_objects().into_iter().next() {
+                    Some(object) => object,
+                    None => ExecutionTreeObject::nil(),
+                }
+            };
+
+        match self.selected_frame {
+            Some(frame_index) => environment.evaluate_in_frame(arg, frame_index, &object),
+            None => environment.evaluate(arg, &object),
+        }
+    }
+
+    fn get_selected_frame<TArg>(&self, environment: &Environment<TArg>) -> Option<usize> {
+        match self.selected_frame {
+            Some(frame_index) => Some(frame_index),
+            None => environment.get_frame_count().checked_sub(1),
+        }
+    }
+
+    fn print_help(&mut self) -> Result<()> {
+        let lines = [
+            "c, continue        resume until the next breakpoint",
+            "s, step            step into the next form",
+            "n, next            step over the current form",
+            "o, out             step out of the current frame",
+            "bt                 list frames and their bindings",
+            "frame N            select frame N for inspection",
+            "locals             list bindings in the selected frame",
+            "p NAME             print the value of a symbol",
+            "set NAME EXPR      bind NAME to the value of EXPR in the selected frame",
+            "eval EXPR          evaluate EXPR in the selected frame",
+            "break SYMBOL       break on forms headed by SYMBOL",
+            "break line N       break when evaluation enters line N",
+            "delete             remove all breakpoints",
+            "help               show this message",
+        ];
+        for line in lines.iter() {
+            try!(writeln!(self.output, "{}", line).wrap_err_to_err());
+        }
+        Ok(())
+    }
+
+    unsafe fn run_command<TArg>(&mut self, arg: &TArg, environment: &mut Environment<TArg>, line: &str) -> Result<Option<DebuggerAction>> {
+        let line = line.trim();
+        let (command, rest) =
+            match line.find(' ') {
+                Some(index) => (&line[..index], line[index + 1..].trim()),
+                None => (line, ""),
+            };
+
+        match command {
+            "" => {},
+            "c" | "continue" => {
+                return Ok(Some(DebuggerAction::Continue));
+            },
+            "s" | "step" => {
+                return Ok(Some(DebuggerAction::StepInto));
+            },
+            "n" | "next" => {
+                return Ok(Some(DebuggerAction::StepOver));
+            },
+            "o" | "out" => {
+                return Ok(Some(DebuggerAction::StepOut));
+            },
+            "bt" => {
+                let frame_count = environment.get_frame_count();
+                for frame_index in (0..frame_count).rev() {
+                    if let Some(frame) = environment.get_frame(frame_index) {
+                        let bindings: Vec<String> = frame.iter().map(|(key, value)| Self::binding_to_string(key, value)).collect();
+                        try!(writeln!(self.output, "#{} ({})", frame_index, bindings.join(", ")).wrap_err_to_err());
+                    }
+                }
+            },
+            "frame" => {
+                match rest.parse::<usize>() {
+                    Ok(frame_index) if frame_index < environment.get_frame_count() => {
+                        self.selected_frame = Some(frame_index);
+                    },
+                    _ => {
+                        try!(writeln!(self.output, "no such frame: {}", rest).wrap_err_to_err());
+                    },
+                }
+            },
+            "locals" => {
+                let mut bindings = Vec::new();
+                match self.get_selected_frame(environment) {
+                    Some(frame_index) => {
+                        if let Some(frame) = environment.get_frame(frame_index) {
+                            for (key, value) in frame.iter() {
+                                bindings.push(Self::binding_to_string(key, value));
+                            }
+                        }
+                    },
+                    None => {
+                        for (key, value) in environment.get_global_frame().iter() {
+                            bindings.push(Self::binding_to_string(key, value));
+                        }
+                    },
+                }
+                for binding in bindings {
+                    try!(writeln!(self.output, "{}", binding).wrap_err_to_err());
+                }
+            },
+            "p" | "eval" => {
+                let message =
+                    match self.evaluate_text(arg, environment, rest) {
+                        Ok(value) => try!(value.to_string()),
+                        Err(error) => format!("error: {}", error),
+                    };
+                try!(writeln!(self.output, "{}", message).wrap_err_to_err());
+            },
+            "set" => {
+                let (name, text) =
+                    match rest.find(' ') {
+                        Some(index) => (&rest[..index], rest[index + 1..].trim()),
+                        None => (rest, ""),
+                    };
+                match self.evaluate_text(arg, environment, text) {
+                    Ok(value) => {
+                        let symbol = Symbol::Object(value);
+                        let is_global = environment.get_global_frame().try_get(name).is_some();
+                        match self.get_selected_frame(environment) {
+                            Some(frame_index) if !is_global => {
+                                if let Some(frame) = environment.get_frame(frame_index) {
+                                    frame.insert(name.to_string(), symbol);
+                                }
+                            },
+                            _ => {
+                                environment.get_global_frame().insert(name.to_string(), symbol);
+                            },
+                        }
+                    },
+                    Err(error) => {
+                        try!(writeln!(self.output, "error: {}", error).wrap_err_to_err());
+                    },
+                }
+            },
+            "break" => {
+                let breakpoint =
+                    if rest.starts_with("line ") {
+                        match rest[5..].trim().parse::<usize>() {
+                            Ok(line) => Some(Breakpoint::Line(None, line)),
+                            Err(_) => None,
+                        }
+                    } else if !rest.is_empty() {
+                        Some(Breakpoint::Symbol(rest.to_string()))
+                    } else {
+                        None
+                    };
+                match breakpoint {
+                    Some(breakpoint) => {
+                        try!(writeln!(self.output, "breakpoint set at {}", breakpoint.to_string()).wrap_err_to_err());
+                        environment.get_debugger().add_breakpoint(breakpoint);
+                    },
+                    None => {
+                        try!(writeln!(self.output, "invalid breakpoint: {}", rest).wrap_err_to_err());
+                    },
+                }
+            },
+            "delete" => {
+                environment.get_debugger().clear_breakpoints();
+            },
+            "help" => {
+                try!(self.print_help());
+            },
+            command => {
+                try!(writeln!(self.output, "unknown command: {}", command).wrap_err_to_err());
+            },
+        }
+
+        Ok(None)
+    }
+}
+
+impl <TArg, TInput, TOutput> DebuggerFrontEnd<TArg> for DebuggerConsole<TInput, TOutput>
+    where TInput: BufRead, TOutput: Write {
+    unsafe fn on_pause(&mut self, arg: &TArg, environment: &mut Environment<TArg>, object: &ExecutionTreeObject, reason: &PauseReason) -> Result<DebuggerAction> {
+        self.selected_frame = None;
+
+        let location =
+            match object {
+                &ExecutionTreeObject::Node(ref node) => {
+                    match node.get_span() {
+                        Some(span) => span.to_string(),
+                        None => "?".to_string(),
+                    }
+                },
+                _ => "?".to_string(),
+            };
+        let form =
+            match object.to_string() {
+                Ok(ok) => ok,
+                Err(_) => object.enum_to_string().to_string(),
+            };
+        try!(writeln!(self.output, "paused ({}) at {}: {}", reason.to_string(), location, form).wrap_err_to_err());
+
+        loop {
+            try!(write!(self.output, "(debug) ").wrap_err_to_err());
+            try!(self.output.flush().wrap_err_to_err());
+
+            let mut line = String::new();
+            let read = try!(self.input.read_line(&mut line).wrap_err_to_err());
+            if read == 0 {
+                return Ok(DebuggerAction::Continue);
+            }
+
+            if let Some(action) = try!(self.run_command(arg, environment, &line)) {
+                return Ok(action);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use lisp::Breakpoint;
+    use lisp::DebuggerConsole;
+    use lisp::Environment;
+    use std::cell::RefCell;
+    use std::io;
+    use std::io::Cursor;
+    use std::io::Write;
+    use std::rc::Rc;
+
+    struct SharedOutput {
+        bytes: Rc<RefCell<Vec<u8>>>,
+    }
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.bytes.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn backtrace_prints_binding_values() {
+        let bytes = Rc::new(RefCell::new(Vec::new()));
+        let input = Cursor::new("bt\nc\nc\n".as_bytes().to_vec());
+        let mut environment = Environment::<()>::new();
+        builtin::arithmetic::register(environment.get_global_frame());
+        builtin::control::register(environment.get_global_frame());
+        builtin::macros::register(environment.get_global_frame());
+        environment.set_debugger_front_end(Box::new(DebuggerConsole::new(input, SharedOutput { bytes: bytes.clone() })));
+        environment.get_debugger().add_breakpoint(Breakpoint::Symbol("+".to_string()));
+
+        let result = unsafe { environment.parse_and_execute(&(), "(begin (defmacro m (x) (+ x 1)) (m 5))") };
+        assert_eq!(result.unwrap(), "6i32");
+        let output = String::from_utf8(bytes.borrow().clone()).unwrap();
+        assert!(output.contains("(x = 5i32)"), "{}", output);
+    }
+}
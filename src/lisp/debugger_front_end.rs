@@ -0,0 +1,9 @@
+use error::*;
+use lisp::DebuggerAction;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::PauseReason;
+
+pub trait DebuggerFrontEnd<TArg> {
+    unsafe fn on_pause(&mut self, arg: &TArg, environment: &mut Environment<TArg>, object: &ExecutionTreeObject, reason: &PauseReason) -> Result<DebuggerAction>;
+}
@@ -1,73 +1,297 @@
 use error::*;
 use lisp::AbstractSyntaxTree;
+use lisp::Coverage;
+use lisp::Debugger;
+use lisp::DebuggerFrontEnd;
+use lisp::EvaluationHook;
 use lisp::ExecutionTree;
 use lisp::ExecutionTreeNode;
 use lisp::ExecutionTreeObject;
+use lisp::FileSystemModuleLoader;
 use lisp::Frame;
+use lisp::IntoNativeFunction;
+use lisp::LispObject;
+use lisp::MacroDefinition;
+use lisp::Module;
+use lisp::ModuleLoader;
+use lisp::NativeFunction;
+use lisp::OverflowPolicy;
+use lisp::Profiler;
 use lisp::Symbol;
+use lisp::module_scope::ModuleScope;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::mem;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 
+const REPORTED_FRAME_COUNT: usize = 8;
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+// The call depth counts nested list forms and function bodies rather than frames, since the
+// evaluator also pushes a frame for every object it evaluates.
 pub struct Environment<TArg> {
+    call_depth: usize,
     call_stack: Vec<Frame<TArg>>,
+    constant_symbols: BTreeSet<String>,
+    coverage: Option<Coverage>,
+    debugger: Debugger,
+    debugger_front_end: Option<Box<DebuggerFrontEnd<TArg>>>,
+    evaluation_hooks: Vec<Box<EvaluationHook>>,
     global_frame: Frame<TArg>,
+    gensym_counter: usize,
+    id: usize,
+    max_call_depth: Option<usize>,
+    max_memory: Option<usize>,
+    module_aliases: BTreeMap<String, String>,
+    module_loader: Box<ModuleLoader>,
+    module_scopes: Vec<ModuleScope>,
+    modules: BTreeMap<String, Module<TArg>>,
+    overflow_policy: OverflowPolicy,
+    profiler: Option<Profiler>,
+    pure_symbols: BTreeSet<String>,
 }
 
 impl <TArg> Environment<TArg> {
     pub fn new() -> Self {
         Environment {
+            call_depth: 0,
             call_stack: Vec::new(),
+            constant_symbols: BTreeSet::new(),
+            coverage: None,
+            debugger: Debugger::new(),
+            debugger_front_end: None,
+            evaluation_hooks: Vec::new(),
             global_frame: Frame::new(),
+            gensym_counter: 0,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            max_call_depth: None,
+            max_memory: None,
+            module_aliases: BTreeMap::new(),
+            module_loader: Box::new(FileSystemModuleLoader::new(PathBuf::from("."))),
+            module_scopes: Vec::new(),
+            modules: BTreeMap::new(),
+            overflow_policy: OverflowPolicy::Checked,
+            profiler: None,
+            pure_symbols: BTreeSet::new(),
         }
     }
 
-    pub unsafe fn evaluate(&mut self, arg: &TArg, object: &ExecutionTreeObject) -> Result<ExecutionTreeObject> {
-        self.push_frame();
+    pub fn add_evaluation_hook(&mut self, evaluation_hook: Box<EvaluationHook>) {
+        self.evaluation_hooks.push(evaluation_hook);
+    }
 
-        let result =
-            match object {
-                &ExecutionTreeObject::Node(ref node) => {
-                    let inner_objects = node.get_objects();
-                    let inner_objects_len = inner_objects.len();
-                    if inner_objects_len > 1 {
-                        self.evaluate_list(arg, inner_objects)
-                    } else {
-                        if let Some((first, _)) = Self::split(inner_objects.into_iter()) {
-                            self.evaluate(arg, &first)
-                        } else {
-                            let nil = ExecutionTreeObject::nil();
-                            Ok(nil)
-                        }
-                    }
+    pub unsafe fn call_builtin(&mut self, arg: &TArg, name: &str, func: unsafe fn(&TArg, &mut Environment<TArg>, Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject>, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+        self.call_function(name, args, |environment, args| func(arg, environment, args))
+    }
+
+    pub unsafe fn call_method(&mut self, arg: &TArg, object: &ExecutionTreeObject, name: &str, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+        let lisp_object = try!(Self::get_lisp_object(object));
+        self.call_function(name, args, |environment, args| {
+            let mut objects = Vec::with_capacity(args.len());
+            for object in args {
+                objects.push(try!(environment.evaluate(arg, object)));
+            }
+            lisp_object.call_method(name, objects)
+        })
+    }
+
+    // Calls a method written as receiver:method, or returns None when the symbol is not in that form.
+    pub unsafe fn call_method_symbol(&mut self, arg: &TArg, symbol: &str, args: Vec<&ExecutionTreeObject>) -> Option<Result<ExecutionTreeObject>> {
+        let (receiver, method) =
+            match Self::split_method_symbol(symbol) {
+                Some(some) => some,
+                None => {
+                    return None;
                 },
+            };
 
-                &ExecutionTreeObject::Symbol(ref symbol) => {
-                    let symbol =
-                        match self.global_frame.try_get(&symbol) {
-                            Some(symbol) => symbol,
-                            None => try!(self.symbol_stack_search(&symbol)),
-                        };
-                    match symbol {
-                        Symbol::BuiltInFunc(func) => {
-                            let empty_args = Vec::new();
-                            func(arg, self, empty_args)
-                        },
-                        Symbol::Object(object) => Ok(object),
-                    }
+        let result =
+            match self.evaluate(arg, &ExecutionTreeObject::Symbol(receiver.to_string())) {
+                Ok(object) => self.call_method(arg, &object, method, args),
+                Err(error) => Err(error),
+            };
+        Some(result)
+    }
+
+    pub unsafe fn call_native_function(&mut self, arg: &TArg, name: &str, function: &NativeFunction, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+        self.call_function(name, args, |environment, args| function.call(arg, environment, name, args))
+    }
+
+    pub fn check_memory(&self, pending_size: usize) -> Result<()> {
+        match self.max_memory {
+            Some(max_memory) => {
+                let used = self.get_memory_usage() + pending_size;
+                if used > max_memory {
+                    Err(ErrorKind::MemoryLimitExceeded(used, max_memory).into())
+                } else {
+                    Ok(())
                 }
+            },
+            None => Ok(()),
+        }
+    }
+
+    pub fn check_result_memory(&self, result: Result<ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+        match result {
+            Ok(object) => {
+                if self.max_memory.is_some() {
+                    try!(self.check_memory(object.get_size()));
+                }
+                Ok(object)
+            },
+            error => error,
+        }
+    }
+
+    pub fn checked_define(&mut self, key: String, value: Symbol<TArg>) -> Result<Option<Symbol<TArg>>> {
+        let size = key.capacity() + value.get_size();
+        if self.max_memory.is_some() {
+            try!(self.check_memory(size));
+        }
+        self.record_allocation(size);
+        Ok(self.define(key, value))
+    }
 
-                other => Ok(other.clone()),
+    pub fn define(&mut self, key: String, value: Symbol<TArg>) -> Option<Symbol<TArg>> {
+        if self.module_scopes.is_empty() {
+            self.global_frame.insert(key, value)
+        } else {
+            self.call_stack[0].insert(key, value)
+        }
+    }
+
+    pub unsafe fn evaluate(&mut self, arg: &TArg, object: &ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        let is_branch_form = self.enter_branch_form(object);
+        let result =
+            match self.trace_form(arg, object) {
+                Ok(()) => {
+                    let result = self.evaluate_object(arg, object);
+                    self.exit_traced_form(object, result)
+                },
+                Err(error) => Err(error),
             };
+        if is_branch_form {
+            self.exit_branch_form();
+        }
+        result
+    }
 
-        self.pop_frame();
+    pub unsafe fn evaluate_in_frame(&mut self, arg: &TArg, frame_index: usize, object: &ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        let split_index = frame_index + 1;
+        if split_index > self.call_stack.len() {
+            return self.evaluate(arg, object);
+        }
+
+        let upper_frames = self.call_stack.split_off(split_index);
+        let result = self.evaluate(arg, object);
+        self.call_stack.truncate(split_index);
+        self.call_stack.extend(upper_frames);
+        result
+    }
+
+    // Evaluates without evaluation hooks, the profiler, coverage or the debugger, for evaluation the
+    // program did not ask for, such as constant folding.
+    pub unsafe fn evaluate_untraced(&mut self, arg: &TArg, object: &ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        let coverage = self.coverage.take();
+        let debugger_front_end = self.debugger_front_end.take();
+        let evaluation_hooks = mem::replace(&mut self.evaluation_hooks, Vec::new());
+        let profiler = self.profiler.take();
+
+        let result = self.evaluate(arg, object);
+
+        self.coverage = coverage;
+        self.debugger_front_end = debugger_front_end;
+        self.evaluation_hooks = evaluation_hooks;
+        self.profiler = profiler;
         result
     }
 
     pub unsafe fn execute(&mut self, arg: &TArg, execution_tree: ExecutionTree) -> Result<String> {
+        self.debugger.clear_entered_line();
         let execution_tree_root = execution_tree.into_root();
-        let execution_tree_root_object = ExecutionTreeObject::Node(execution_tree_root);
+        let execution_tree_root_object = try!(self.macroexpand_all(arg, &ExecutionTreeObject::Node(execution_tree_root)));
         let result = try!(self.evaluate(arg, &execution_tree_root_object));
         result.to_string()
     }
 
+    // Marks the start of a form for branch coverage. When this returns true, exit_branch_form must be
+    // called once the form has been evaluated.
+    pub fn enter_branch_form(&mut self, object: &ExecutionTreeObject) -> bool {
+        match (&mut self.coverage, object) {
+            (&mut Some(ref mut coverage), &ExecutionTreeObject::Node(ref node)) => coverage.enter_branch_form(node),
+            _ => false,
+        }
+    }
+
+    pub fn exit_branch_form(&mut self) {
+        if let Some(ref mut coverage) = self.coverage {
+            coverage.exit_branch_form();
+        }
+    }
+
+    pub fn exit_traced_form(&mut self, object: &ExecutionTreeObject, result: Result<ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+        self.pop_frame();
+        if let &ExecutionTreeObject::Node(_) = object {
+            self.call_depth -= 1;
+        }
+
+        for evaluation_hook in &mut self.evaluation_hooks {
+            match result {
+                Ok(ref result) => evaluation_hook.on_return(object, result),
+                Err(ref error) => evaluation_hook.on_error(object, error),
+            }
+        }
+
+        result
+    }
+
+    pub fn export(&mut self, symbol: String) -> Result<()> {
+        match self.module_scopes.last_mut() {
+            Some(module_scope) => {
+                module_scope.push_export(symbol);
+                Ok(())
+            },
+            None => Err(ErrorKind::ExportOutsideModule(symbol).into()),
+        }
+    }
+
+    pub unsafe fn expand_macro(&mut self, arg: &TArg, definition: &MacroDefinition, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+        let frame = try!(definition.bind(args));
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.enter(definition.get_name(), Profiler::macro_str());
+        }
+        let result =
+            self.with_frame(frame, |environment| {
+                let mut result = ExecutionTreeObject::nil();
+                for object in definition.get_body() {
+                    result = try!(environment.evaluate(arg, object));
+                }
+                Ok(result)
+            });
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.exit();
+        }
+        self.check_result_memory(result)
+    }
+
+    // Parentheses end a symbol in the reader, so source text can never name a generated symbol.
+    pub fn gensym(&mut self, prefix: &str) -> String {
+        self.gensym_counter += 1;
+        format!("{}({})", prefix, self.gensym_counter)
+    }
+
+    pub fn get_call_depth(&self) -> usize {
+        self.call_depth
+    }
+
+    pub fn get_coverage(&self) -> Option<&Coverage> {
+        self.coverage.as_ref()
+    }
+
     pub fn get_current_frame(&mut self) -> Option<&mut Frame<TArg>> {
         let len = self.call_stack.len();
         match len {
@@ -76,10 +300,243 @@ impl <TArg> Environment<TArg> {
         }
     }
 
+    pub fn get_debugger(&mut self) -> &mut Debugger {
+        &mut self.debugger
+    }
+
+    pub fn get_doc(&self, symbol: &str) -> Option<&String> {
+        match self.global_frame.get_doc(symbol) {
+            Some(doc) => Some(doc),
+            None => {
+                self.call_stack
+                    .iter()
+                    .rev()
+                    .filter_map(|frame| frame.get_doc(symbol))
+                    .next()
+            },
+        }
+    }
+
+    pub fn get_frame(&mut self, frame_index: usize) -> Option<&mut Frame<TArg>> {
+        self.call_stack.get_mut(frame_index)
+    }
+
+    pub fn get_frame_count(&self) -> usize {
+        self.call_stack.len()
+    }
+
     pub fn get_global_frame(&mut self) -> &mut Frame<TArg> {
         &mut self.global_frame
     }
 
+    // Identifies this environment for caches that outlive a single evaluation.
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+
+    pub fn get_max_call_depth(&self) -> Option<usize> {
+        self.max_call_depth
+    }
+
+    pub fn get_max_memory(&self) -> Option<usize> {
+        self.max_memory
+    }
+
+    pub fn get_memory_usage(&self) -> usize {
+        let mut result = self.global_frame.get_size();
+        for frame in &self.call_stack {
+            result += frame.get_size();
+        }
+        result
+    }
+
+    pub fn get_module(&self, path: &str) -> Option<&Module<TArg>> {
+        let path =
+            match self.module_aliases.get(path) {
+                Some(path) => path,
+                None => path,
+            };
+        self.modules.get(path)
+    }
+
+    pub fn get_overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    pub fn get_property(&self, object: &ExecutionTreeObject, name: &str) -> Result<ExecutionTreeObject> {
+        let lisp_object = try!(Self::get_lisp_object(object));
+        lisp_object.get_property(name)
+    }
+
+    pub fn get_profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
+    pub fn get_symbol(&self, symbol: &str) -> Result<Symbol<TArg>> {
+        match self.global_frame.try_get(symbol) {
+            Some(symbol) => Ok(symbol),
+            None => {
+                match self.symbol_stack_search(symbol) {
+                    Err(error) => {
+                        match self.symbol_module_search(symbol) {
+                            Some(symbol) => Ok(symbol),
+                            None => Err(error),
+                        }
+                    },
+                    ok => ok,
+                }
+            },
+        }
+    }
+
+    pub unsafe fn import_module(&mut self, arg: &TArg, path: &str) -> Result<String> {
+        let alias = try!(self.load_module(arg, path));
+        let exports: Vec<(String, Symbol<TArg>)> =
+            match self.modules.get(path) {
+                Some(module) => {
+                    module.get_exports()
+                        .iter()
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect()
+                },
+                None => Vec::new(),
+            };
+        for (key, value) in exports {
+            self.define(key, value);
+        }
+        Ok(alias)
+    }
+
+    pub fn is_constant(&self, symbol: &str) -> bool {
+        self.constant_symbols.contains(symbol)
+    }
+
+    pub fn is_pure(&self, symbol: &str) -> bool {
+        self.pure_symbols.contains(symbol)
+    }
+
+    pub unsafe fn load_module(&mut self, arg: &TArg, path: &str) -> Result<String> {
+        let alias = Module::<TArg>::alias_from_path(path).to_string();
+        if let Some(existing_path) = self.module_aliases.get(&alias) {
+            if existing_path != path {
+                return Err(ErrorKind::ModuleAliasConflict(alias, path.to_string(), existing_path.clone()).into());
+            }
+        }
+
+        if !self.modules.contains_key(path) {
+            if self.module_scopes.iter().any(|module_scope| module_scope.get_path() == path) {
+                return Err(ErrorKind::CircularModuleImport(path.to_string()).into());
+            }
+
+            let source = try!(self.module_loader.load(path));
+            let ast = try!(AbstractSyntaxTree::new(&source));
+            let execution_tree = try!(ExecutionTree::new(&ast));
+            let root = ExecutionTreeObject::Node(execution_tree.into_root());
+            let root = try!(self.macroexpand_all(arg, &root));
+
+            let call_stack = mem::replace(&mut self.call_stack, vec![Frame::new()]);
+            self.module_scopes.push(ModuleScope::new(path.to_string()));
+            let result = self.evaluate(arg, &root);
+            let module_scope = self.module_scopes.pop();
+            let module_frame = mem::replace(&mut self.call_stack, call_stack).into_iter().next();
+            try!(result);
+
+            let mut exports = Frame::new();
+            if let (Some(module_scope), Some(module_frame)) = (module_scope, module_frame) {
+                for symbol in module_scope.get_exports() {
+                    match module_frame.try_get(symbol) {
+                        Some(value) => {
+                            exports.insert(symbol.clone(), value);
+                        },
+                        None => {
+                            return Err(ErrorKind::UndefinedExport(path.to_string(), symbol.clone()).into());
+                        },
+                    }
+                }
+            }
+            self.modules.insert(path.to_string(), Module::new(path.to_string(), exports));
+        }
+
+        self.module_aliases.insert(alias.clone(), path.to_string());
+        Ok(alias)
+    }
+
+    pub unsafe fn macroexpand(&mut self, arg: &TArg, object: &ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        let mut result = object.clone();
+        while let Some(expansion) = try!(self.macroexpand_1(arg, &result)) {
+            result = expansion;
+        }
+        Ok(result)
+    }
+
+    pub unsafe fn macroexpand_1(&mut self, arg: &TArg, object: &ExecutionTreeObject) -> Result<Option<ExecutionTreeObject>> {
+        let objects =
+            match object {
+                &ExecutionTreeObject::Node(ref node) => node.get_objects(),
+                _ => {
+                    return Ok(None);
+                },
+            };
+
+        if let Some((&ExecutionTreeObject::Symbol(ref symbol), rest)) = Self::split(objects.into_iter()) {
+            if let Ok(Symbol::Macro(definition)) = self.get_symbol(symbol) {
+                let expansion = try!(self.expand_macro(arg, &definition, rest.collect()));
+                return Ok(Some(expansion));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Expands every macro form in the tree once, so evaluating the result never expands them again.
+    // Macros defined by the tree itself are unknown here and still expand when their forms are evaluated.
+    pub unsafe fn macroexpand_all(&mut self, arg: &TArg, object: &ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        let span =
+            match object {
+                &ExecutionTreeObject::Node(ref node) => node.get_span().cloned(),
+                _ => {
+                    return Ok(object.clone());
+                },
+            };
+
+        let node =
+            match try!(self.macroexpand(arg, object)) {
+                ExecutionTreeObject::Node(node) => node,
+                other => {
+                    return Ok(other);
+                },
+            };
+        let span =
+            match node.get_span() {
+                Some(span) => Some(span.clone()),
+                None => span,
+            };
+
+        let objects =
+            if Self::is_unexpanded_form(&node) {
+                node.into_objects()
+            } else {
+                let mut objects = Vec::with_capacity(node.get_objects().len());
+                for object in node.get_objects() {
+                    objects.push(try!(self.macroexpand_all(arg, object)));
+                }
+                objects
+            };
+
+        match span {
+            Some(span) => Ok(ExecutionTreeObject::Node(ExecutionTreeNode::with_span(objects, span))),
+            None => Ok(ExecutionTreeObject::Node(ExecutionTreeNode::new(objects))),
+        }
+    }
+
+    pub fn mark_constant(&mut self, symbol: String) {
+        self.constant_symbols.insert(symbol);
+    }
+
+    pub fn mark_pure(&mut self, symbol: String) {
+        self.pure_symbols.insert(symbol);
+    }
+
     pub unsafe fn parse_and_execute(&mut self, arg: &TArg, source: &str) -> Result<String> {
         let ast = try!(AbstractSyntaxTree::new(source));
 
@@ -88,66 +545,380 @@ impl <TArg> Environment<TArg> {
         self.execute(arg, execution_tree)
     }
 
-    pub fn with_frame<T, TFn>(&mut self, frame: Frame<TArg>, func: TFn) -> T
-        where TFn: FnOnce(&mut Environment<TArg>) -> T {
+    // Attributes an allocation of a list, map or binding to the innermost profiled call.
+    pub fn record_allocation(&mut self, size: usize) {
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.record_allocation(size);
+        }
+    }
+
+    pub fn record_branch(&mut self, branch: usize) {
+        if let Some(ref mut coverage) = self.coverage {
+            coverage.record_branch(branch);
+        }
+    }
+
+    pub fn register_fn<TMarker, TFn>(&mut self, name: &str, func: TFn) -> Option<Symbol<TArg>>
+        where TFn: IntoNativeFunction<TMarker> {
+        self.define(name.to_string(), Symbol::NativeFunc(func.into_native_function()))
+    }
+
+    pub fn set_coverage(&mut self, coverage: Option<Coverage>) {
+        self.coverage = coverage;
+    }
+
+    pub fn set_debugger_front_end(&mut self, debugger_front_end: Box<DebuggerFrontEnd<TArg>>) {
+        self.debugger_front_end = Some(debugger_front_end);
+    }
+
+    pub fn set_max_call_depth(&mut self, max_call_depth: Option<usize>) {
+        self.max_call_depth = max_call_depth;
+    }
+
+    pub fn set_max_memory(&mut self, max_memory: Option<usize>) {
+        self.max_memory = max_memory;
+    }
+
+    pub fn set_module_loader(&mut self, module_loader: Box<ModuleLoader>) {
+        self.module_loader = module_loader;
+    }
+
+    pub fn set_overflow_policy(&mut self, overflow_policy: OverflowPolicy) {
+        self.overflow_policy = overflow_policy;
+    }
+
+    pub fn set_profiler(&mut self, profiler: Option<Profiler>) {
+        self.profiler = profiler;
+    }
+
+    pub fn take_coverage(&mut self) -> Option<Coverage> {
+        self.coverage.take()
+    }
+
+    pub fn take_debugger_front_end(&mut self) -> Option<Box<DebuggerFrontEnd<TArg>>> {
+        self.debugger_front_end.take()
+    }
+
+    pub fn take_evaluation_hooks(&mut self) -> Vec<Box<EvaluationHook>> {
+        mem::replace(&mut self.evaluation_hooks, Vec::new())
+    }
+
+    pub fn take_profiler(&mut self) -> Option<Profiler> {
+        self.profiler.take()
+    }
+
+    // Records coverage and the profiler, notifies evaluation hooks, gives the debugger a chance to
+    // pause, counts list forms towards the maximum call depth and pushes the frame the form is
+    // evaluated in. When this succeeds, exit_traced_form must be called with the result of the form.
+    // Evaluators that do not go through evaluate use this so that both trace forms the same way.
+    pub unsafe fn trace_form(&mut self, arg: &TArg, object: &ExecutionTreeObject) -> Result<()> {
+        if let Some(ref mut coverage) = self.coverage {
+            coverage.record(object);
+        }
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.record_form();
+        }
+
+        for evaluation_hook in &mut self.evaluation_hooks {
+            evaluation_hook.on_form_enter(object);
+        }
+
+        let is_node =
+            match object {
+                &ExecutionTreeObject::Node(_) => true,
+                _ => false,
+            };
+        let mut result = self.try_pause(arg, object);
+        if result.is_ok() && is_node {
+            result = self.check_call_depth();
+        }
+        match result {
+            Ok(()) => {
+                if is_node {
+                    self.call_depth += 1;
+                }
+                self.push_frame();
+                Ok(())
+            },
+            Err(error) => {
+                for evaluation_hook in &mut self.evaluation_hooks {
+                    evaluation_hook.on_error(object, &error);
+                }
+                Err(error)
+            },
+        }
+    }
+
+    // Every frame pushed through here counts towards the maximum call depth, so callers cannot
+    // enter a function body without the limit being checked.
+    pub fn with_frame<T, TFn>(&mut self, frame: Frame<TArg>, func: TFn) -> Result<T>
+        where TFn: FnOnce(&mut Environment<TArg>) -> Result<T> {
+        try!(self.check_call_depth());
+        self.call_depth += 1;
         self.call_stack.push(frame);
+        self.notify_frame_push();
         let result = func(self);
         self.call_stack.pop();
+        self.notify_frame_pop();
+        self.call_depth -= 1;
         result
     }
 
+    unsafe fn call_function<'a, TFn>(&mut self, name: &str, args: Vec<&'a ExecutionTreeObject>, func: TFn) -> Result<ExecutionTreeObject>
+        where TFn: FnOnce(&mut Environment<TArg>, Vec<&'a ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+        if self.evaluation_hooks.is_empty() && self.profiler.is_none() {
+            let result = func(self, args);
+            return self.check_result_memory(result);
+        }
+
+        for evaluation_hook in &mut self.evaluation_hooks {
+            evaluation_hook.on_builtin_call(name, &args);
+        }
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.enter(name, Profiler::builtin_str());
+        }
+        let result = func(self, args);
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.exit();
+        }
+        for evaluation_hook in &mut self.evaluation_hooks {
+            evaluation_hook.on_builtin_return(name, &result);
+        }
+        self.check_result_memory(result)
+    }
+
+    fn check_call_depth(&self) -> Result<()> {
+        let depth = self.call_depth;
+        match self.max_call_depth {
+            Some(max_call_depth) if depth >= max_call_depth => {
+                let frames =
+                    self.call_stack
+                        .iter()
+                        .rev()
+                        .take(REPORTED_FRAME_COUNT)
+                        .map(|frame| frame.to_string())
+                        .collect();
+                Err(ErrorKind::StackDepthExceeded(max_call_depth, frames).into())
+            },
+            _ => Ok(()),
+        }
+    }
+
+    fn get_lisp_object(object: &ExecutionTreeObject) -> Result<Arc<LispObject>> {
+        let lisp_object =
+            match object {
+                &ExecutionTreeObject::Foreign(ref foreign) => foreign.get_object(),
+                _ => None,
+            };
+        match lisp_object {
+            Some(lisp_object) => Ok(lisp_object.clone()),
+            None => {
+                let actual =
+                    match object {
+                        &ExecutionTreeObject::Foreign(ref foreign) => foreign.get_type_name(),
+                        object => object.enum_to_string(),
+                    };
+                Err(ErrorKind::InvalidExecutionTreeObjectConversion(actual.to_string(), Self::lisp_object_str().to_string()).into())
+            },
+        }
+    }
+
+    // Quoted data and macro bodies are not code to be evaluated, so they are left as written.
+    fn is_unexpanded_form(node: &ExecutionTreeNode) -> bool {
+        match node.get_objects().first() {
+            Some(&ExecutionTreeObject::Symbol(ref symbol)) => {
+                symbol == "defmacro" || symbol == "quasiquote" || symbol == "quote"
+            },
+            _ => false,
+        }
+    }
+
+    fn lisp_object_str() -> &'static str {
+        "LispObject"
+    }
+
+    fn notify_frame_pop(&mut self) {
+        let depth = self.call_stack.len();
+        for evaluation_hook in &mut self.evaluation_hooks {
+            evaluation_hook.on_frame_pop(depth);
+        }
+    }
+
+    fn notify_frame_push(&mut self) {
+        let depth = self.call_stack.len();
+        for evaluation_hook in &mut self.evaluation_hooks {
+            evaluation_hook.on_frame_push(depth);
+        }
+    }
+
     fn pop_frame(&mut self) {
         self.call_stack.pop();
+        self.notify_frame_pop();
     }
 
     fn push_frame(&mut self) {
         self.call_stack.push(Frame::new());
+        self.notify_frame_push();
     }
 
     unsafe fn evaluate_list(&mut self, arg: &TArg, list: &Vec<ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
         self.push_frame();
+        let result = self.evaluate_list_objects(arg, list);
+        self.pop_frame();
+        result
+    }
+
+    unsafe fn evaluate_object(&mut self, arg: &TArg, object: &ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        match object {
+            &ExecutionTreeObject::Node(ref node) => {
+                let inner_objects = node.get_objects();
+                let inner_objects_len = inner_objects.len();
+                if inner_objects_len > 1 {
+                    let result = self.evaluate_list(arg, inner_objects);
+                    Self::push_backtrace_form(node, result)
+                } else {
+                    if let Some((first, _)) = Self::split(inner_objects.into_iter()) {
+                        let result = self.evaluate(arg, &first);
+                        Self::push_backtrace_form(node, result)
+                    } else {
+                        let nil = ExecutionTreeObject::nil();
+                        Ok(nil)
+                    }
+                }
+            },
+
+            &ExecutionTreeObject::Symbol(ref symbol) => {
+                match self.get_symbol(&symbol) {
+                    Ok(Symbol::BuiltInFunc(func)) => {
+                        let empty_args = Vec::new();
+                        self.call_builtin(arg, symbol, func, empty_args)
+                    },
+                    Ok(Symbol::Macro(definition)) => {
+                        let empty_args = Vec::new();
+                        match self.expand_macro(arg, &definition, empty_args) {
+                            Ok(expansion) => self.evaluate(arg, &expansion),
+                            Err(error) => Err(error),
+                        }
+                    },
+                    Ok(Symbol::NativeFunc(function)) => {
+                        let empty_args = Vec::new();
+                        self.call_native_function(arg, symbol, &function, empty_args)
+                    },
+                    Ok(Symbol::Object(object)) => Ok(object),
+                    Err(error) => {
+                        match self.call_method_symbol(arg, symbol, Vec::new()) {
+                            Some(result) => result,
+                            None => Err(error),
+                        }
+                    },
+                }
+            },
+
+            other => Ok(other.clone()),
+        }
+    }
+
+    unsafe fn evaluate_list_objects(&mut self, arg: &TArg, list: &Vec<ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
         let size = list.len();
 
-        let result =
-            if let Some((first, rest)) = Self::split(list.into_iter()) {
-                match first {
-                    &ExecutionTreeObject::Symbol(ref symbol) => {
-                        let symbol =
-                        match self.global_frame.try_get(&symbol) {
-                            Some(symbol) => symbol,
-                            None => try!(self.symbol_stack_search(&symbol)),
-                        };
-                        match symbol {
-                            Symbol::BuiltInFunc(func) => {
-                                func(arg, self, rest.collect())
+        if let Some((first, rest)) = Self::split(list.into_iter()) {
+            match first {
+                &ExecutionTreeObject::Symbol(ref first_symbol) => {
+                    let symbol =
+                        match self.get_symbol(&first_symbol) {
+                            Ok(symbol) => symbol,
+                            Err(error) => {
+                                return match self.call_method_symbol(arg, first_symbol, rest.collect()) {
+                                    Some(result) => result,
+                                    None => Err(error),
+                                };
                             },
+                        };
+                    match symbol {
+                        Symbol::BuiltInFunc(func) => {
+                            self.call_builtin(arg, first_symbol, func, rest.collect())
+                        },
 
-                            Symbol::Object(object) => {
-                                let mut result = Vec::with_capacity(size);
-                                result.push(object);
-                                for object in rest {
-                                    result.push(try!(self.evaluate(arg, &object)));
+                        Symbol::Macro(definition) => {
+                            let expansion = try!(self.expand_macro(arg, &definition, rest.collect()));
+                            self.evaluate(arg, &expansion)
+                        },
+
+                        Symbol::NativeFunc(function) => {
+                            self.call_native_function(arg, first_symbol, &function, rest.collect())
+                        },
+
+                        Symbol::Object(object) => {
+                            let mut result = Vec::with_capacity(size);
+                            let mut pending_size = 0;
+                            result.push(object);
+                            for object in rest {
+                                let object = try!(self.evaluate(arg, &object));
+                                if self.max_memory.is_some() {
+                                    pending_size += object.get_size();
+                                    try!(self.check_memory(pending_size));
                                 }
-                                Ok(ExecutionTreeObject::Node(ExecutionTreeNode::new(result)))
-                            },
-                        }
-                    },
+                                result.push(object);
+                            }
+                            let node = ExecutionTreeNode::new(result);
+                            self.record_allocation(node.get_heap_size());
+                            Ok(ExecutionTreeObject::Node(node))
+                        },
+                    }
+                },
 
-                    other => {
-                        let mut result = Vec::with_capacity(size);
-                        result.push(try!(self.evaluate(arg, &other)));
-                        for object in rest {
-                            result.push(try!(self.evaluate(arg, &object)));
+                other => {
+                    let mut result = Vec::with_capacity(size);
+                    let mut pending_size = 0;
+                    result.push(try!(self.evaluate(arg, &other)));
+                    for object in rest {
+                        let object = try!(self.evaluate(arg, &object));
+                        if self.max_memory.is_some() {
+                            pending_size += object.get_size();
+                            try!(self.check_memory(pending_size));
                         }
-                        Ok(ExecutionTreeObject::Node(ExecutionTreeNode::new(result)))
+                        result.push(object);
+                    }
+                    let node = ExecutionTreeNode::new(result);
+                    self.record_allocation(node.get_heap_size());
+                    Ok(ExecutionTreeObject::Node(node))
+                },
+            }
+        } else {
+            Ok(ExecutionTreeObject::Node(ExecutionTreeNode::new(Vec::new())))
+        }
+    }
+
+    fn push_backtrace_form(node: &ExecutionTreeNode, result: Result<ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+        match result {
+            Err(error) => {
+                match node.get_objects().first() {
+                    Some(&ExecutionTreeObject::Symbol(ref symbol)) => {
+                        Err(error.push_lisp_backtrace_form(symbol.clone(), node.get_span()))
                     },
+                    _ => Err(error),
                 }
-            } else {
-                Ok(ExecutionTreeObject::Node(ExecutionTreeNode::new(Vec::new())))
-            };
+            },
+            ok => ok,
+        }
+    }
 
-        self.pop_frame();
-        result
+    unsafe fn try_pause(&mut self, arg: &TArg, object: &ExecutionTreeObject) -> Result<()> {
+        if self.debugger_front_end.is_none() {
+            return Ok(());
+        }
+
+        let depth = self.call_stack.len();
+        if let Some(reason) = self.debugger.try_pause(object, depth) {
+            if let Some(mut debugger_front_end) = self.debugger_front_end.take() {
+                let action = debugger_front_end.on_pause(arg, self, object, &reason);
+                if self.debugger_front_end.is_none() {
+                    self.debugger_front_end = Some(debugger_front_end);
+                }
+                self.debugger.resume(try!(action), depth);
+            }
+        }
+        Ok(())
     }
 
     fn split<'a, TObjects>(mut objects: TObjects) -> Option<(&'a ExecutionTreeObject, TObjects)>
@@ -159,6 +930,13 @@ impl <TArg> Environment<TArg> {
         }
     }
 
+    fn split_method_symbol(symbol: &str) -> Option<(&str, &str)> {
+        match symbol.find(':') {
+            Some(index) if index > 0 && index + 1 < symbol.len() => Some((&symbol[..index], &symbol[(index + 1)..])),
+            _ => None,
+        }
+    }
+
     fn symbol_stack_search(&self, symbol: &str) -> Result<Symbol<TArg>> {
         let call_stack = &self.call_stack;
         for stack_frame in call_stack.into_iter().rev() {
@@ -169,4 +947,191 @@ impl <TArg> Environment<TArg> {
 
         Err(ErrorKind::SymbolNotOnStack(symbol.to_string()).into())
     }
+
+    fn symbol_module_search(&self, symbol: &str) -> Option<Symbol<TArg>> {
+        match symbol.rfind('/') {
+            Some(index) if index > 0 && index + 1 < symbol.len() => {
+                let path = &symbol[..index];
+                let name = &symbol[(index + 1)..];
+                match self.get_module(path) {
+                    Some(module) => module.try_get(name),
+                    None => None,
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use error::*;
+    use lisp::AbstractSyntaxTree;
+    use lisp::Environment;
+    use lisp::ExecutionTree;
+    use lisp::ExecutionTreeObject;
+    use lisp::Frame;
+    use lisp::MemoryModuleLoader;
+    use lisp::Profiler;
+
+    fn assert_memory_limit_exceeded(result: Result<String>) {
+        match result {
+            Err(error) => {
+                match error.kind() {
+                    &ErrorKind::MemoryLimitExceeded(..) => { },
+                    kind => panic!("unexpected error kind, {}", kind.enum_to_string()),
+                }
+            },
+            Ok(ok) => panic!("unexpected success, {}", ok),
+        }
+    }
+
+    fn nested_source(depth: usize) -> String {
+        format!("({}1{})", "(".repeat(depth), ")".repeat(depth))
+    }
+
+    #[test]
+    fn max_call_depth_counts_nested_forms() {
+        let mut environment = Environment::<()>::new();
+        environment.set_max_call_depth(Some(12));
+        unsafe {
+            assert_eq!(environment.parse_and_execute(&(), &nested_source(10)).unwrap(), "1i32");
+            match environment.parse_and_execute(&(), &nested_source(12)) {
+                Err(error) => {
+                    match error.kind() {
+                        &ErrorKind::StackDepthExceeded(12, _) => { },
+                        kind => panic!("unexpected error kind, {}", kind.enum_to_string()),
+                    }
+                },
+                Ok(ok) => panic!("unexpected success, {}", ok),
+            }
+        }
+        assert_eq!(environment.get_call_depth(), 0);
+    }
+
+    #[test]
+    fn with_frame_enforces_max_call_depth() {
+        let mut environment = Environment::<()>::new();
+        environment.set_max_call_depth(Some(1));
+        let frame_count = environment.with_frame(Frame::new(), |environment| Ok(environment.get_frame_count()));
+        assert_eq!(frame_count.unwrap(), 1);
+        let result =
+            environment.with_frame(Frame::new(), |environment| {
+                environment.with_frame(Frame::new(), |_| Ok(()))
+            });
+        assert!(result.is_err());
+        assert_eq!(environment.get_call_depth(), 0);
+        assert_eq!(environment.get_frame_count(), 0);
+    }
+
+    #[test]
+    fn max_memory_is_checked_when_defining() {
+        let mut environment = Environment::<()>::new();
+        builtin::definition::register(environment.get_global_frame());
+        environment.set_max_memory(Some(environment.get_memory_usage() + 4096));
+        unsafe {
+            assert!(environment.parse_and_execute(&(), "(define small \"abc\")").is_ok());
+            let source = format!("(define large \"{}\")", "a".repeat(8192));
+            assert_memory_limit_exceeded(environment.parse_and_execute(&(), &source));
+        }
+    }
+
+    #[test]
+    fn max_memory_is_checked_when_building_lists() {
+        let mut environment = Environment::<()>::new();
+        environment.set_max_memory(Some(environment.get_memory_usage() + 4096));
+        let items: Vec<String> = (0..1000).map(|index| index.to_string()).collect();
+        unsafe {
+            assert!(environment.parse_and_execute(&(), "(1 2 3)").is_ok());
+            assert_memory_limit_exceeded(environment.parse_and_execute(&(), &format!("(({}))", items.join(" "))));
+        }
+    }
+
+    #[test]
+    fn backtrace_forms_carry_source_spans() {
+        let mut environment = Environment::<()>::new();
+        builtin::control::register(environment.get_global_frame());
+        builtin::definition::register(environment.get_global_frame());
+        let error = unsafe { environment.parse_and_execute(&(), "(begin\n  1\n  (define x))") }.unwrap_err();
+        match error.kind() {
+            &ErrorKind::InvalidArgumentCount(..) => { },
+            kind => panic!("unexpected error kind, {}", kind.enum_to_string()),
+        }
+        let forms = error.get_lisp_backtrace().unwrap();
+        assert_eq!(forms[0], "define at 3:3");
+        assert_eq!(forms[1], "begin at 1:1");
+        assert_eq!(error.clone().get_lisp_backtrace(), Some(forms));
+    }
+
+    #[test]
+    fn macroexpand_all_expands_forms_once() {
+        let mut environment = Environment::<()>::new();
+        builtin::control::register(environment.get_global_frame());
+        builtin::macros::register(environment.get_global_frame());
+        let ast = AbstractSyntaxTree::new("(begin (m 1) (quote (m 2)) (m (m 3)))").unwrap();
+        let root = ExecutionTreeObject::Node(ExecutionTree::new(&ast).unwrap().into_root());
+        unsafe {
+            environment.parse_and_execute(&(), "(defmacro m (x) x)").unwrap();
+            let expanded = environment.macroexpand_all(&(), &root).unwrap();
+            assert_eq!(expanded.to_string().unwrap(), "(begin 1i32 (quote (m 2i32)) 3i32)");
+
+            environment.set_profiler(Some(Profiler::new()));
+            assert_eq!(environment.evaluate(&(), &expanded).unwrap().to_string().unwrap(), "3i32");
+            assert_eq!(environment.evaluate(&(), &expanded).unwrap().to_string().unwrap(), "3i32");
+        }
+        assert!(environment.get_profiler().unwrap().get_entry("m").is_none());
+    }
+
+    #[test]
+    fn profiler_counts_allocations_where_they_are_made() {
+        let mut environment = Environment::<()>::new();
+        builtin::control::register(environment.get_global_frame());
+        builtin::definition::register(environment.get_global_frame());
+        builtin::map::register(environment.get_global_frame());
+        environment.set_profiler(Some(Profiler::new()));
+        unsafe {
+            environment.parse_and_execute(&(), "(begin (define m (hash-map 1 2)) (keys m) m)").unwrap();
+        }
+        let profiler = environment.get_profiler().unwrap();
+        let allocation_count = |name| profiler.get_entry(name).unwrap().get_allocation_count();
+        assert_eq!(allocation_count("hash-map"), 1);
+        assert_eq!(allocation_count("keys"), 1);
+        assert_eq!(allocation_count("define"), 1);
+        assert_eq!(allocation_count("begin"), 0);
+        assert!(profiler.get_entry("hash-map").unwrap().get_allocation_size() > 0);
+    }
+
+    #[test]
+    fn gensym_names_cannot_be_read() {
+        let mut environment = Environment::<()>::new();
+        let symbol = environment.gensym("G");
+        assert_eq!(symbol, "G(1)");
+        assert_ne!(environment.gensym("G"), symbol);
+    }
+
+    #[test]
+    fn module_aliases_cannot_collide() {
+        let mut module_loader = MemoryModuleLoader::new();
+        module_loader.insert("a/util".to_string(), "(define x 1)".to_string());
+        module_loader.insert("b/util".to_string(), "(define x 2)".to_string());
+        let mut environment = Environment::<()>::new();
+        builtin::definition::register(environment.get_global_frame());
+        environment.set_module_loader(Box::new(module_loader));
+        unsafe {
+            assert_eq!(environment.load_module(&(), "a/util").unwrap(), "util");
+            assert_eq!(environment.load_module(&(), "a/util").unwrap(), "util");
+            match environment.load_module(&(), "b/util") {
+                Err(error) => {
+                    match error.kind() {
+                        &ErrorKind::ModuleAliasConflict(ref alias, ref path, ref existing_path) => {
+                            assert_eq!((alias.as_str(), path.as_str(), existing_path.as_str()), ("util", "b/util", "a/util"));
+                        },
+                        kind => panic!("unexpected error kind, {}", kind.enum_to_string()),
+                    }
+                },
+                Ok(ok) => panic!("unexpected success, {}", ok),
+            }
+        }
+    }
 }
@@ -0,0 +1,146 @@
+use error::*;
+use lisp::ExecutionTreeObject;
+
+pub trait EvaluationHook {
+    fn on_builtin_call(&mut self, _name: &str, _args: &[&ExecutionTreeObject]) { }
+
+    fn on_builtin_return(&mut self, _name: &str, _result: &Result<ExecutionTreeObject>) { }
+
+    fn on_error(&mut self, _object: &ExecutionTreeObject, _error: &Error) { }
+
+    fn on_form_enter(&mut self, _object: &ExecutionTreeObject) { }
+
+    fn on_frame_pop(&mut self, _depth: usize) { }
+
+    fn on_frame_push(&mut self, _depth: usize) { }
+
+    fn on_return(&mut self, _object: &ExecutionTreeObject, _result: &ExecutionTreeObject) { }
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use error::*;
+    use lisp::AbstractSyntaxTree;
+    use lisp::Bytecode;
+    use lisp::Environment;
+    use lisp::EvaluationHook;
+    use lisp::ExecutionTree;
+    use lisp::ExecutionTreeObject;
+    use lisp::VirtualMachine;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct RecordingHook {
+        events: Rc<RefCell<Vec<String>>>,
+        frames: Rc<RefCell<Vec<(bool, usize)>>>,
+    }
+
+    impl RecordingHook {
+        fn push(&self, event: String) {
+            self.events.borrow_mut().push(event);
+        }
+    }
+
+    impl EvaluationHook for RecordingHook {
+        fn on_builtin_call(&mut self, name: &str, args: &[&ExecutionTreeObject]) {
+            self.push(format!("call {} {}", name, args.len()));
+        }
+
+        fn on_builtin_return(&mut self, name: &str, result: &Result<ExecutionTreeObject>) {
+            self.push(format!("builtin return {} {}", name, result.is_ok()));
+        }
+
+        fn on_error(&mut self, object: &ExecutionTreeObject, error: &Error) {
+            self.push(format!("error {} {}", object.to_string().unwrap(), error.kind().enum_to_string()));
+        }
+
+        fn on_form_enter(&mut self, object: &ExecutionTreeObject) {
+            self.push(format!("enter {}", object.to_string().unwrap()));
+        }
+
+        fn on_frame_pop(&mut self, depth: usize) {
+            self.frames.borrow_mut().push((false, depth));
+        }
+
+        fn on_frame_push(&mut self, depth: usize) {
+            self.frames.borrow_mut().push((true, depth));
+        }
+
+        fn on_return(&mut self, object: &ExecutionTreeObject, result: &ExecutionTreeObject) {
+            self.push(format!("return {} {}", object.to_string().unwrap(), result.to_string().unwrap()));
+        }
+    }
+
+    // Runs the source on the tree walker and then on the virtual machine, returning the events and
+    // frame pushes and pops recorded by each.
+    fn record(source: &str) -> Vec<(Vec<String>, Vec<(bool, usize)>)> {
+        let ast = AbstractSyntaxTree::new(source).unwrap();
+        let execution_tree = ExecutionTree::new(&ast).unwrap();
+        let root = ExecutionTreeObject::Node(execution_tree.get_root().clone());
+        let mut results = Vec::new();
+        for uses_virtual_machine in [false, true].iter() {
+            let events = Rc::new(RefCell::new(Vec::new()));
+            let frames = Rc::new(RefCell::new(Vec::new()));
+            let mut environment = Environment::<()>::new();
+            builtin::arithmetic::register(environment.get_global_frame());
+            environment.add_evaluation_hook(Box::new(RecordingHook { events: events.clone(), frames: frames.clone() }));
+            let _ =
+                if *uses_virtual_machine {
+                    let mut virtual_machine = VirtualMachine::new(Bytecode::compile(&execution_tree));
+                    unsafe { virtual_machine.run(&(), &mut environment) }
+                } else {
+                    unsafe { environment.evaluate(&(), &root) }
+                };
+            let events = events.borrow().clone();
+            let frames = frames.borrow().clone();
+            results.push((events, frames));
+        }
+        results
+    }
+
+    #[test]
+    fn hooks_see_calls_returns_and_errors_in_order() {
+        let results = record("(+ 1 (+ 2 x))");
+        let expected = vec![
+            "enter (+ 1i32 (+ 2i32 x))",
+            "call + 2",
+            "enter 1i32",
+            "return 1i32 1i32",
+            "enter (+ 2i32 x)",
+            "call + 2",
+            "enter 2i32",
+            "return 2i32 2i32",
+            "enter x",
+            "error x ErrorKind::SymbolNotOnStack",
+            "builtin return + false",
+            "error (+ 2i32 x) ErrorKind::SymbolNotOnStack",
+            "builtin return + false",
+            "error (+ 1i32 (+ 2i32 x)) ErrorKind::SymbolNotOnStack",
+        ];
+        for &(ref events, _) in &results {
+            assert_eq!(events, &expected);
+        }
+    }
+
+    #[test]
+    fn frame_depths_follow_pushes_and_pops() {
+        for source in ["(+ 1 (+ 2 3))", "(+ 1 (+ 2 x))"].iter() {
+            for &(_, ref frames) in &record(source) {
+                let mut depth = 0;
+                let mut max_depth = 0;
+                for &(is_push, frame_depth) in frames {
+                    if is_push {
+                        depth += 1;
+                    } else {
+                        depth -= 1;
+                    }
+                    assert_eq!(frame_depth, depth, "{}", source);
+                    max_depth = max_depth.max(depth);
+                }
+                assert_eq!(depth, 0, "{}", source);
+                assert!(max_depth >= 3, "{}", source);
+            }
+        }
+    }
+}
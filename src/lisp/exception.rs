@@ -0,0 +1,133 @@
+use error::*;
+use lisp::ExecutionTreeObject;
+use std::cmp::Ordering;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::mem;
+
+// An exception caught from an error keeps that error, so rethrowing it preserves the original kind
+// and message. The error takes no part in comparisons or hashing.
+#[derive(Clone, Debug)]
+pub struct Exception {
+    error: Option<Box<Error>>,
+    kind: String,
+    message: String,
+    tag: String,
+    value: Box<ExecutionTreeObject>,
+}
+
+impl Exception {
+    pub fn new(kind: String, tag: String, message: String, value: ExecutionTreeObject) -> Self {
+        Exception {
+            error: None,
+            kind: kind,
+            message: message,
+            tag: tag,
+            value: Box::new(value),
+        }
+    }
+
+    pub fn from_error(error: &Error) -> Self {
+        let error_kind = error.kind();
+        let kind = Self::kind_name(error_kind).to_string();
+        let message = error_kind.to_string();
+        let mut result =
+            match error_kind {
+                &ErrorKind::Thrown(ref tag, ref value) => {
+                    Self::new(kind, tag.clone(), message, value.clone())
+                },
+                _ => {
+                    let value = ExecutionTreeObject::String(message.clone());
+                    Self::new(kind.clone(), kind, message, value)
+                },
+            };
+        result.error = Some(Box::new(error.clone()));
+        result
+    }
+
+    pub fn get_kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn get_size(&self) -> usize {
+        mem::size_of::<Exception>() +
+        self.kind.capacity() +
+        self.message.capacity() +
+        self.tag.capacity() +
+        self.value.get_size()
+    }
+
+    pub fn get_tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub fn get_value(&self) -> &ExecutionTreeObject {
+        &self.value
+    }
+
+    pub fn into_error(self) -> Error {
+        match self.error {
+            Some(error) => *error,
+            None => ErrorKind::Thrown(self.tag, *self.value).into(),
+        }
+    }
+
+    // Resource limits cannot be caught, so a script cannot recover from exceeding them.
+    pub fn is_catchable(&self) -> bool {
+        self.kind != Self::kind_name(&ErrorKind::StackDepthExceeded(0, Vec::new())) &&
+        self.kind != Self::kind_name(&ErrorKind::MemoryLimitExceeded(0, 0))
+    }
+
+    pub fn is_match(&self, selector: &str) -> bool {
+        self.is_catchable() && (selector == "_" || selector == self.tag || selector == self.kind)
+    }
+
+    pub fn kind_name(kind: &ErrorKind) -> &'static str {
+        let name = kind.enum_to_string();
+        match name.find("::") {
+            Some(index) => &name[(index + 2)..],
+            None => name,
+        }
+    }
+
+    pub fn to_string(&self) -> Result<String> {
+        let value = try!(self.value.to_string());
+        let result = format!("#<exception {} {}>", self.tag, value);
+        Ok(result)
+    }
+
+    fn key(&self) -> (&String, &String, &String, &ExecutionTreeObject) {
+        (&self.kind, &self.message, &self.tag, &self.value)
+    }
+}
+
+impl PartialEq for Exception {
+    fn eq(&self, other: &Exception) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Exception {}
+
+impl PartialOrd for Exception {
+    fn partial_cmp(&self, other: &Exception) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Exception {
+    fn cmp(&self, other: &Exception) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Hash for Exception {
+    fn hash<THasher>(&self, state: &mut THasher)
+        where THasher: Hasher {
+        self.key().hash(state);
+    }
+}
@@ -1,34 +1,69 @@
+use encoding;
 use error::*;
 use lisp::AbstractSyntaxTree;
 use lisp::AbstractSyntaxTreeNode;
 use lisp::AbstractSyntaxTreeObject;
 use lisp::ExecutionTreeNode;
 use lisp::ExecutionTreeObject;
+use lisp::Span;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::Zero;
 use std::ops::Deref;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 
+static NEXT_SOURCE_ID: AtomicUsize = AtomicUsize::new(1);
+
+// Every parsed tree gets its own source id, which its spans carry, so spans from different texts
+// with the same offsets can be told apart.
 pub struct ExecutionTree {
     root: ExecutionTreeNode,
+    source_id: usize,
 }
 
 impl ExecutionTree {
     pub fn new(syntax_tree: &AbstractSyntaxTree) -> Result<Self> {
         let abstract_root = syntax_tree.get_root();
-        let root = try!(Self::visit_node(abstract_root.deref()));
+        let line_starts = Span::line_starts(syntax_tree.get_program_text());
+        let source_id = NEXT_SOURCE_ID.fetch_add(1, Ordering::Relaxed);
+        let root = try!(Self::visit_node(abstract_root.deref(), &line_starts, source_id));
         let result =
             ExecutionTree {
                 root: root,
+                source_id: source_id,
             };
         Ok(result)
     }
 
+    pub fn from_root(root: ExecutionTreeNode) -> Self {
+        let source_id =
+            match root.get_span() {
+                Some(span) => span.get_source_id(),
+                None => 0,
+            };
+        ExecutionTree {
+            root: root,
+            source_id: source_id,
+        }
+    }
+
     pub fn get_root(&self) -> &ExecutionTreeNode {
         &self.root
     }
 
+    pub fn get_source_id(&self) -> usize {
+        self.source_id
+    }
+
     pub fn into_root(self) -> ExecutionTreeNode {
         self.root
     }
 
+    pub fn parse_atom(string: &str) -> Result<ExecutionTreeObject> {
+        Self::visit_string(string)
+    }
+
     fn convert_escape_sequences(string: &str) -> Result<String> {
         let mut result = String::with_capacity(string.len());
 
@@ -64,13 +99,13 @@ impl ExecutionTree {
         Ok(result)
     }
 
-    fn visit_node(current_node: &AbstractSyntaxTreeNode) -> Result<ExecutionTreeNode> {
+    fn visit_node(current_node: &AbstractSyntaxTreeNode, line_starts: &[usize], source_id: usize) -> Result<ExecutionTreeNode> {
         let mut execution_objects = Vec::new();
 
         for object in current_node.get_objects() {
             match *object {
                 AbstractSyntaxTreeObject::Node(ref inner_node) => {
-                    let new_node = try!(Self::visit_node(inner_node));
+                    let new_node = try!(Self::visit_node(inner_node, line_starts, source_id));
                     let new_object = ExecutionTreeObject::Node(new_node);
                     execution_objects.push(new_object);
                 },
@@ -81,7 +116,9 @@ impl ExecutionTree {
             }
         }
 
-        let result = ExecutionTreeNode::new(execution_objects);
+        let mut span = Span::from_offsets(current_node.get_start(), current_node.get_end(), line_starts);
+        span.set_source_id(source_id);
+        let result = ExecutionTreeNode::with_span(execution_objects, span);
         Ok(result)
     }
 
@@ -110,7 +147,11 @@ impl ExecutionTree {
                 if first_char.is_digit(10) ||
                    (first_char == '-' && char_len > 1) ||
                    (first_char == '+' && char_len > 1) {
-                    if char_len > 5 {
+                    if last_char == 'n' {
+                        Self::visit_big_int_string(string)
+                    } else if string.contains('/') {
+                        Self::visit_rational_string(string)
+                    } else if char_len > 5 {
                         Self::visit_six_char_number_string(string)
                     } else if char_len > 3 {
                         Self::visit_four_char_number_string(string)
@@ -121,6 +162,22 @@ impl ExecutionTree {
                     }
                 } else {
                     match first_char {
+                        'b' if string.starts_with("b\"") => {
+                            if char_len < 3 || last_char != '"' {
+                                Err(ErrorKind::NoClosingDoubleQuoteInExecutionTree(string.to_string()).into())
+                            } else {
+                                let bytes = try!(encoding::unescape_bytes(&string[2..(byte_len - 1)]));
+                                Ok(ExecutionTreeObject::Bytes(bytes))
+                            }
+                        },
+                        '#' if string.starts_with("#x\"") => {
+                            if char_len < 4 || last_char != '"' {
+                                Err(ErrorKind::NoClosingDoubleQuoteInExecutionTree(string.to_string()).into())
+                            } else {
+                                let bytes = try!(encoding::decode_hex(&string[3..(byte_len - 1)]));
+                                Ok(ExecutionTreeObject::Bytes(bytes))
+                            }
+                        },
                         '"' => {
                             if char_len < 2 || last_char != '"' {
                                 Err(ErrorKind::NoClosingDoubleQuoteInExecutionTree(string.to_string()).into())
@@ -159,6 +216,29 @@ impl ExecutionTree {
         }
     }
 
+    fn visit_big_int_string(string: &str) -> Result<ExecutionTreeObject> {
+        match string[..(string.len() - 1)].parse() {
+            Ok(ok) => Ok(ExecutionTreeObject::BigInt(ok)),
+            Err(_) => {
+                Err(ErrorKind::NumericTokenCannotBeParsed(ExecutionTreeObject::big_int_str().to_string(), string.to_string()).into())
+            },
+        }
+    }
+
+    fn visit_rational_string(string: &str) -> Result<ExecutionTreeObject> {
+        let mut parts = string.splitn(2, '/');
+        let numerator = parts.next().and_then(|part| part.parse::<BigInt>().ok());
+        let denominator = parts.next().and_then(|part| part.parse::<BigInt>().ok());
+        match (numerator, denominator) {
+            (Some(numerator), Some(denominator)) if !denominator.is_zero() => {
+                Ok(ExecutionTreeObject::Rational(BigRational::new(numerator, denominator)))
+            },
+            _ => {
+                Err(ErrorKind::NumericTokenCannotBeParsed(ExecutionTreeObject::rational_str().to_string(), string.to_string()).into())
+            },
+        }
+    }
+
     fn visit_six_char_number_string(string: &str) -> Result<ExecutionTreeObject> {
         if let Some((last_five_index, _)) = string.char_indices().rev().take(5).nth(4) {
             let (first_chars, last_five_chars) = string.split_at(last_five_index);
@@ -316,3 +396,38 @@ impl ExecutionTree {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use lisp::ExecutionTree;
+    use lisp::ExecutionTreeObject;
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+
+    #[test]
+    fn parses_big_int_literals() {
+        let expected: BigInt = "123456789012345678901234567890".parse().unwrap();
+        assert_eq!(ExecutionTree::parse_atom("123456789012345678901234567890n").unwrap(), ExecutionTreeObject::BigInt(expected));
+        assert_eq!(ExecutionTree::parse_atom("-5n").unwrap(), ExecutionTreeObject::BigInt(BigInt::from(-5)));
+        assert!(ExecutionTree::parse_atom("12xn").is_err());
+    }
+
+    #[test]
+    fn parses_rational_literals_in_lowest_terms() {
+        let expected = BigRational::new(BigInt::from(-1), BigInt::from(2));
+        assert_eq!(ExecutionTree::parse_atom("-2/4").unwrap(), ExecutionTreeObject::Rational(expected));
+        assert_eq!(ExecutionTree::parse_atom("3/6").unwrap().to_string().unwrap(), "1/2");
+        assert!(ExecutionTree::parse_atom("1/0").is_err());
+        assert!(ExecutionTree::parse_atom("1/x").is_err());
+    }
+
+    #[test]
+    fn parses_byte_string_literals() {
+        assert_eq!(ExecutionTree::parse_atom("b\"a\\x00\\n\"").unwrap(), ExecutionTreeObject::Bytes(b"a\x00\n".to_vec()));
+        assert_eq!(ExecutionTree::parse_atom("#x\"00ff\"").unwrap(), ExecutionTreeObject::Bytes(vec![0x00, 0xff]));
+        assert_eq!(ExecutionTree::parse_atom("b\"\"").unwrap(), ExecutionTreeObject::Bytes(Vec::new()));
+        assert!(ExecutionTree::parse_atom("b\"abc").is_err());
+        assert!(ExecutionTree::parse_atom("#x\"0\"").is_err());
+        assert!(ExecutionTree::parse_atom("b\"\\q\"").is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use error::*;
+use lisp::ExecutionTreeObject;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::mem;
+use std::slice::Iter;
+
+#[derive(Clone, Debug)]
+pub struct ExecutionTreeMap {
+    entries: Vec<(ExecutionTreeObject, ExecutionTreeObject)>,
+    entries_size: usize,
+    indices: HashMap<ExecutionTreeObject, usize>,
+}
+
+impl ExecutionTreeMap {
+    pub fn new() -> Self {
+        ExecutionTreeMap {
+            entries: Vec::new(),
+            entries_size: 0,
+            indices: HashMap::new(),
+        }
+    }
+
+    pub fn contains_key(&self, key: &ExecutionTreeObject) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    pub fn get(&self, key: &ExecutionTreeObject) -> Option<&ExecutionTreeObject> {
+        match self.indices.get(key) {
+            Some(&index) => Some(&self.entries[index].1),
+            None => None,
+        }
+    }
+
+    pub fn get_heap_size(&self) -> usize {
+        let entry_size = mem::size_of::<(ExecutionTreeObject, ExecutionTreeObject)>();
+        let index_size = mem::size_of::<(ExecutionTreeObject, usize)>();
+        self.entries.capacity() * entry_size + self.indices.capacity() * index_size + self.entries_size
+    }
+
+    pub fn insert(&mut self, key: ExecutionTreeObject, value: ExecutionTreeObject) -> Option<ExecutionTreeObject> {
+        match self.indices.get(&key) {
+            Some(&index) => {
+                self.entries_size += Self::get_value_size(&value);
+                let replaced = mem::replace(&mut self.entries[index].1, value);
+                self.entries_size -= Self::get_value_size(&replaced);
+                return Some(replaced);
+            },
+            None => {},
+        }
+
+        self.entries_size += Self::get_key_size(&key) + Self::get_value_size(&value);
+        self.indices.insert(key.clone(), self.entries.len());
+        self.entries.push((key, value));
+        None
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, (ExecutionTreeObject, ExecutionTreeObject)> {
+        self.entries.iter()
+    }
+
+    pub fn keys(&self) -> Vec<ExecutionTreeObject> {
+        self.entries.iter().map(|&(ref key, _)| key.clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn remove(&mut self, key: &ExecutionTreeObject) -> Option<ExecutionTreeObject> {
+        let index =
+            match self.indices.remove(key) {
+                Some(index) => index,
+                None => {
+                    return None;
+                },
+            };
+
+        let (key, value) = self.entries.remove(index);
+        self.entries_size -= Self::get_key_size(&key) + Self::get_value_size(&value);
+        for &mut (ref key, _) in &mut self.entries[index..] {
+            if let Some(entry_index) = self.indices.get_mut(key) {
+                *entry_index -= 1;
+            }
+        }
+        Some(value)
+    }
+
+    pub fn sorted_entries(&self) -> Vec<&(ExecutionTreeObject, ExecutionTreeObject)> {
+        let mut result: Vec<&(ExecutionTreeObject, ExecutionTreeObject)> = self.entries.iter().collect();
+        result.sort_by(|left, right| left.0.cmp(&right.0));
+        result
+    }
+
+    pub fn to_string(&self) -> Result<String> {
+        let mut result = String::new();
+        result.push('{');
+        for &(ref key, ref value) in &self.entries {
+            result.push_str(&try!(key.to_string()));
+            result.push(' ');
+            result.push_str(&try!(value.to_string()));
+            result.push(' ');
+        }
+        if result.len() != 1 {
+            result.pop();
+        }
+        result.push('}');
+        Ok(result)
+    }
+
+    pub fn values(&self) -> Vec<ExecutionTreeObject> {
+        self.entries.iter().map(|&(_, ref value)| value.clone()).collect()
+    }
+
+    // Keys are stored twice, once in the entries and once in the indices.
+    fn get_key_size(key: &ExecutionTreeObject) -> usize {
+        (key.get_size() - mem::size_of::<ExecutionTreeObject>()) * 2
+    }
+
+    fn get_value_size(value: &ExecutionTreeObject) -> usize {
+        value.get_size() - mem::size_of::<ExecutionTreeObject>()
+    }
+}
+
+impl PartialEq for ExecutionTreeMap {
+    fn eq(&self, other: &ExecutionTreeMap) -> bool {
+        self.len() == other.len() &&
+            self.entries.iter().all(|&(ref key, ref value)| other.get(key) == Some(value))
+    }
+}
+
+impl Eq for ExecutionTreeMap {}
+
+impl PartialOrd for ExecutionTreeMap {
+    fn partial_cmp(&self, other: &ExecutionTreeMap) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ExecutionTreeMap {
+    fn cmp(&self, other: &ExecutionTreeMap) -> Ordering {
+        self.sorted_entries().cmp(&other.sorted_entries())
+    }
+}
+
+impl Hash for ExecutionTreeMap {
+    fn hash<THasher>(&self, state: &mut THasher)
+        where THasher: Hasher {
+        let mut entries_hash: u64 = 0;
+        for entry in &self.entries {
+            let mut hasher = DefaultHasher::new();
+            entry.hash(&mut hasher);
+            entries_hash = entries_hash.wrapping_add(hasher.finish());
+        }
+        self.entries.len().hash(state);
+        entries_hash.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lisp::ExecutionTreeMap;
+    use lisp::ExecutionTreeObject;
+
+    #[test]
+    fn heap_size_tracks_inserts_and_removes() {
+        let mut map = ExecutionTreeMap::new();
+        let empty_size = map.get_heap_size();
+        let key = ExecutionTreeObject::String("key".to_string());
+        map.insert(key.clone(), ExecutionTreeObject::String("a".repeat(100)));
+        let inserted_size = map.get_heap_size();
+        assert!(inserted_size >= empty_size + 100);
+
+        map.insert(key.clone(), ExecutionTreeObject::String("a".repeat(10)));
+        assert!(map.get_heap_size() < inserted_size);
+        assert_eq!(map.get(&key), Some(&ExecutionTreeObject::String("a".repeat(10))));
+
+        map.remove(&key);
+        assert!(map.is_empty());
+        assert_eq!(map.entries_size, 0);
+    }
+}
@@ -1,29 +1,68 @@
 use error::*;
 use lisp::ExecutionTreeObject;
+use lisp::Span;
+use std::cmp::Ordering;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::mem;
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct ExecutionTreeNode {
+    heap_size: usize,
     objects: Vec<ExecutionTreeObject>,
+    span: Option<Span>,
 }
 
 impl ExecutionTreeNode {
     pub fn new(objects: Vec<ExecutionTreeObject>) -> Self {
         ExecutionTreeNode {
+            heap_size: Self::compute_heap_size(&objects),
             objects: objects,
+            span: None,
         }
     }
 
+    pub fn with_span(objects: Vec<ExecutionTreeObject>, span: Span) -> Self {
+        ExecutionTreeNode {
+            heap_size: Self::compute_heap_size(&objects),
+            objects: objects,
+            span: Some(span),
+        }
+    }
+
+    pub fn get_heap_size(&self) -> usize {
+        self.heap_size
+    }
+
     pub fn get_objects(&self) -> &Vec<ExecutionTreeObject> {
         &self.objects
     }
 
+    pub fn get_span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
     pub fn into_objects(self) -> Vec<ExecutionTreeObject> {
         self.objects
     }
 
+    // A keyword list is a non-empty list that alternates keywords, symbols starting with ':', and values.
+    pub fn is_keyword_list(&self) -> bool {
+        !self.objects.is_empty() &&
+        self.objects.len() % 2 == 0 &&
+        self.objects.chunks(2).all(|pair| {
+            match pair[0] {
+                ExecutionTreeObject::Symbol(ref symbol) => symbol.starts_with(':') && symbol.len() > 1,
+                _ => false,
+            }
+        })
+    }
+
     pub fn nil() -> Self {
         ExecutionTreeNode {
+            heap_size: 0,
             objects: Vec::new(),
+            span: None,
         }
     }
 
@@ -41,4 +80,41 @@ impl ExecutionTreeNode {
         result.push(')');
         Ok(result)
     }
+
+    // Nodes are immutable, so the size of the children is computed once on construction.
+    fn compute_heap_size(objects: &Vec<ExecutionTreeObject>) -> usize {
+        let unused_capacity = objects.capacity() - objects.len();
+        let mut result = unused_capacity * mem::size_of::<ExecutionTreeObject>();
+        for object in objects {
+            result += object.get_size();
+        }
+        result
+    }
+}
+
+impl PartialEq for ExecutionTreeNode {
+    fn eq(&self, other: &ExecutionTreeNode) -> bool {
+        self.objects == other.objects
+    }
+}
+
+impl Eq for ExecutionTreeNode {}
+
+impl PartialOrd for ExecutionTreeNode {
+    fn partial_cmp(&self, other: &ExecutionTreeNode) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ExecutionTreeNode {
+    fn cmp(&self, other: &ExecutionTreeNode) -> Ordering {
+        self.objects.cmp(&other.objects)
+    }
+}
+
+impl Hash for ExecutionTreeNode {
+    fn hash<THasher>(&self, state: &mut THasher)
+        where THasher: Hasher {
+        self.objects.hash(state);
+    }
 }
@@ -1,18 +1,34 @@
+use encoding;
 use error::*;
+use lisp::Exception;
+use lisp::ExecutionTreeMap;
 use lisp::ExecutionTreeNode;
-
-#[derive(Clone)]
+use lisp::Foreign;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use std::cmp::Ordering;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::mem;
+
+#[derive(Clone, Debug)]
 pub enum ExecutionTreeObject {
+    BigInt(BigInt),
     Bool(bool),
+    Bytes(Vec<u8>),
     Char(char),
+    Exception(Exception),
     F32(f32),
     F64(f64),
+    Foreign(Foreign),
     I8(i8),
     I16(i16),
     I32(i32),
     I64(i64),
     ISize(isize),
+    Map(ExecutionTreeMap),
     Node(ExecutionTreeNode),
+    Rational(BigRational),
     Symbol(String),
     String(String),
     U8(u8),
@@ -27,14 +43,26 @@ impl ExecutionTreeObject {
         ExecutionTreeObject::Node(ExecutionTreeNode::nil())
     }
 
+    pub fn big_int_str() -> &'static str {
+        "ExecutionTreeObject::BigInt"
+    }
+
     pub fn bool_str() -> &'static str {
         "ExecutionTreeObject::Bool"
     }
 
+    pub fn bytes_str() -> &'static str {
+        "ExecutionTreeObject::Bytes"
+    }
+
     pub fn char_str() -> &'static str {
         "ExecutionTreeObject::Char"
     }
 
+    pub fn exception_str() -> &'static str {
+        "ExecutionTreeObject::Exception"
+    }
+
     pub fn f32_str() -> &'static str {
         "ExecutionTreeObject::F32"
     }
@@ -43,6 +71,10 @@ impl ExecutionTreeObject {
         "ExecutionTreeObject::F64"
     }
 
+    pub fn foreign_str() -> &'static str {
+        "ExecutionTreeObject::Foreign"
+    }
+
     pub fn i8_str() -> &'static str {
         "ExecutionTreeObject::I8"
     }
@@ -63,10 +95,18 @@ impl ExecutionTreeObject {
         "ExecutionTreeObject::ISize"
     }
 
+    pub fn map_str() -> &'static str {
+        "ExecutionTreeObject::Map"
+    }
+
     pub fn node_str() -> &'static str {
         "ExecutionTreeObject::Node"
     }
 
+    pub fn rational_str() -> &'static str {
+        "ExecutionTreeObject::Rational"
+    }
+
     pub fn symbol_str() -> &'static str {
         "ExecutionTreeObject::Symbol"
     }
@@ -97,16 +137,22 @@ impl ExecutionTreeObject {
 
     pub fn enum_to_string(&self) -> &'static str {
         match self {
+            &ExecutionTreeObject::BigInt(_) => Self::big_int_str(),
             &ExecutionTreeObject::Bool(_) => Self::bool_str(),
+            &ExecutionTreeObject::Bytes(_) => Self::bytes_str(),
             &ExecutionTreeObject::Char(_) => Self::char_str(),
+            &ExecutionTreeObject::Exception(_) => Self::exception_str(),
             &ExecutionTreeObject::F32(_) => Self::f32_str(),
             &ExecutionTreeObject::F64(_) => Self::f64_str(),
+            &ExecutionTreeObject::Foreign(_) => Self::foreign_str(),
             &ExecutionTreeObject::I8(_) => Self::i8_str(),
             &ExecutionTreeObject::I16(_) => Self::i16_str(),
             &ExecutionTreeObject::I32(_) => Self::i32_str(),
             &ExecutionTreeObject::I64(_) => Self::i64_str(),
             &ExecutionTreeObject::ISize(_) => Self::isize_str(),
+            &ExecutionTreeObject::Map(_) => Self::map_str(),
             &ExecutionTreeObject::Node(_) => Self::node_str(),
+            &ExecutionTreeObject::Rational(_) => Self::rational_str(),
             &ExecutionTreeObject::Symbol(_) => Self::symbol_str(),
             &ExecutionTreeObject::String(_) => Self::string_str(),
             &ExecutionTreeObject::U8(_) => Self::u8_str(),
@@ -117,19 +163,41 @@ impl ExecutionTreeObject {
         }
     }
 
+    pub fn get_size(&self) -> usize {
+        let heap_size =
+            match self {
+                &ExecutionTreeObject::BigInt(ref some) => (some.bits() / 8) as usize,
+                &ExecutionTreeObject::Bytes(ref some) => some.capacity(),
+                &ExecutionTreeObject::Exception(ref some) => some.get_size(),
+                &ExecutionTreeObject::Map(ref some) => some.get_heap_size(),
+                &ExecutionTreeObject::Node(ref some) => some.get_heap_size(),
+                &ExecutionTreeObject::Rational(ref some) => ((some.numer().bits() + some.denom().bits()) / 8) as usize,
+                &ExecutionTreeObject::Symbol(ref some) => some.capacity(),
+                &ExecutionTreeObject::String(ref some) => some.capacity(),
+                _ => 0,
+            };
+        mem::size_of::<ExecutionTreeObject>() + heap_size
+    }
+
     pub fn to_string(&self) -> Result<String> {
         let result =
             match self {
+                &ExecutionTreeObject::BigInt(ref some) => format!("{}n", some.to_string()),
                 &ExecutionTreeObject::Bool(ref some) => some.to_string(),
+                &ExecutionTreeObject::Bytes(ref some) => encoding::escape_bytes(some),
                 &ExecutionTreeObject::Char(ref some) => format!("'{}'", some.to_string()),
+                &ExecutionTreeObject::Exception(ref some) => try!(some.to_string()),
                 &ExecutionTreeObject::F32(ref some) => format!("{}f32", some.to_string()),
                 &ExecutionTreeObject::F64(ref some) => format!("{}f64", some.to_string()),
+                &ExecutionTreeObject::Foreign(ref some) => some.to_string(),
                 &ExecutionTreeObject::I8(ref some) => format!("{}i8", some.to_string()),
                 &ExecutionTreeObject::I16(ref some) => format!("{}i16", some.to_string()),
                 &ExecutionTreeObject::I32(ref some) => format!("{}i32", some.to_string()),
                 &ExecutionTreeObject::I64(ref some) => format!("{}i64", some.to_string()),
                 &ExecutionTreeObject::ISize(ref some) => format!("{}isize", some.to_string()),
+                &ExecutionTreeObject::Map(ref some) => try!(some.to_string()),
                 &ExecutionTreeObject::Node(ref some) => try!(some.to_string()),
+                &ExecutionTreeObject::Rational(ref some) => format!("{}/{}", some.numer().to_string(), some.denom().to_string()),
                 &ExecutionTreeObject::Symbol(ref some) => some.to_string(),
                 &ExecutionTreeObject::String(ref some) => format!("\"{}\"", some),
                 &ExecutionTreeObject::U8(ref some) => format!("{}u8", some.to_string()),
@@ -140,6 +208,151 @@ impl ExecutionTreeObject {
             };
         Ok(result)
     }
+
+    fn variant_index(&self) -> usize {
+        match self {
+            &ExecutionTreeObject::BigInt(_) => 0,
+            &ExecutionTreeObject::Bool(_) => 1,
+            &ExecutionTreeObject::Bytes(_) => 2,
+            &ExecutionTreeObject::Char(_) => 3,
+            &ExecutionTreeObject::Exception(_) => 4,
+            &ExecutionTreeObject::F32(_) => 5,
+            &ExecutionTreeObject::F64(_) => 6,
+            &ExecutionTreeObject::Foreign(_) => 7,
+            &ExecutionTreeObject::I8(_) => 8,
+            &ExecutionTreeObject::I16(_) => 9,
+            &ExecutionTreeObject::I32(_) => 10,
+            &ExecutionTreeObject::I64(_) => 11,
+            &ExecutionTreeObject::ISize(_) => 12,
+            &ExecutionTreeObject::Map(_) => 13,
+            &ExecutionTreeObject::Node(_) => 14,
+            &ExecutionTreeObject::Rational(_) => 15,
+            &ExecutionTreeObject::Symbol(_) => 16,
+            &ExecutionTreeObject::String(_) => 17,
+            &ExecutionTreeObject::U8(_) => 18,
+            &ExecutionTreeObject::U16(_) => 19,
+            &ExecutionTreeObject::U32(_) => 20,
+            &ExecutionTreeObject::U64(_) => 21,
+            &ExecutionTreeObject::USize(_) => 22,
+        }
+    }
+}
+
+// Equality, ordering and hashing are structural and never convert between variants, so 1i32 and 1i64
+// are distinct. Floats compare by bit pattern under the IEEE total order, nodes compare element by
+// element ignoring source spans, and maps compare their entries regardless of insertion order.
+// Values of different variants order by their position in the enum.
+impl PartialEq for ExecutionTreeObject {
+    fn eq(&self, other: &ExecutionTreeObject) -> bool {
+        match (self, other) {
+            (&ExecutionTreeObject::BigInt(ref left), &ExecutionTreeObject::BigInt(ref right)) => left == right,
+            (&ExecutionTreeObject::Bool(ref left), &ExecutionTreeObject::Bool(ref right)) => left == right,
+            (&ExecutionTreeObject::Bytes(ref left), &ExecutionTreeObject::Bytes(ref right)) => left == right,
+            (&ExecutionTreeObject::Char(ref left), &ExecutionTreeObject::Char(ref right)) => left == right,
+            (&ExecutionTreeObject::Exception(ref left), &ExecutionTreeObject::Exception(ref right)) => left == right,
+            (&ExecutionTreeObject::F32(ref left), &ExecutionTreeObject::F32(ref right)) => left.to_bits() == right.to_bits(),
+            (&ExecutionTreeObject::F64(ref left), &ExecutionTreeObject::F64(ref right)) => left.to_bits() == right.to_bits(),
+            (&ExecutionTreeObject::Foreign(ref left), &ExecutionTreeObject::Foreign(ref right)) => left.equals(right),
+            (&ExecutionTreeObject::I8(ref left), &ExecutionTreeObject::I8(ref right)) => left == right,
+            (&ExecutionTreeObject::I16(ref left), &ExecutionTreeObject::I16(ref right)) => left == right,
+            (&ExecutionTreeObject::I32(ref left), &ExecutionTreeObject::I32(ref right)) => left == right,
+            (&ExecutionTreeObject::I64(ref left), &ExecutionTreeObject::I64(ref right)) => left == right,
+            (&ExecutionTreeObject::ISize(ref left), &ExecutionTreeObject::ISize(ref right)) => left == right,
+            (&ExecutionTreeObject::Map(ref left), &ExecutionTreeObject::Map(ref right)) => left == right,
+            (&ExecutionTreeObject::Node(ref left), &ExecutionTreeObject::Node(ref right)) => left == right,
+            (&ExecutionTreeObject::Rational(ref left), &ExecutionTreeObject::Rational(ref right)) => left == right,
+            (&ExecutionTreeObject::Symbol(ref left), &ExecutionTreeObject::Symbol(ref right)) => left == right,
+            (&ExecutionTreeObject::String(ref left), &ExecutionTreeObject::String(ref right)) => left == right,
+            (&ExecutionTreeObject::U8(ref left), &ExecutionTreeObject::U8(ref right)) => left == right,
+            (&ExecutionTreeObject::U16(ref left), &ExecutionTreeObject::U16(ref right)) => left == right,
+            (&ExecutionTreeObject::U32(ref left), &ExecutionTreeObject::U32(ref right)) => left == right,
+            (&ExecutionTreeObject::U64(ref left), &ExecutionTreeObject::U64(ref right)) => left == right,
+            (&ExecutionTreeObject::USize(ref left), &ExecutionTreeObject::USize(ref right)) => left == right,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ExecutionTreeObject {}
+
+impl Hash for ExecutionTreeObject {
+    fn hash<THasher>(&self, state: &mut THasher)
+        where THasher: Hasher {
+        mem::discriminant(self).hash(state);
+        match self {
+            &ExecutionTreeObject::BigInt(ref some) => some.hash(state),
+            &ExecutionTreeObject::Bool(ref some) => some.hash(state),
+            &ExecutionTreeObject::Bytes(ref some) => some.hash(state),
+            &ExecutionTreeObject::Char(ref some) => some.hash(state),
+            &ExecutionTreeObject::Exception(ref some) => some.hash(state),
+            &ExecutionTreeObject::F32(ref some) => some.to_bits().hash(state),
+            &ExecutionTreeObject::F64(ref some) => some.to_bits().hash(state),
+            &ExecutionTreeObject::Foreign(ref some) => some.hash_code().hash(state),
+            &ExecutionTreeObject::I8(ref some) => some.hash(state),
+            &ExecutionTreeObject::I16(ref some) => some.hash(state),
+            &ExecutionTreeObject::I32(ref some) => some.hash(state),
+            &ExecutionTreeObject::I64(ref some) => some.hash(state),
+            &ExecutionTreeObject::ISize(ref some) => some.hash(state),
+            &ExecutionTreeObject::Map(ref some) => some.hash(state),
+            &ExecutionTreeObject::Node(ref some) => some.hash(state),
+            &ExecutionTreeObject::Rational(ref some) => some.hash(state),
+            &ExecutionTreeObject::Symbol(ref some) => some.hash(state),
+            &ExecutionTreeObject::String(ref some) => some.hash(state),
+            &ExecutionTreeObject::U8(ref some) => some.hash(state),
+            &ExecutionTreeObject::U16(ref some) => some.hash(state),
+            &ExecutionTreeObject::U32(ref some) => some.hash(state),
+            &ExecutionTreeObject::U64(ref some) => some.hash(state),
+            &ExecutionTreeObject::USize(ref some) => some.hash(state),
+        }
+    }
+}
+
+impl PartialOrd for ExecutionTreeObject {
+    fn partial_cmp(&self, other: &ExecutionTreeObject) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ExecutionTreeObject {
+    fn cmp(&self, other: &ExecutionTreeObject) -> Ordering {
+        match (self, other) {
+            (&ExecutionTreeObject::BigInt(ref left), &ExecutionTreeObject::BigInt(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::Bool(ref left), &ExecutionTreeObject::Bool(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::Bytes(ref left), &ExecutionTreeObject::Bytes(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::Char(ref left), &ExecutionTreeObject::Char(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::Exception(ref left), &ExecutionTreeObject::Exception(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::F32(ref left), &ExecutionTreeObject::F32(ref right)) => left.total_cmp(right),
+            (&ExecutionTreeObject::F64(ref left), &ExecutionTreeObject::F64(ref right)) => left.total_cmp(right),
+            (&ExecutionTreeObject::Foreign(ref left), &ExecutionTreeObject::Foreign(ref right)) => left.compare(right),
+            (&ExecutionTreeObject::I8(ref left), &ExecutionTreeObject::I8(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::I16(ref left), &ExecutionTreeObject::I16(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::I32(ref left), &ExecutionTreeObject::I32(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::I64(ref left), &ExecutionTreeObject::I64(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::ISize(ref left), &ExecutionTreeObject::ISize(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::Map(ref left), &ExecutionTreeObject::Map(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::Node(ref left), &ExecutionTreeObject::Node(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::Rational(ref left), &ExecutionTreeObject::Rational(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::Symbol(ref left), &ExecutionTreeObject::Symbol(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::String(ref left), &ExecutionTreeObject::String(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::U8(ref left), &ExecutionTreeObject::U8(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::U16(ref left), &ExecutionTreeObject::U16(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::U32(ref left), &ExecutionTreeObject::U32(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::U64(ref left), &ExecutionTreeObject::U64(ref right)) => left.cmp(right),
+            (&ExecutionTreeObject::USize(ref left), &ExecutionTreeObject::USize(ref right)) => left.cmp(right),
+            (left, right) => left.variant_index().cmp(&right.variant_index()),
+        }
+    }
+}
+
+impl From<ExecutionTreeObject> for Result<BigInt> {
+    fn from(object: ExecutionTreeObject) -> Result<BigInt> {
+        match object {
+            ExecutionTreeObject::BigInt(result) => Ok(result),
+            object => {
+                Err(ErrorKind::InvalidExecutionTreeObjectConversion(object.enum_to_string().to_string(), ExecutionTreeObject::big_int_str().to_string()).into())
+            },
+        }
+    }
 }
 
 impl From<ExecutionTreeObject> for Result<bool> {
@@ -153,6 +366,17 @@ impl From<ExecutionTreeObject> for Result<bool> {
     }
 }
 
+impl From<ExecutionTreeObject> for Result<Vec<u8>> {
+    fn from(object: ExecutionTreeObject) -> Result<Vec<u8>> {
+        match object {
+            ExecutionTreeObject::Bytes(result) => Ok(result),
+            object => {
+                Err(ErrorKind::InvalidExecutionTreeObjectConversion(object.enum_to_string().to_string(), ExecutionTreeObject::bytes_str().to_string()).into())
+            },
+        }
+    }
+}
+
 impl From<ExecutionTreeObject> for Result<char> {
     fn from(object: ExecutionTreeObject) -> Result<char> {
         match object {
@@ -164,6 +388,23 @@ impl From<ExecutionTreeObject> for Result<char> {
     }
 }
 
+impl From<ExecutionTreeObject> for Result<Exception> {
+    fn from(object: ExecutionTreeObject) -> Result<Exception> {
+        match object {
+            ExecutionTreeObject::Exception(result) => Ok(result),
+            object => {
+                Err(ErrorKind::InvalidExecutionTreeObjectConversion(object.enum_to_string().to_string(), ExecutionTreeObject::exception_str().to_string()).into())
+            },
+        }
+    }
+}
+
+impl From<ExecutionTreeObject> for Result<ExecutionTreeObject> {
+    fn from(object: ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        Ok(object)
+    }
+}
+
 impl From<ExecutionTreeObject> for Result<f32> {
     fn from(object: ExecutionTreeObject) -> Result<f32> {
         match object {
@@ -186,6 +427,17 @@ impl From<ExecutionTreeObject> for Result<f64> {
     }
 }
 
+impl From<ExecutionTreeObject> for Result<Foreign> {
+    fn from(object: ExecutionTreeObject) -> Result<Foreign> {
+        match object {
+            ExecutionTreeObject::Foreign(result) => Ok(result),
+            object => {
+                Err(ErrorKind::InvalidExecutionTreeObjectConversion(object.enum_to_string().to_string(), ExecutionTreeObject::foreign_str().to_string()).into())
+            },
+        }
+    }
+}
+
 impl From<ExecutionTreeObject> for Result<i8> {
     fn from(object: ExecutionTreeObject) -> Result<i8> {
         match object {
@@ -241,6 +493,17 @@ impl From<ExecutionTreeObject> for Result<isize> {
     }
 }
 
+impl From<ExecutionTreeObject> for Result<ExecutionTreeMap> {
+    fn from(object: ExecutionTreeObject) -> Result<ExecutionTreeMap> {
+        match object {
+            ExecutionTreeObject::Map(result) => Ok(result),
+            object => {
+                Err(ErrorKind::InvalidExecutionTreeObjectConversion(object.enum_to_string().to_string(), ExecutionTreeObject::map_str().to_string()).into())
+            },
+        }
+    }
+}
+
 impl From<ExecutionTreeObject> for Result<ExecutionTreeNode> {
     fn from(object: ExecutionTreeObject) -> Result<ExecutionTreeNode> {
         match object {
@@ -252,6 +515,17 @@ impl From<ExecutionTreeObject> for Result<ExecutionTreeNode> {
     }
 }
 
+impl From<ExecutionTreeObject> for Result<BigRational> {
+    fn from(object: ExecutionTreeObject) -> Result<BigRational> {
+        match object {
+            ExecutionTreeObject::Rational(result) => Ok(result),
+            object => {
+                Err(ErrorKind::InvalidExecutionTreeObjectConversion(object.enum_to_string().to_string(), ExecutionTreeObject::rational_str().to_string()).into())
+            },
+        }
+    }
+}
+
 impl From<ExecutionTreeObject> for Result<String> {
     fn from(object: ExecutionTreeObject) -> Result<String> {
         match object {
@@ -0,0 +1,125 @@
+use error::*;
+use lisp::ModuleLoader;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+pub struct FileSystemModuleLoader {
+    root: PathBuf,
+}
+
+impl FileSystemModuleLoader {
+    pub fn new(root: PathBuf) -> Self {
+        FileSystemModuleLoader {
+            root: root,
+        }
+    }
+
+    pub fn extension_str() -> &'static str {
+        "lisp"
+    }
+
+    pub fn get_root(&self) -> &PathBuf {
+        &self.root
+    }
+
+    fn canonicalize(path: &str, file_path: &Path) -> Result<PathBuf> {
+        match file_path.canonicalize() {
+            Ok(result) => Ok(result),
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => {
+                Err(ErrorKind::ModuleNotFound(path.to_string()).into())
+            },
+            Err(error) => Err(error).wrap_err_to_err(),
+        }
+    }
+
+    fn is_relative_path(path: &Path) -> bool {
+        path.components().next().is_some() &&
+        path.components().all(|component| {
+            match component {
+                Component::Normal(_) => true,
+                _ => false,
+            }
+        })
+    }
+}
+
+impl ModuleLoader for FileSystemModuleLoader {
+    // Module paths are resolved below the root, and symbolic links are followed before checking that
+    // the file is still inside it.
+    fn load(&self, path: &str) -> Result<String> {
+        let relative_path = Path::new(path);
+        if !Self::is_relative_path(relative_path) {
+            return Err(ErrorKind::InvalidModulePath(path.to_string()).into());
+        }
+
+        let mut file_path = self.root.join(relative_path);
+        if file_path.extension().is_none() {
+            file_path.set_extension(Self::extension_str());
+        }
+
+        let file_path = try!(Self::canonicalize(path, &file_path));
+        let root = try!(Self::canonicalize(path, &self.root));
+        if !file_path.starts_with(&root) {
+            return Err(ErrorKind::InvalidModulePath(path.to_string()).into());
+        }
+
+        let mut file = try!(File::open(&file_path).wrap_err_to_err());
+        let mut result = String::new();
+        try!(file.read_to_string(&mut result).wrap_err_to_err());
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use error::*;
+    use lisp::FileSystemModuleLoader;
+    use lisp::ModuleLoader;
+    use std::env;
+    use std::fs;
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::process;
+
+    fn new_root(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("simplisp-{}-{}", name, process::id()));
+        fs::create_dir_all(root.join("modules")).unwrap();
+        let mut file = File::create(root.join("modules").join("util.lisp")).unwrap();
+        file.write_all(b"(define x 1)").unwrap();
+        let mut file = File::create(root.join("secret.lisp")).unwrap();
+        file.write_all(b"(define secret 1)").unwrap();
+        root
+    }
+
+    fn assert_error_kind(result: Result<String>, expected: &str) {
+        match result {
+            Err(error) => assert_eq!(error.kind().enum_to_string(), expected),
+            Ok(ok) => panic!("unexpected success, {}", ok),
+        }
+    }
+
+    #[test]
+    fn loads_modules_below_the_root() {
+        let root = new_root("load");
+        let loader = FileSystemModuleLoader::new(root.join("modules"));
+        assert_eq!(loader.load("util").unwrap(), "(define x 1)");
+        assert_error_kind(loader.load("missing"), "ErrorKind::ModuleNotFound");
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_root() {
+        let root = new_root("contain");
+        let loader = FileSystemModuleLoader::new(root.join("modules"));
+        assert_error_kind(loader.load("../secret"), "ErrorKind::InvalidModulePath");
+        assert_error_kind(loader.load("./util"), "ErrorKind::InvalidModulePath");
+        let absolute_path = root.join("secret.lisp").to_string_lossy().into_owned();
+        assert_error_kind(loader.load(&absolute_path), "ErrorKind::InvalidModulePath");
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,208 @@
+use lisp::LispObject;
+use std::any;
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::Arc;
+
+pub struct Foreign {
+    eq: Option<Arc<Fn(&Any, &Any) -> bool + Send + Sync>>,
+    hash: Option<Arc<Fn(&Any) -> u64 + Send + Sync>>,
+    object: Option<Arc<LispObject>>,
+    print: Option<Arc<Fn(&Any) -> String + Send + Sync>>,
+    type_name: &'static str,
+    value: Arc<Any + Send + Sync>,
+}
+
+impl Foreign {
+    pub fn new<T>(value: T) -> Self
+        where T: Any + Send + Sync {
+        Self::from_arc(Arc::new(value))
+    }
+
+    pub fn from_arc<T>(value: Arc<T>) -> Self
+        where T: Any + Send + Sync {
+        Foreign {
+            eq: None,
+            hash: None,
+            object: None,
+            print: None,
+            type_name: any::type_name::<T>(),
+            value: value,
+        }
+    }
+
+    pub fn from_object<T>(value: T) -> Self
+        where T: LispObject {
+        let value = Arc::new(value);
+        let mut result = Self::from_arc(value.clone());
+        result.object = Some(value);
+        result
+    }
+
+    pub fn compare(&self, other: &Foreign) -> Ordering {
+        if self.equals(other) {
+            return Ordering::Equal;
+        }
+
+        self.type_name.cmp(other.type_name)
+            .then(self.hash_code().cmp(&other.hash_code()))
+            .then(self.get_address().cmp(&other.get_address()))
+    }
+
+    pub fn downcast_arc<T>(&self) -> Option<Arc<T>>
+        where T: Any + Send + Sync {
+        match self.value.clone().downcast::<T>() {
+            Ok(ok) => Some(ok),
+            Err(_) => None,
+        }
+    }
+
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+        where T: Any + Send + Sync {
+        self.value.downcast_ref::<T>()
+    }
+
+    pub fn equals(&self, other: &Foreign) -> bool {
+        if Arc::ptr_eq(&self.value, &other.value) {
+            return true;
+        }
+
+        // The eq hook is only used when both sides carry one, so equality stays symmetric.
+        match (&self.eq, &other.eq) {
+            (&Some(ref eq), &Some(_)) if self.type_name == other.type_name => eq(&*self.value, &*other.value),
+            _ => false,
+        }
+    }
+
+    pub fn get_address(&self) -> usize {
+        &*self.value as *const Any as *const () as usize
+    }
+
+    pub fn get_object(&self) -> Option<&Arc<LispObject>> {
+        self.object.as_ref()
+    }
+
+    pub fn get_type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn get_value(&self) -> &Arc<Any + Send + Sync> {
+        &self.value
+    }
+
+    // Values with an eq hook but no hash hook can be equal at different addresses, so they hash by
+    // type name alone.
+    pub fn hash_code(&self) -> u64 {
+        match (&self.hash, &self.eq) {
+            (&Some(ref hash), _) => hash(&*self.value),
+            (&None, &Some(_)) => {
+                let mut hasher = DefaultHasher::new();
+                self.type_name.hash(&mut hasher);
+                hasher.finish()
+            },
+            (&None, &None) => {
+                let mut hasher = DefaultHasher::new();
+                self.get_address().hash(&mut hasher);
+                hasher.finish()
+            },
+        }
+    }
+
+    pub fn is<T>(&self) -> bool
+        where T: Any + Send + Sync {
+        self.value.is::<T>()
+    }
+
+    pub fn to_string(&self) -> String {
+        match (&self.print, &self.object) {
+            (&Some(ref print), _) => print(&*self.value),
+            (&None, &Some(ref object)) => object.to_string(),
+            (&None, &None) => format!("#<foreign {}>", self.type_name),
+        }
+    }
+
+    pub fn with_eq<T, TFn>(mut self, eq: TFn) -> Self
+        where T: Any, TFn: Fn(&T, &T) -> bool + Send + Sync + 'static {
+        let eq =
+            move |left: &Any, right: &Any| {
+                match (left.downcast_ref::<T>(), right.downcast_ref::<T>()) {
+                    (Some(left), Some(right)) => eq(left, right),
+                    _ => false,
+                }
+            };
+        self.eq = Some(Arc::new(eq));
+        self
+    }
+
+    pub fn with_hash<T, TFn>(mut self, hash: TFn) -> Self
+        where T: Any, TFn: Fn(&T) -> u64 + Send + Sync + 'static {
+        let hash =
+            move |value: &Any| {
+                match value.downcast_ref::<T>() {
+                    Some(value) => hash(value),
+                    None => 0,
+                }
+            };
+        self.hash = Some(Arc::new(hash));
+        self
+    }
+
+    pub fn with_print<T, TFn>(mut self, print: TFn) -> Self
+        where T: Any, TFn: Fn(&T) -> String + Send + Sync + 'static {
+        let type_name = self.type_name;
+        let print =
+            move |value: &Any| {
+                match value.downcast_ref::<T>() {
+                    Some(value) => print(value),
+                    None => format!("#<foreign {}>", type_name),
+                }
+            };
+        self.print = Some(Arc::new(print));
+        self
+    }
+}
+
+impl Clone for Foreign {
+    fn clone(&self) -> Self {
+        Foreign {
+            eq: self.eq.clone(),
+            hash: self.hash.clone(),
+            object: self.object.clone(),
+            print: self.print.clone(),
+            type_name: self.type_name,
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for Foreign {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Foreign({})", self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lisp::Foreign;
+
+    #[test]
+    fn equal_values_without_a_hash_hook_hash_alike() {
+        let left = Foreign::new(5u32).with_eq(|left: &u32, right: &u32| left == right);
+        let right = Foreign::new(5u32).with_eq(|left: &u32, right: &u32| left == right);
+        assert!(left.equals(&right));
+        assert_eq!(left.hash_code(), right.hash_code());
+    }
+
+    #[test]
+    fn eq_hooks_are_only_used_when_both_sides_carry_one() {
+        let with_eq = Foreign::new(5u32).with_eq(|_: &u32, _: &u32| true);
+        let without_eq = Foreign::new(5u32);
+        assert!(!with_eq.equals(&without_eq));
+        assert!(!without_eq.equals(&with_eq));
+        assert!(without_eq.equals(&without_eq.clone()));
+    }
+}
@@ -1,19 +1,73 @@
+use lisp::IntoNativeFunction;
 use lisp::Symbol;
 use std::collections::BTreeMap;
+use std::collections::btree_map::Iter;
 
 pub struct Frame<TArg> {
+    docs: BTreeMap<String, String>,
+    generation: usize,
+    size: usize,
     symbols: BTreeMap<String, Symbol<TArg>>,
 }
 
 impl <TArg> Frame<TArg> {
     pub fn new() -> Self {
         Frame {
+            docs: BTreeMap::new(),
+            generation: 0,
+            size: 0,
             symbols: BTreeMap::new(),
         }
     }
 
+    pub fn get_doc(&self, symbol: &str) -> Option<&String> {
+        self.docs.get(symbol)
+    }
+
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
     pub fn insert(&mut self, key: String, value: Symbol<TArg>) -> Option<Symbol<TArg>> {
-        self.symbols.insert(key, value)
+        let key_size = key.capacity();
+        self.generation += 1;
+        self.size += key_size + value.get_size();
+        let result = self.symbols.insert(key, value);
+        if let Some(ref replaced) = result {
+            self.size -= key_size + replaced.get_size();
+        }
+        result
+    }
+
+    pub fn iter(&self) -> Iter<'_, String, Symbol<TArg>> {
+        self.symbols.iter()
+    }
+
+    pub fn register_fn<TMarker, TFn>(&mut self, name: &str, func: TFn) -> Option<Symbol<TArg>>
+        where TFn: IntoNativeFunction<TMarker> {
+        self.insert(name.to_string(), Symbol::NativeFunc(func.into_native_function()))
+    }
+
+    pub fn set_doc(&mut self, symbol: String, doc: String) {
+        self.docs.insert(symbol, doc);
+    }
+
+    pub fn to_string(&self) -> String {
+        let mut result = String::new();
+        result.push('(');
+        for key in self.symbols.keys() {
+            result.push_str(key);
+            result.push(' ');
+        }
+        if result.len() != 1 {
+            result.pop();
+        }
+        result.push(')');
+        result
     }
 
     pub fn try_get(&self, symbol: &str) -> Option<Symbol<TArg>> {
@@ -0,0 +1,9 @@
+#[derive(Clone, Copy, Debug)]
+pub enum Instruction {
+    CallSymbol(usize, usize, usize, usize),
+    Constant(usize),
+    ExitTrace(usize),
+    LoadSymbol(usize),
+    MakeList(usize),
+    Trace(usize),
+}
@@ -0,0 +1,54 @@
+use lisp::ExecutionTreeObject;
+use lisp::NativeArgument;
+use lisp::NativeFunction;
+use lisp::NativeReturn;
+use std::rc::Rc;
+
+pub trait IntoNativeFunction<TMarker> {
+    fn into_native_function(self) -> NativeFunction;
+}
+
+macro_rules! impl_into_native_function {
+    ($($argument:ident),*) => {
+        impl <TFn, TReturn, $($argument),*> IntoNativeFunction<(TReturn, $($argument,)*)> for TFn
+            where TFn: Fn($($argument),*) -> TReturn + 'static,
+                  TReturn: NativeReturn,
+                  $($argument: NativeArgument),* {
+            #[allow(unused_mut, unused_variables)]
+            fn into_native_function(self) -> NativeFunction {
+                let mut min_count = 0;
+                let mut max_count = Some(0);
+                $(
+                    min_count += $argument::get_min_count();
+                    max_count =
+                        match (max_count, $argument::get_max_count()) {
+                            (Some(max_count), Some(count)) => Some(max_count + count),
+                            _ => None,
+                        };
+                )*
+
+                let func =
+                    move |objects: Vec<ExecutionTreeObject>| {
+                        let mut optional_count = objects.len().saturating_sub(min_count);
+                        let mut objects = objects.into_iter();
+                        $(
+                            #[allow(non_snake_case)]
+                            let $argument = try!($argument::take(&mut objects, &mut optional_count));
+                        )*
+                        (self)($($argument),*).into_object()
+                    };
+                NativeFunction::new(min_count, max_count, Rc::new(func))
+            }
+        }
+    };
+}
+
+impl_into_native_function!();
+impl_into_native_function!(A1);
+impl_into_native_function!(A1, A2);
+impl_into_native_function!(A1, A2, A3);
+impl_into_native_function!(A1, A2, A3, A4);
+impl_into_native_function!(A1, A2, A3, A4, A5);
+impl_into_native_function!(A1, A2, A3, A4, A5, A6);
+impl_into_native_function!(A1, A2, A3, A4, A5, A6, A7);
+impl_into_native_function!(A1, A2, A3, A4, A5, A6, A7, A8);
@@ -0,0 +1,22 @@
+use error::*;
+use lisp::ExecutionTreeObject;
+use std::any;
+use std::any::Any;
+
+pub trait LispObject: Any + Send + Sync {
+    fn call_method(&self, name: &str, _args: Vec<ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+        Err(ErrorKind::UndefinedMethod(self.get_type_name().to_string(), name.to_string()).into())
+    }
+
+    fn get_property(&self, name: &str) -> Result<ExecutionTreeObject> {
+        Err(ErrorKind::UndefinedProperty(self.get_type_name().to_string(), name.to_string()).into())
+    }
+
+    fn get_type_name(&self) -> &str {
+        any::type_name::<Self>()
+    }
+
+    fn to_string(&self) -> String {
+        format!("#<{}>", self.get_type_name())
+    }
+}
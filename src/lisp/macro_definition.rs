@@ -0,0 +1,133 @@
+use error::*;
+use lisp::ExecutionTreeNode;
+use lisp::ExecutionTreeObject;
+use lisp::Frame;
+use lisp::Symbol;
+use std::mem;
+
+#[derive(Clone, Debug)]
+pub struct MacroDefinition {
+    body: Vec<ExecutionTreeObject>,
+    name: String,
+    parameters: Vec<String>,
+    rest_parameter: Option<String>,
+}
+
+impl MacroDefinition {
+    pub fn new(name: String, parameters: Vec<String>, rest_parameter: Option<String>, body: Vec<ExecutionTreeObject>) -> Self {
+        MacroDefinition {
+            body: body,
+            name: name,
+            parameters: parameters,
+            rest_parameter: rest_parameter,
+        }
+    }
+
+    pub fn bind<TArg>(&self, args: Vec<&ExecutionTreeObject>) -> Result<Frame<TArg>> {
+        let parameters_len = self.parameters.len();
+        let args_len = args.len();
+        let is_valid =
+            match self.rest_parameter {
+                Some(_) => args_len >= parameters_len,
+                None => args_len == parameters_len,
+            };
+        if !is_valid {
+            let expected =
+                match self.rest_parameter {
+                    Some(_) => format!("at least {}", parameters_len),
+                    None => parameters_len.to_string(),
+                };
+            return Err(ErrorKind::InvalidArgumentCount(self.name.clone(), expected, args_len).into());
+        }
+
+        let mut frame = Frame::new();
+        let mut args = args.into_iter();
+        for parameter in &self.parameters {
+            if let Some(object) = args.next() {
+                frame.insert(parameter.clone(), Symbol::Object(object.clone()));
+            }
+        }
+        if let Some(ref rest_parameter) = self.rest_parameter {
+            let rest = args.cloned().collect();
+            let rest = ExecutionTreeObject::Node(ExecutionTreeNode::new(rest));
+            frame.insert(rest_parameter.clone(), Symbol::Object(rest));
+        }
+        Ok(frame)
+    }
+
+    pub fn get_body(&self) -> &Vec<ExecutionTreeObject> {
+        &self.body
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_parameters(&self) -> &Vec<String> {
+        &self.parameters
+    }
+
+    pub fn get_rest_parameter(&self) -> Option<&String> {
+        self.rest_parameter.as_ref()
+    }
+
+    pub fn get_size(&self) -> usize {
+        let mut result = mem::size_of::<MacroDefinition>() + self.name.capacity();
+        for parameter in &self.parameters {
+            result += parameter.capacity();
+        }
+        for object in &self.body {
+            result += object.get_size();
+        }
+        result
+    }
+
+    pub fn parse(name: String, parameter_list: &ExecutionTreeObject, body: Vec<ExecutionTreeObject>) -> Result<Self> {
+        let parameter_objects =
+            match parameter_list {
+                &ExecutionTreeObject::Node(ref node) => node.get_objects(),
+                other => {
+                    return Err(Self::invalid_parameter_list(&name, other));
+                },
+            };
+
+        let mut parameters = Vec::new();
+        let mut rest_parameter = None;
+        let mut objects = parameter_objects.iter();
+        while let Some(object) = objects.next() {
+            match object {
+                &ExecutionTreeObject::Symbol(ref symbol) if symbol == Self::rest_str() => {
+                    match (objects.next(), objects.next()) {
+                        (Some(&ExecutionTreeObject::Symbol(ref rest)), None) => {
+                            rest_parameter = Some(rest.clone());
+                        },
+                        _ => {
+                            return Err(Self::invalid_parameter_list(&name, parameter_list));
+                        },
+                    }
+                },
+                &ExecutionTreeObject::Symbol(ref symbol) => {
+                    parameters.push(symbol.clone());
+                },
+                _ => {
+                    return Err(Self::invalid_parameter_list(&name, parameter_list));
+                },
+            }
+        }
+
+        Ok(Self::new(name, parameters, rest_parameter, body))
+    }
+
+    pub fn rest_str() -> &'static str {
+        "&rest"
+    }
+
+    fn invalid_parameter_list(name: &str, parameter_list: &ExecutionTreeObject) -> Error {
+        let form =
+            match parameter_list.to_string() {
+                Ok(ok) => ok,
+                Err(_) => parameter_list.enum_to_string().to_string(),
+            };
+        ErrorKind::InvalidForm(name.to_string(), form).into()
+    }
+}
@@ -0,0 +1,28 @@
+use error::*;
+use lisp::ModuleLoader;
+use std::collections::BTreeMap;
+
+pub struct MemoryModuleLoader {
+    modules: BTreeMap<String, String>,
+}
+
+impl MemoryModuleLoader {
+    pub fn new() -> Self {
+        MemoryModuleLoader {
+            modules: BTreeMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, path: String, source: String) -> Option<String> {
+        self.modules.insert(path, source)
+    }
+}
+
+impl ModuleLoader for MemoryModuleLoader {
+    fn load(&self, path: &str) -> Result<String> {
+        match self.modules.get(path) {
+            Some(source) => Ok(source.clone()),
+            None => Err(ErrorKind::ModuleNotFound(path.to_string()).into()),
+        }
+    }
+}
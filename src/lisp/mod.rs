@@ -4,36 +4,176 @@ mod abstract_syntax_tree_object;
 
 mod abstract_syntax_tree_node;
 
+mod begin_flattening_pass;
+
+mod breakpoint;
+
+mod bytecode;
+
+mod constant_folding_pass;
+
+mod constant_inlining_pass;
+
+mod coverage;
+
+mod dead_branch_elimination_pass;
+
+mod debugger;
+
+mod debugger_action;
+
+mod debugger_console;
+
+mod debugger_front_end;
+
 mod environment;
 
+mod evaluation_hook;
+
+mod exception;
+
 mod execution_tree;
 
+mod execution_tree_map;
+
 mod execution_tree_node;
 
 mod execution_tree_object;
 
+mod file_system_module_loader;
+
+mod foreign;
+
 mod frame;
 
+mod instruction;
+
+mod into_native_function;
+
 mod last_char_type;
 
+mod lisp_object;
+
+mod macro_definition;
+
+mod memory_module_loader;
+
+mod module;
+
+mod module_loader;
+
+mod module_scope;
+
+mod native_argument;
+
+
+mod native_function;
+
+mod native_return;
+
+mod optimization_pass;
+
+mod optimizer;
+
+mod overflow_policy;
+
+mod pause_reason;
+
+mod profile_entry;
+
+mod profiler;
+
+mod span;
+
 mod symbol;
 
+mod virtual_machine;
+
 pub use lisp::abstract_syntax_tree::AbstractSyntaxTree;
 
 pub use lisp::abstract_syntax_tree_object::AbstractSyntaxTreeObject;
 
 pub use lisp::abstract_syntax_tree_node::AbstractSyntaxTreeNode;
 
+pub use lisp::begin_flattening_pass::BeginFlatteningPass;
+
+pub use lisp::breakpoint::Breakpoint;
+
+pub use lisp::bytecode::Bytecode;
+
+pub use lisp::constant_folding_pass::ConstantFoldingPass;
+
+pub use lisp::constant_inlining_pass::ConstantInliningPass;
+
+pub use lisp::coverage::Coverage;
+
+pub use lisp::dead_branch_elimination_pass::DeadBranchEliminationPass;
+
+pub use lisp::debugger::Debugger;
+
+pub use lisp::debugger_action::DebuggerAction;
+
+pub use lisp::debugger_console::DebuggerConsole;
+
+pub use lisp::debugger_front_end::DebuggerFrontEnd;
+
 pub use lisp::environment::Environment;
 
+pub use lisp::evaluation_hook::EvaluationHook;
+
+pub use lisp::exception::Exception;
+
 pub use lisp::execution_tree::ExecutionTree;
 
+pub use lisp::execution_tree_map::ExecutionTreeMap;
+
 pub use lisp::execution_tree_node::ExecutionTreeNode;
 
 pub use lisp::execution_tree_object::ExecutionTreeObject;
 
+pub use lisp::file_system_module_loader::FileSystemModuleLoader;
+
+pub use lisp::foreign::Foreign;
+
 pub use lisp::frame::Frame;
 
+pub use lisp::instruction::Instruction;
+
+pub use lisp::into_native_function::IntoNativeFunction;
+
 pub use lisp::last_char_type::LastCharType;
 
+pub use lisp::lisp_object::LispObject;
+
+pub use lisp::macro_definition::MacroDefinition;
+
+pub use lisp::memory_module_loader::MemoryModuleLoader;
+
+pub use lisp::module::Module;
+
+pub use lisp::module_loader::ModuleLoader;
+
+pub use lisp::native_argument::NativeArgument;
+
+
+pub use lisp::native_function::NativeFunction;
+
+pub use lisp::native_return::NativeReturn;
+
+pub use lisp::optimization_pass::OptimizationPass;
+
+pub use lisp::optimizer::Optimizer;
+
+pub use lisp::overflow_policy::OverflowPolicy;
+
+pub use lisp::pause_reason::PauseReason;
+
+pub use lisp::profile_entry::ProfileEntry;
+
+pub use lisp::profiler::Profiler;
+
+pub use lisp::span::Span;
+
 pub use lisp::symbol::Symbol;
+
+pub use lisp::virtual_machine::VirtualMachine;
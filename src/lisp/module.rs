@@ -0,0 +1,35 @@
+use lisp::Frame;
+use lisp::Symbol;
+
+pub struct Module<TArg> {
+    exports: Frame<TArg>,
+    path: String,
+}
+
+impl <TArg> Module<TArg> {
+    pub fn new(path: String, exports: Frame<TArg>) -> Self {
+        Module {
+            exports: exports,
+            path: path,
+        }
+    }
+
+    pub fn alias_from_path(path: &str) -> &str {
+        match path.rfind('/') {
+            Some(index) => &path[(index + 1)..],
+            None => path,
+        }
+    }
+
+    pub fn get_exports(&self) -> &Frame<TArg> {
+        &self.exports
+    }
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn try_get(&self, symbol: &str) -> Option<Symbol<TArg>> {
+        self.exports.try_get(symbol)
+    }
+}
@@ -0,0 +1,5 @@
+use error::*;
+
+pub trait ModuleLoader {
+    fn load(&self, path: &str) -> Result<String>;
+}
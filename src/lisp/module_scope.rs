@@ -0,0 +1,25 @@
+pub struct ModuleScope {
+    exports: Vec<String>,
+    path: String,
+}
+
+impl ModuleScope {
+    pub fn new(path: String) -> Self {
+        ModuleScope {
+            exports: Vec::new(),
+            path: path,
+        }
+    }
+
+    pub fn get_exports(&self) -> &Vec<String> {
+        &self.exports
+    }
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn push_export(&mut self, symbol: String) {
+        self.exports.push(symbol);
+    }
+}
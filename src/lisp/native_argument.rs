@@ -0,0 +1,146 @@
+use error::*;
+use lisp::Exception;
+use lisp::ExecutionTreeMap;
+use lisp::ExecutionTreeNode;
+use lisp::ExecutionTreeObject;
+use lisp::Foreign;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use std::any;
+use std::any::Any;
+use std::sync::Arc;
+use std::vec::IntoIter;
+
+// Arguments are taken from left to right. Each takes its minimum count of objects, and optional
+// arguments share the objects beyond the total minimum, so an optional argument before a required
+// one is only filled when there are enough objects for both.
+pub trait NativeArgument: Sized {
+    fn get_max_count() -> Option<usize>;
+
+    fn get_min_count() -> usize;
+
+    fn take(objects: &mut IntoIter<ExecutionTreeObject>, optional_count: &mut usize) -> Result<Self>;
+}
+
+macro_rules! impl_native_argument {
+    ($($native_type:ty),*) => {
+        $(
+            impl NativeArgument for $native_type {
+                fn get_max_count() -> Option<usize> {
+                    Some(1)
+                }
+
+                fn get_min_count() -> usize {
+                    1
+                }
+
+                fn take(objects: &mut IntoIter<ExecutionTreeObject>, _: &mut usize) -> Result<Self> {
+                    match objects.next() {
+                        Some(object) => From::from(object),
+                        None => Err(ErrorKind::InvalidExecutionTreeObjectConversion("nothing".to_string(), stringify!($native_type).to_string()).into()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_native_argument!(BigInt, BigRational, bool, char, Exception, ExecutionTreeMap, ExecutionTreeNode, ExecutionTreeObject, f32, f64, Foreign, i8, i16, i32, i64, isize, String, u8, u16, u32, u64, usize);
+
+impl <T> NativeArgument for Option<T>
+    where Result<T>: From<ExecutionTreeObject> {
+    fn get_max_count() -> Option<usize> {
+        Some(1)
+    }
+
+    fn get_min_count() -> usize {
+        0
+    }
+
+    // Nil is passed as None.
+    fn take(objects: &mut IntoIter<ExecutionTreeObject>, optional_count: &mut usize) -> Result<Self> {
+        if *optional_count == 0 {
+            return Ok(None);
+        }
+
+        *optional_count -= 1;
+        match objects.next() {
+            Some(ExecutionTreeObject::Node(ref node)) if node.get_objects().is_empty() => Ok(None),
+            Some(object) => {
+                let result: T = try!(From::from(object));
+                Ok(Some(result))
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+impl <T> NativeArgument for Arc<T>
+    where T: Any + Send + Sync {
+    fn get_max_count() -> Option<usize> {
+        Some(1)
+    }
+
+    fn get_min_count() -> usize {
+        1
+    }
+
+    fn take(objects: &mut IntoIter<ExecutionTreeObject>, optional_count: &mut usize) -> Result<Self> {
+        let foreign: Foreign = try!(<Foreign as NativeArgument>::take(objects, optional_count));
+        match foreign.downcast_arc::<T>() {
+            Some(result) => Ok(result),
+            None => Err(ErrorKind::InvalidExecutionTreeObjectConversion(foreign.get_type_name().to_string(), any::type_name::<T>().to_string()).into()),
+        }
+    }
+}
+
+impl <T> NativeArgument for Vec<T>
+    where Result<T>: From<ExecutionTreeObject> {
+    fn get_max_count() -> Option<usize> {
+        None
+    }
+
+    fn get_min_count() -> usize {
+        0
+    }
+
+    fn take(objects: &mut IntoIter<ExecutionTreeObject>, optional_count: &mut usize) -> Result<Self> {
+        let mut result = Vec::with_capacity(*optional_count);
+        for object in objects.take(*optional_count) {
+            let item: T = try!(From::from(object));
+            result.push(item);
+        }
+        *optional_count = 0;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lisp::Environment;
+
+    fn new_environment() -> Environment<()> {
+        let mut environment = Environment::new();
+        environment.register_fn("optional-first", |a: Option<i64>, b: i64| a.unwrap_or(0) * 100 + b);
+        environment.register_fn("rest-between", |first: i64, rest: Vec<i64>, last: i64| first * 100 + rest.len() as i64 * 10 + last);
+        environment
+    }
+
+    #[test]
+    fn optional_arguments_are_filled_from_the_argument_count() {
+        let mut environment = new_environment();
+        unsafe {
+            assert_eq!(environment.parse_and_execute(&(), "(optional-first 5i64)").unwrap(), "5i64");
+            assert_eq!(environment.parse_and_execute(&(), "(optional-first 1i64 5i64)").unwrap(), "105i64");
+            assert_eq!(environment.parse_and_execute(&(), "(rest-between 1i64 2i64)").unwrap(), "102i64");
+            assert_eq!(environment.parse_and_execute(&(), "(rest-between 1i64 7i64 8i64 2i64)").unwrap(), "122i64");
+        }
+    }
+
+    #[test]
+    fn nil_is_passed_as_none() {
+        let mut environment = new_environment();
+        let result = unsafe { environment.parse_and_execute(&(), "(optional-first () 5i64)") };
+        assert_eq!(result.unwrap(), "5i64");
+    }
+}
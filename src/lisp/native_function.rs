@@ -0,0 +1,72 @@
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use std::rc::Rc;
+
+pub struct NativeFunction {
+    func: Rc<Fn(Vec<ExecutionTreeObject>) -> Result<ExecutionTreeObject>>,
+    max_count: Option<usize>,
+    min_count: usize,
+}
+
+impl NativeFunction {
+    pub fn new(min_count: usize, max_count: Option<usize>, func: Rc<Fn(Vec<ExecutionTreeObject>) -> Result<ExecutionTreeObject>>) -> Self {
+        NativeFunction {
+            func: func,
+            max_count: max_count,
+            min_count: min_count,
+        }
+    }
+
+    pub unsafe fn call<TArg>(&self, arg: &TArg, environment: &mut Environment<TArg>, name: &str, args: Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+        let objects = try!(Self::evaluate_arguments(arg, environment, name, self.min_count, self.max_count, args));
+        (self.func)(objects)
+    }
+
+    pub unsafe fn evaluate_arguments<TArg>(arg: &TArg, environment: &mut Environment<TArg>, name: &str, min_count: usize, max_count: Option<usize>, args: Vec<&ExecutionTreeObject>) -> Result<Vec<ExecutionTreeObject>> {
+        try!(Self::check_argument_count(name, min_count, max_count, args.len()));
+
+        let mut result = Vec::with_capacity(args.len());
+        for object in args {
+            result.push(try!(environment.evaluate(arg, object)));
+        }
+        Ok(result)
+    }
+
+    pub fn get_max_count(&self) -> Option<usize> {
+        self.max_count
+    }
+
+    pub fn get_min_count(&self) -> usize {
+        self.min_count
+    }
+
+    fn check_argument_count(name: &str, min_count: usize, max_count: Option<usize>, actual: usize) -> Result<()> {
+        let is_valid =
+            match max_count {
+                Some(max_count) => actual >= min_count && actual <= max_count,
+                None => actual >= min_count,
+            };
+        if is_valid {
+            return Ok(());
+        }
+
+        let expected =
+            match max_count {
+                Some(max_count) if max_count == min_count => max_count.to_string(),
+                Some(max_count) => format!("{} to {}", min_count, max_count),
+                None => format!("at least {}", min_count),
+            };
+        Err(ErrorKind::InvalidArgumentCount(name.to_string(), expected, actual).into())
+    }
+}
+
+impl Clone for NativeFunction {
+    fn clone(&self) -> Self {
+        NativeFunction {
+            func: self.func.clone(),
+            max_count: self.max_count,
+            min_count: self.min_count,
+        }
+    }
+}
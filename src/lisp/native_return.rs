@@ -0,0 +1,96 @@
+use error::*;
+use lisp::Exception;
+use lisp::ExecutionTreeMap;
+use lisp::ExecutionTreeNode;
+use lisp::ExecutionTreeObject;
+use lisp::Foreign;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+
+pub trait NativeReturn {
+    fn into_object(self) -> Result<ExecutionTreeObject>;
+}
+
+macro_rules! impl_native_return {
+    ($($native_type:ty => $variant:ident),*) => {
+        $(
+            impl NativeReturn for $native_type {
+                fn into_object(self) -> Result<ExecutionTreeObject> {
+                    Ok(ExecutionTreeObject::$variant(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_native_return!(
+    BigInt => BigInt,
+    BigRational => Rational,
+    bool => Bool,
+    char => Char,
+    Exception => Exception,
+    ExecutionTreeMap => Map,
+    ExecutionTreeNode => Node,
+    f32 => F32,
+    f64 => F64,
+    Foreign => Foreign,
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    isize => ISize,
+    String => String,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    usize => USize);
+
+impl NativeReturn for () {
+    fn into_object(self) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::nil())
+    }
+}
+
+impl NativeReturn for ExecutionTreeObject {
+    fn into_object(self) -> Result<ExecutionTreeObject> {
+        Ok(self)
+    }
+}
+
+impl <'a> NativeReturn for &'a str {
+    fn into_object(self) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::String(self.to_string()))
+    }
+}
+
+impl <T> NativeReturn for Option<T>
+    where T: NativeReturn {
+    fn into_object(self) -> Result<ExecutionTreeObject> {
+        match self {
+            Some(some) => some.into_object(),
+            None => Ok(ExecutionTreeObject::nil()),
+        }
+    }
+}
+
+impl <T> NativeReturn for Result<T>
+    where T: NativeReturn {
+    fn into_object(self) -> Result<ExecutionTreeObject> {
+        match self {
+            Ok(ok) => ok.into_object(),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+impl <T> NativeReturn for Vec<T>
+    where T: NativeReturn {
+    fn into_object(self) -> Result<ExecutionTreeObject> {
+        let mut result = Vec::with_capacity(self.len());
+        for item in self {
+            result.push(try!(item.into_object()));
+        }
+        Ok(ExecutionTreeObject::Node(ExecutionTreeNode::new(result)))
+    }
+}
@@ -0,0 +1,8 @@
+use error::*;
+use lisp::Environment;
+use lisp::ExecutionTreeObject;
+use lisp::Optimizer;
+
+pub trait OptimizationPass<TArg> {
+    unsafe fn rewrite(&self, arg: &TArg, environment: &mut Environment<TArg>, optimizer: &Optimizer<TArg>, object: ExecutionTreeObject) -> Result<ExecutionTreeObject>;
+}
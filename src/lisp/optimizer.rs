@@ -0,0 +1,178 @@
+use error::*;
+use lisp::BeginFlatteningPass;
+use lisp::ConstantFoldingPass;
+use lisp::ConstantInliningPass;
+use lisp::DeadBranchEliminationPass;
+use lisp::Environment;
+use lisp::ExecutionTree;
+use lisp::ExecutionTreeNode;
+use lisp::ExecutionTreeObject;
+use lisp::OptimizationPass;
+use lisp::Span;
+use lisp::Symbol;
+
+pub struct Optimizer<TArg> {
+    begin_symbol: String,
+    if_symbol: String,
+    passes: Vec<Box<OptimizationPass<TArg>>>,
+}
+
+impl <TArg> Optimizer<TArg> {
+    pub fn new() -> Self {
+        Optimizer {
+            begin_symbol: "begin".to_string(),
+            if_symbol: "if".to_string(),
+            passes: Vec::new(),
+        }
+    }
+
+    pub fn with_default_passes() -> Self {
+        let mut result = Self::new();
+        result.push_pass(Box::new(ConstantInliningPass));
+        result.push_pass(Box::new(ConstantFoldingPass));
+        result.push_pass(Box::new(DeadBranchEliminationPass));
+        result.push_pass(Box::new(BeginFlatteningPass));
+        result
+    }
+
+    // Rewritten forms keep the span of the form they replace, so errors raised by optimized code still
+    // report where that code was written.
+    pub fn create_node(&self, objects: Vec<ExecutionTreeObject>, span: Option<Span>) -> ExecutionTreeNode {
+        match span {
+            Some(span) => ExecutionTreeNode::with_span(objects, span),
+            None => ExecutionTreeNode::new(objects),
+        }
+    }
+
+    pub fn get_begin_symbol(&self) -> &str {
+        &self.begin_symbol
+    }
+
+    pub fn get_form_head<'a>(&self, object: &'a ExecutionTreeObject) -> Option<&'a str> {
+        match object {
+            &ExecutionTreeObject::Node(ref node) => {
+                match node.get_objects().first() {
+                    Some(&ExecutionTreeObject::Symbol(ref symbol)) => Some(symbol),
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    }
+
+    pub fn get_if_symbol(&self) -> &str {
+        &self.if_symbol
+    }
+
+    pub fn is_builtin(&self, environment: &mut Environment<TArg>, symbol: &str) -> bool {
+        match environment.get_global_frame().try_get(symbol) {
+            Some(Symbol::BuiltInFunc(_)) | Some(Symbol::NativeFunc(_)) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_literal(&self, object: &ExecutionTreeObject) -> bool {
+        match object {
+            &ExecutionTreeObject::Node(_) | &ExecutionTreeObject::Symbol(_) => false,
+            _ => true,
+        }
+    }
+
+    pub unsafe fn optimize(&self, arg: &TArg, environment: &mut Environment<TArg>, execution_tree: ExecutionTree) -> Result<ExecutionTree> {
+        let root = execution_tree.into_root();
+        let span = root.get_span().cloned();
+        match try!(self.optimize_object(arg, environment, ExecutionTreeObject::Node(root))) {
+            ExecutionTreeObject::Node(root) => Ok(ExecutionTree::from_root(root)),
+            other => Ok(ExecutionTree::from_root(self.create_node(vec![other], span))),
+        }
+    }
+
+    pub unsafe fn optimize_object(&self, arg: &TArg, environment: &mut Environment<TArg>, object: ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        let mut result = object;
+        for pass in &self.passes {
+            result = try!(self.run_pass(arg, environment, &**pass, result));
+        }
+        Ok(result)
+    }
+
+    pub fn push_pass(&mut self, pass: Box<OptimizationPass<TArg>>) {
+        self.passes.push(pass);
+    }
+
+    pub fn set_begin_symbol(&mut self, begin_symbol: String) {
+        self.begin_symbol = begin_symbol;
+    }
+
+    pub fn set_if_symbol(&mut self, if_symbol: String) {
+        self.if_symbol = if_symbol;
+    }
+
+    fn get_evaluated_start(&self, environment: &mut Environment<TArg>, objects: &Vec<ExecutionTreeObject>) -> Option<usize> {
+        if objects.len() == 1 {
+            return Some(0);
+        }
+
+        match objects.first() {
+            Some(&ExecutionTreeObject::Symbol(ref symbol)) => {
+                let is_control = *symbol == self.begin_symbol || *symbol == self.if_symbol;
+                match environment.get_global_frame().try_get(symbol) {
+                    Some(Symbol::BuiltInFunc(_)) if is_control || environment.is_pure(symbol) => Some(1),
+                    Some(Symbol::NativeFunc(_)) if environment.is_pure(symbol) => Some(1),
+                    Some(Symbol::Object(_)) => Some(1),
+                    _ => None,
+                }
+            },
+            Some(_) => Some(0),
+            None => None,
+        }
+    }
+
+    unsafe fn run_pass(&self, arg: &TArg, environment: &mut Environment<TArg>, pass: &OptimizationPass<TArg>, object: ExecutionTreeObject) -> Result<ExecutionTreeObject> {
+        let object =
+            match object {
+                ExecutionTreeObject::Node(node) => {
+                    let span = node.get_span().cloned();
+                    let mut objects = node.into_objects();
+                    if let Some(start) = self.get_evaluated_start(environment, &objects) {
+                        let rest = objects.split_off(start);
+                        for object in rest {
+                            objects.push(try!(self.run_pass(arg, environment, pass, object)));
+                        }
+                    }
+                    ExecutionTreeObject::Node(self.create_node(objects, span))
+                },
+                other => other,
+            };
+        pass.rewrite(arg, environment, self, object)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use lisp::AbstractSyntaxTree;
+    use lisp::Environment;
+    use lisp::ExecutionTree;
+    use lisp::ExecutionTreeObject;
+    use lisp::Optimizer;
+
+    #[test]
+    fn optimized_forms_keep_their_spans() {
+        let mut environment = Environment::<()>::new();
+        builtin::control::register(environment.get_global_frame());
+        builtin::definition::register(environment.get_global_frame());
+        let ast = AbstractSyntaxTree::new("(begin\n  (if true\n    (begin 1 (define x)))\n  2)").unwrap();
+        let execution_tree = ExecutionTree::new(&ast).unwrap();
+        let optimizer = Optimizer::with_default_passes();
+        let execution_tree = unsafe { optimizer.optimize(&(), &mut environment, execution_tree) }.unwrap();
+
+        let root = execution_tree.get_root();
+        assert_eq!(ExecutionTreeObject::Node(root.clone()).to_string().unwrap(), "(begin 1i32 (define x) 2i32)");
+        assert_eq!(root.get_span().unwrap().to_string(), "1:1");
+
+        let error = unsafe { environment.execute(&(), execution_tree) }.unwrap_err();
+        let forms = error.get_lisp_backtrace().unwrap();
+        assert_eq!(forms[0], "define at 3:14");
+        assert_eq!(forms[1], "begin at 1:1");
+    }
+}
@@ -0,0 +1,7 @@
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    Checked,
+    Promote,
+    Saturating,
+    Wrapping,
+}
@@ -0,0 +1,16 @@
+use lisp::Breakpoint;
+
+#[derive(Clone, Debug)]
+pub enum PauseReason {
+    Breakpoint(Breakpoint),
+    Step,
+}
+
+impl PauseReason {
+    pub fn to_string(&self) -> String {
+        match self {
+            &PauseReason::Breakpoint(ref breakpoint) => format!("breakpoint at {}", breakpoint.to_string()),
+            &PauseReason::Step => "step".to_string(),
+        }
+    }
+}
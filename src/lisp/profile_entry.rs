@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+// Allocations are the lists, maps and bindings created while the entry is the innermost call, with
+// sizes approximated by their heap size.
+#[derive(Clone, Debug)]
+pub struct ProfileEntry {
+    allocation_count: usize,
+    allocation_size: usize,
+    call_count: usize,
+    exclusive_time: Duration,
+    inclusive_time: Duration,
+}
+
+impl ProfileEntry {
+    pub fn new() -> Self {
+        ProfileEntry {
+            allocation_count: 0,
+            allocation_size: 0,
+            call_count: 0,
+            exclusive_time: Duration::from_secs(0),
+            inclusive_time: Duration::from_secs(0),
+        }
+    }
+
+    pub fn add_allocation(&mut self, size: usize) {
+        self.allocation_count += 1;
+        self.allocation_size += size;
+    }
+
+    pub fn add_call(&mut self) {
+        self.call_count += 1;
+    }
+
+    pub fn add_exclusive_time(&mut self, duration: Duration) {
+        self.exclusive_time += duration;
+    }
+
+    pub fn add_inclusive_time(&mut self, duration: Duration) {
+        self.inclusive_time += duration;
+    }
+
+    pub fn get_allocation_count(&self) -> usize {
+        self.allocation_count
+    }
+
+    pub fn get_allocation_size(&self) -> usize {
+        self.allocation_size
+    }
+
+    pub fn get_call_count(&self) -> usize {
+        self.call_count
+    }
+
+    pub fn get_exclusive_time(&self) -> Duration {
+        self.exclusive_time
+    }
+
+    pub fn get_inclusive_time(&self) -> Duration {
+        self.inclusive_time
+    }
+}
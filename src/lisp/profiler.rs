@@ -0,0 +1,260 @@
+use json::JsonWriter;
+use lisp::ProfileEntry;
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::time::Duration;
+use std::time::Instant;
+
+const DEFAULT_MAX_TRACE_EVENTS: usize = 100_000;
+
+struct ProfilerCall {
+    category: &'static str,
+    child_time: Duration,
+    name: String,
+    start: Instant,
+}
+
+struct TraceEvent {
+    category: &'static str,
+    duration: Duration,
+    name: String,
+    start: Duration,
+}
+
+pub struct Profiler {
+    entries: BTreeMap<String, ProfileEntry>,
+    form_count: usize,
+    max_trace_events: usize,
+    sample_interval: Option<usize>,
+    samples: BTreeMap<String, usize>,
+    stack: Vec<ProfilerCall>,
+    stack_times: BTreeMap<String, Duration>,
+    start: Instant,
+    trace_events: VecDeque<TraceEvent>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Profiler {
+            entries: BTreeMap::new(),
+            form_count: 0,
+            max_trace_events: DEFAULT_MAX_TRACE_EVENTS,
+            sample_interval: None,
+            samples: BTreeMap::new(),
+            stack: Vec::new(),
+            stack_times: BTreeMap::new(),
+            start: Instant::now(),
+            trace_events: VecDeque::new(),
+        }
+    }
+
+    pub fn builtin_str() -> &'static str {
+        "builtin"
+    }
+
+    pub fn enter(&mut self, name: &str, category: &'static str) {
+        self.entries.entry(name.to_string()).or_insert_with(ProfileEntry::new).add_call();
+        let call =
+            ProfilerCall {
+                category: category,
+                child_time: Duration::from_secs(0),
+                name: name.to_string(),
+                start: Instant::now(),
+            };
+        self.stack.push(call);
+    }
+
+    pub fn exit(&mut self) {
+        let stack_key = self.get_stack_key();
+        let call =
+            match self.stack.pop() {
+                Some(call) => call,
+                None => {
+                    return;
+                },
+            };
+
+        let duration = call.start.elapsed();
+        let exclusive_time =
+            if duration > call.child_time {
+                duration - call.child_time
+            } else {
+                Duration::from_secs(0)
+            };
+        let is_recursive = self.stack.iter().any(|outer| outer.name == call.name);
+
+        {
+            let entry = self.entries.entry(call.name.clone()).or_insert_with(ProfileEntry::new);
+            entry.add_exclusive_time(exclusive_time);
+            if !is_recursive {
+                entry.add_inclusive_time(duration);
+            }
+        }
+
+        *self.stack_times.entry(stack_key).or_insert(Duration::from_secs(0)) += exclusive_time;
+
+        if let Some(caller) = self.stack.last_mut() {
+            caller.child_time += duration;
+        }
+
+        let trace_event =
+            TraceEvent {
+                category: call.category,
+                duration: duration,
+                start: call.start.duration_since(self.start),
+                name: call.name,
+            };
+        self.push_trace_event(trace_event);
+    }
+
+    pub fn get_entries(&self) -> &BTreeMap<String, ProfileEntry> {
+        &self.entries
+    }
+
+    pub fn get_entry(&self, name: &str) -> Option<&ProfileEntry> {
+        self.entries.get(name)
+    }
+
+    pub fn get_max_trace_events(&self) -> usize {
+        self.max_trace_events
+    }
+
+    pub fn get_sample_interval(&self) -> Option<usize> {
+        self.sample_interval
+    }
+
+    pub fn get_samples(&self) -> &BTreeMap<String, usize> {
+        &self.samples
+    }
+
+    pub fn macro_str() -> &'static str {
+        "macro"
+    }
+
+    // Records an allocation made while the innermost call is running.
+    pub fn record_allocation(&mut self, size: usize) {
+        if let Some(call) = self.stack.last() {
+            if let Some(entry) = self.entries.get_mut(&call.name) {
+                entry.add_allocation(size);
+            }
+        }
+    }
+
+    pub fn record_form(&mut self) {
+        if let Some(sample_interval) = self.sample_interval {
+            self.form_count += 1;
+            if sample_interval != 0 && self.form_count % sample_interval == 0 && !self.stack.is_empty() {
+                let stack_key = self.get_stack_key();
+                *self.samples.entry(stack_key).or_insert(0) += 1;
+            }
+        }
+    }
+
+    // Only the most recent trace events are kept, so long runs do not grow the profiler without bound.
+    pub fn set_max_trace_events(&mut self, max_trace_events: usize) {
+        self.max_trace_events = max_trace_events;
+        while self.trace_events.len() > max_trace_events {
+            self.trace_events.pop_front();
+        }
+    }
+
+    pub fn set_sample_interval(&mut self, sample_interval: Option<usize>) {
+        self.sample_interval = sample_interval;
+    }
+
+    pub fn to_chrome_trace(&self) -> String {
+        let mut result = String::new();
+        result.push_str("{\"traceEvents\":[");
+        for (index, trace_event) in self.trace_events.iter().enumerate() {
+            if index != 0 {
+                result.push(',');
+            }
+            let mut name = String::new();
+            JsonWriter::write_string(&trace_event.name, &mut name);
+            result.push_str(&format!(
+                "{{\"name\":{},\"cat\":\"{}\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":1}}",
+                name,
+                trace_event.category,
+                Self::to_microseconds(trace_event.start),
+                Self::to_microseconds(trace_event.duration)));
+        }
+        result.push_str("],\"displayTimeUnit\":\"ns\"}");
+        result
+    }
+
+    pub fn to_folded_stacks(&self) -> String {
+        let mut result = String::new();
+        for (stack_key, duration) in &self.stack_times {
+            result.push_str(&format!("{} {}\n", stack_key, Self::to_microseconds(*duration)));
+        }
+        result
+    }
+
+    pub fn to_sampled_folded_stacks(&self) -> String {
+        let mut result = String::new();
+        for (stack_key, count) in &self.samples {
+            result.push_str(&format!("{} {}\n", stack_key, count));
+        }
+        result
+    }
+
+    fn get_stack_key(&self) -> String {
+        let names: Vec<&str> = self.stack.iter().map(|call| call.name.as_str()).collect();
+        names.join(";")
+    }
+
+    fn push_trace_event(&mut self, trace_event: TraceEvent) {
+        if self.max_trace_events == 0 {
+            return;
+        }
+        if self.trace_events.len() == self.max_trace_events {
+            self.trace_events.pop_front();
+        }
+        self.trace_events.push_back(trace_event);
+    }
+
+    fn to_microseconds(duration: Duration) -> u64 {
+        duration.as_secs() * 1_000_000 + (duration.subsec_nanos() / 1_000) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lisp::Profiler;
+
+    #[test]
+    fn trace_events_are_capped() {
+        let mut profiler = Profiler::new();
+        profiler.set_max_trace_events(2);
+        for name in ["a", "b", "c"].iter() {
+            profiler.enter(name, Profiler::builtin_str());
+            profiler.exit();
+        }
+        let trace = profiler.to_chrome_trace();
+        assert!(!trace.contains("\"name\":\"a\""));
+        assert!(trace.contains("\"name\":\"b\""));
+        assert!(trace.contains("\"name\":\"c\""));
+        assert_eq!(profiler.get_entry("a").unwrap().get_call_count(), 1);
+    }
+
+    #[test]
+    fn trace_event_names_are_escaped() {
+        let mut profiler = Profiler::new();
+        profiler.enter("a\"b\u{1}", Profiler::builtin_str());
+        profiler.exit();
+        assert!(profiler.to_chrome_trace().contains("\"name\":\"a\\\"b\\u0001\""));
+    }
+
+    #[test]
+    fn allocations_are_recorded_for_the_innermost_call() {
+        let mut profiler = Profiler::new();
+        profiler.enter("outer", Profiler::builtin_str());
+        profiler.enter("inner", Profiler::builtin_str());
+        profiler.record_allocation(16);
+        profiler.exit();
+        profiler.exit();
+        let inner = profiler.get_entry("inner").unwrap();
+        assert_eq!((inner.get_allocation_count(), inner.get_allocation_size()), (1, 16));
+        assert_eq!(profiler.get_entry("outer").unwrap().get_allocation_count(), 0);
+    }
+}
@@ -0,0 +1,69 @@
+#[derive(Clone, Copy, Debug)]
+pub struct Span {
+    column: usize,
+    end: usize,
+    line: usize,
+    source_id: usize,
+    start: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Span {
+            column: column,
+            end: end,
+            line: line,
+            source_id: 0,
+            start: start,
+        }
+    }
+
+    pub fn from_offsets(start: usize, end: usize, line_starts: &[usize]) -> Self {
+        let line_index =
+            match line_starts.binary_search(&start) {
+                Ok(index) => index,
+                Err(index) => index - 1,
+            };
+        let column = start - line_starts[line_index] + 1;
+        Self::new(start, end, line_index + 1, column)
+    }
+
+    pub fn get_column(&self) -> usize {
+        self.column
+    }
+
+    pub fn get_end(&self) -> usize {
+        self.end
+    }
+
+    pub fn get_line(&self) -> usize {
+        self.line
+    }
+
+    // Identifies the parsed text the span points into, or 0 when the source is unknown.
+    pub fn get_source_id(&self) -> usize {
+        self.source_id
+    }
+
+    pub fn get_start(&self) -> usize {
+        self.start
+    }
+
+    pub fn line_starts(program_text: &str) -> Vec<usize> {
+        let mut result = vec![0];
+        for (index, character) in program_text.char_indices() {
+            if character == '\n' {
+                result.push(index + 1);
+            }
+        }
+        result
+    }
+
+    pub fn set_source_id(&mut self, source_id: usize) {
+        self.source_id = source_id;
+    }
+
+    pub fn to_string(&self) -> String {
+        format!("{}:{}", self.line, self.column)
+    }
+}
@@ -1,10 +1,26 @@
 use error::*;
 use lisp::Environment;
 use lisp::ExecutionTreeObject;
+use lisp::MacroDefinition;
+use lisp::NativeFunction;
+use std::mem;
 
 pub enum Symbol<TArg> {
     Object(ExecutionTreeObject),
     BuiltInFunc(unsafe fn(&TArg, &mut Environment<TArg>, Vec<&ExecutionTreeObject>) -> Result<ExecutionTreeObject>),
+    Macro(MacroDefinition),
+    NativeFunc(NativeFunction),
+}
+
+impl <TArg> Symbol<TArg> {
+    pub fn get_size(&self) -> usize {
+        match self {
+            &Symbol::Object(ref some) => some.get_size(),
+            &Symbol::BuiltInFunc(_) => mem::size_of::<Symbol<TArg>>(),
+            &Symbol::Macro(ref some) => some.get_size(),
+            &Symbol::NativeFunc(_) => mem::size_of::<Symbol<TArg>>(),
+        }
+    }
 }
 
 impl <TArg> Clone for Symbol<TArg> {
@@ -12,6 +28,8 @@ impl <TArg> Clone for Symbol<TArg> {
         match self {
             &Symbol::Object(ref some) => Symbol::Object(some.clone()),
             &Symbol::BuiltInFunc(some) => Symbol::BuiltInFunc(some),
+            &Symbol::Macro(ref some) => Symbol::Macro(some.clone()),
+            &Symbol::NativeFunc(ref some) => Symbol::NativeFunc(some.clone()),
         }
     }
 }
@@ -0,0 +1,384 @@
+use error::*;
+use lisp::Bytecode;
+use lisp::Environment;
+use lisp::ExecutionTreeNode;
+use lisp::ExecutionTreeObject;
+use lisp::Instruction;
+use lisp::Symbol;
+
+pub struct VirtualMachine<TArg> {
+    bytecode: Bytecode,
+    cache: Vec<Option<Symbol<TArg>>>,
+    cache_key: Option<(usize, usize)>,
+    stack: Vec<ExecutionTreeObject>,
+    traces: Vec<usize>,
+}
+
+impl <TArg> VirtualMachine<TArg> {
+    pub fn new(bytecode: Bytecode) -> Self {
+        let symbols_len = bytecode.get_symbols().len();
+        VirtualMachine {
+            bytecode: bytecode,
+            cache: (0..symbols_len).map(|_| None).collect(),
+            cache_key: None,
+            stack: Vec::new(),
+            traces: Vec::new(),
+        }
+    }
+
+    pub fn get_bytecode(&self) -> &Bytecode {
+        &self.bytecode
+    }
+
+    pub unsafe fn execute(&mut self, arg: &TArg, environment: &mut Environment<TArg>) -> Result<String> {
+        let result = try!(self.run(arg, environment));
+        result.to_string()
+    }
+
+    // Forms still open when an instruction fails are exited innermost first, as the tree walker
+    // unwinds them.
+    pub unsafe fn run(&mut self, arg: &TArg, environment: &mut Environment<TArg>) -> Result<ExecutionTreeObject> {
+        self.stack.clear();
+        self.traces.clear();
+        environment.get_debugger().clear_entered_line();
+
+        let mut result = self.run_instructions(arg, environment);
+        while let Some(trace) = self.traces.pop() {
+            result = environment.exit_traced_form(&self.bytecode.get_traced_objects()[trace], result);
+        }
+        result
+    }
+
+    fn push_backtrace_form(&self, slot: usize, result: Result<ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+        match result {
+            Err(error) => {
+                let symbol = self.bytecode.get_symbols()[slot].clone();
+                Err(error.push_lisp_backtrace_form(symbol, None))
+            },
+            ok => ok,
+        }
+    }
+
+    // The cache is only valid for the environment and global frame generation it was filled from.
+    fn resolve(&mut self, environment: &mut Environment<TArg>, slot: usize) -> Result<Symbol<TArg>> {
+        let id = environment.get_id();
+        let global_frame = environment.get_global_frame();
+        let cache_key = (id, global_frame.get_generation());
+        if self.cache_key != Some(cache_key) {
+            for symbol in &mut self.cache {
+                *symbol = None;
+            }
+            self.cache_key = Some(cache_key);
+        }
+
+        if let Some(ref symbol) = self.cache[slot] {
+            return Ok(symbol.clone());
+        }
+
+        let name = &self.bytecode.get_symbols()[slot];
+        match global_frame.try_get(name) {
+            Some(symbol) => {
+                self.cache[slot] = Some(symbol.clone());
+                Ok(symbol)
+            },
+            None => {
+                match environment.get_symbol(name) {
+                    Err(error) => Err(error.push_lisp_backtrace_form(name.clone(), None)),
+                    ok => ok,
+                }
+            },
+        }
+    }
+
+    unsafe fn run_instructions(&mut self, arg: &TArg, environment: &mut Environment<TArg>) -> Result<ExecutionTreeObject> {
+        let instructions_len = self.bytecode.get_instructions().len();
+        let mut index = 0;
+        while index < instructions_len {
+            match self.bytecode.get_instructions()[index] {
+                Instruction::CallSymbol(slot, forms, trace, skip) => {
+                    let symbol =
+                        match self.resolve(environment, slot) {
+                            Ok(symbol) => symbol,
+                            Err(error) => {
+                                let name = &self.bytecode.get_symbols()[slot];
+                                let args = self.bytecode.get_forms()[forms].iter().collect();
+                                let result =
+                                    match environment.call_method_symbol(arg, name, args) {
+                                        Some(result) => result,
+                                        None => {
+                                            return Err(error);
+                                        },
+                                    };
+                                let result = try!(self.push_backtrace_form(slot, result));
+                                self.stack.push(result);
+                                index += skip + 1;
+                                continue;
+                            },
+                        };
+                    let result =
+                        match symbol {
+                            Symbol::BuiltInFunc(func) => {
+                                let name = &self.bytecode.get_symbols()[slot];
+                                let args = self.bytecode.get_forms()[forms].iter().collect();
+                                let is_branch_form = environment.enter_branch_form(&self.bytecode.get_traced_objects()[trace]);
+                                let result = environment.call_builtin(arg, name, func, args);
+                                if is_branch_form {
+                                    environment.exit_branch_form();
+                                }
+                                result
+                            },
+                            Symbol::Macro(definition) => {
+                                let args = self.bytecode.get_forms()[forms].iter().collect();
+                                match environment.expand_macro(arg, &definition, args) {
+                                    Ok(expansion) => environment.evaluate(arg, &expansion),
+                                    Err(error) => Err(error),
+                                }
+                            },
+                            Symbol::NativeFunc(function) => {
+                                let name = &self.bytecode.get_symbols()[slot];
+                                let args = self.bytecode.get_forms()[forms].iter().collect();
+                                environment.call_native_function(arg, name, &function, args)
+                            },
+                            Symbol::Object(object) => {
+                                self.stack.push(object);
+                                index += 1;
+                                continue;
+                            },
+                        };
+                    let result = try!(self.push_backtrace_form(slot, result));
+                    self.stack.push(result);
+                    index += skip;
+                },
+                Instruction::Constant(constant) => {
+                    let object = self.bytecode.get_constants()[constant].clone();
+                    self.stack.push(object);
+                },
+                Instruction::ExitTrace(trace) => {
+                    self.traces.pop();
+                    let result =
+                        match self.stack.pop() {
+                            Some(result) => result,
+                            None => ExecutionTreeObject::nil(),
+                        };
+                    let result = try!(environment.exit_traced_form(&self.bytecode.get_traced_objects()[trace], Ok(result)));
+                    self.stack.push(result);
+                },
+                Instruction::LoadSymbol(slot) => {
+                    let symbol =
+                        match self.resolve(environment, slot) {
+                            Ok(symbol) => symbol,
+                            Err(error) => {
+                                let name = &self.bytecode.get_symbols()[slot];
+                                let result =
+                                    match environment.call_method_symbol(arg, name, Vec::new()) {
+                                        Some(result) => result,
+                                        None => {
+                                            return Err(error);
+                                        },
+                                    };
+                                let result = try!(self.push_backtrace_form(slot, result));
+                                self.stack.push(result);
+                                index += 1;
+                                continue;
+                            },
+                        };
+                    let result =
+                        match symbol {
+                            Symbol::BuiltInFunc(func) => {
+                                let name = &self.bytecode.get_symbols()[slot];
+                                let result = environment.call_builtin(arg, name, func, Vec::new());
+                                try!(self.push_backtrace_form(slot, result))
+                            },
+                            Symbol::Macro(definition) => {
+                                let result =
+                                    match environment.expand_macro(arg, &definition, Vec::new()) {
+                                        Ok(expansion) => environment.evaluate(arg, &expansion),
+                                        Err(error) => Err(error),
+                                    };
+                                try!(self.push_backtrace_form(slot, result))
+                            },
+                            Symbol::NativeFunc(function) => {
+                                let name = &self.bytecode.get_symbols()[slot];
+                                let result = environment.call_native_function(arg, name, &function, Vec::new());
+                                try!(self.push_backtrace_form(slot, result))
+                            },
+                            Symbol::Object(object) => object,
+                        };
+                    self.stack.push(result);
+                },
+                Instruction::MakeList(len) => {
+                    let start = self.stack.len() - len;
+                    let objects = self.stack.split_off(start);
+                    let node = ExecutionTreeNode::new(objects);
+                    environment.record_allocation(node.get_heap_size());
+                    let result = ExecutionTreeObject::Node(node);
+                    if environment.get_max_memory().is_some() {
+                        try!(environment.check_memory(result.get_size()));
+                    }
+                    self.stack.push(result);
+                },
+                Instruction::Trace(trace) => {
+                    try!(environment.trace_form(arg, &self.bytecode.get_traced_objects()[trace]));
+                    self.traces.push(trace);
+                },
+            }
+            index += 1;
+        }
+
+        match self.stack.pop() {
+            Some(result) => Ok(result),
+            None => Ok(ExecutionTreeObject::nil()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use builtin;
+    use error::*;
+    use lisp::AbstractSyntaxTree;
+    use lisp::Breakpoint;
+    use lisp::Bytecode;
+    use lisp::Coverage;
+    use lisp::DebuggerAction;
+    use lisp::DebuggerFrontEnd;
+    use lisp::Environment;
+    use lisp::ExecutionTree;
+    use lisp::ExecutionTreeObject;
+    use lisp::Foreign;
+    use lisp::LispObject;
+    use lisp::PauseReason;
+    use lisp::Profiler;
+    use lisp::Symbol;
+    use lisp::VirtualMachine;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct CountingFrontEnd {
+        pauses: Rc<Cell<usize>>,
+    }
+
+    impl DebuggerFrontEnd<()> for CountingFrontEnd {
+        unsafe fn on_pause(&mut self, _: &(), _: &mut Environment<()>, _: &ExecutionTreeObject, _: &PauseReason) -> Result<DebuggerAction> {
+            self.pauses.set(self.pauses.get() + 1);
+            Ok(DebuggerAction::Continue)
+        }
+    }
+
+    struct Doubler;
+
+    impl LispObject for Doubler {
+        fn call_method(&self, name: &str, args: Vec<ExecutionTreeObject>) -> Result<ExecutionTreeObject> {
+            match (name, args.first()) {
+                ("double", Some(&ExecutionTreeObject::I64(value))) => Ok(ExecutionTreeObject::I64(value * 2)),
+                ("answer", None) => Ok(ExecutionTreeObject::I64(42)),
+                _ => Err(ErrorKind::UndefinedMethod(self.get_type_name().to_string(), name.to_string()).into()),
+            }
+        }
+    }
+
+    fn new_environment() -> Environment<()> {
+        let mut environment = Environment::new();
+        builtin::arithmetic::register(environment.get_global_frame());
+        environment
+    }
+
+    fn parse(source: &str) -> ExecutionTree {
+        let ast = AbstractSyntaxTree::new(source).unwrap();
+        ExecutionTree::new(&ast).unwrap()
+    }
+
+    fn outcome(result: Result<ExecutionTreeObject>) -> String {
+        match result {
+            Ok(object) => object.to_string().unwrap(),
+            Err(error) => error.kind().enum_to_string().to_string(),
+        }
+    }
+
+    #[test]
+    fn runs_like_the_tree_walker() {
+        let sources = ["(1 2 3)", "(())", "((x y) (y x))", "(+ x (+ y 3))", "((+ 1 2) (x) 4)"];
+        for source in sources.iter() {
+            let mut environment = new_environment();
+            environment.get_global_frame().insert("x".to_string(), Symbol::Object(ExecutionTreeObject::I32(1)));
+            environment.get_global_frame().insert("y".to_string(), Symbol::Object(ExecutionTreeObject::I32(2)));
+            let expected = unsafe { environment.parse_and_execute(&(), source) }.unwrap();
+            let mut virtual_machine = VirtualMachine::new(Bytecode::compile(&parse(source)));
+            let actual = unsafe { virtual_machine.execute(&(), &mut environment) }.unwrap();
+            assert_eq!(actual, expected, "{}", source);
+        }
+    }
+
+    #[test]
+    fn counts_the_call_depth_like_the_tree_walker() {
+        let mut environment = new_environment();
+        builtin::macros::register(environment.get_global_frame());
+        environment.set_max_call_depth(Some(12));
+        unsafe { environment.parse_and_execute(&(), "(defmacro m (x) (m x))") }.unwrap();
+        let mut sources = vec!["(m 1)".to_string()];
+        for depth in 8..16 {
+            sources.push(format!("{}1{}", "(+ 1 ".repeat(depth), ")".repeat(depth)));
+            sources.push(format!("({}1{})", "(".repeat(depth), ")".repeat(depth)));
+        }
+        let mut exceeded_count = 0;
+        for source in &sources {
+            let execution_tree = parse(source);
+            let root = ExecutionTreeObject::Node(execution_tree.get_root().clone());
+            let expected = outcome(unsafe { environment.evaluate(&(), &root) });
+            let mut virtual_machine = VirtualMachine::new(Bytecode::compile(&execution_tree));
+            let actual = outcome(unsafe { virtual_machine.run(&(), &mut environment) });
+            assert_eq!(actual, expected, "{}", source);
+            assert_eq!(environment.get_call_depth(), 0);
+            assert_eq!(environment.get_frame_count(), 0);
+            if actual == "ErrorKind::StackDepthExceeded" {
+                exceeded_count += 1;
+            }
+        }
+        assert!(exceeded_count > 1 && exceeded_count < sources.len());
+    }
+
+    #[test]
+    fn symbol_cache_is_keyed_by_environment() {
+        let mut virtual_machine = VirtualMachine::new(Bytecode::compile(&parse("(x)")));
+        let mut first_environment = Environment::<()>::new();
+        first_environment.get_global_frame().insert("x".to_string(), Symbol::Object(ExecutionTreeObject::I32(1)));
+        let mut second_environment = Environment::<()>::new();
+        second_environment.get_global_frame().insert("x".to_string(), Symbol::Object(ExecutionTreeObject::I32(2)));
+        unsafe {
+            assert_eq!(virtual_machine.execute(&(), &mut first_environment).unwrap(), "1i32");
+            assert_eq!(virtual_machine.execute(&(), &mut second_environment).unwrap(), "2i32");
+        }
+    }
+
+    #[test]
+    fn records_coverage_and_pauses_the_debugger() {
+        let source = "(+ 1\n   (+ 2 3))";
+        let execution_tree = parse(source);
+        let mut coverage = Coverage::new("test".to_string(), source.to_string());
+        coverage.register_tree(&execution_tree);
+        let pauses = Rc::new(Cell::new(0));
+        let mut environment = new_environment();
+        environment.set_coverage(Some(coverage));
+        environment.set_debugger_front_end(Box::new(CountingFrontEnd { pauses: pauses.clone() }));
+        environment.get_debugger().add_breakpoint(Breakpoint::Line(None, 1));
+
+        let mut virtual_machine = VirtualMachine::new(Bytecode::compile(&execution_tree));
+        assert_eq!(unsafe { virtual_machine.execute(&(), &mut environment) }.unwrap(), "6i32");
+        assert_eq!(pauses.get(), 1);
+        assert_eq!(environment.get_coverage().unwrap().get_line_counts().get(&1), Some(&1));
+    }
+
+    #[test]
+    fn calls_methods_with_colon_syntax() {
+        let mut environment = new_environment();
+        let doubler = ExecutionTreeObject::Foreign(Foreign::from_object(Doubler));
+        environment.get_global_frame().insert("doubler".to_string(), Symbol::Object(doubler));
+        environment.set_profiler(Some(Profiler::new()));
+        for &(source, expected) in [("(doubler:double 4i64)", "8i64"), ("(doubler:answer)", "42i64"), ("(+ 1i64 (doubler:double 2i64))", "5i64")].iter() {
+            let mut virtual_machine = VirtualMachine::new(Bytecode::compile(&parse(source)));
+            assert_eq!(unsafe { virtual_machine.execute(&(), &mut environment) }.unwrap(), expected);
+            assert_eq!(unsafe { environment.parse_and_execute(&(), source) }.unwrap(), expected);
+        }
+        assert_eq!(environment.get_profiler().unwrap().get_entry("double").unwrap().get_call_count(), 4);
+    }
+}
@@ -0,0 +1,90 @@
+mod schema;
+
+mod schema_violation;
+
+pub use schema::schema::Schema;
+
+pub use schema::schema_violation::SchemaViolation;
+
+use data;
+use data::DataOptions;
+use error::*;
+
+pub fn parse(text: &str) -> Result<Schema> {
+    let object = try!(data::read(text, &DataOptions::new()));
+    Schema::new(&object)
+}
+
+pub fn validate_text(schema: &Schema, text: &str, options: &DataOptions) -> Result<Vec<SchemaViolation>> {
+    let (object, spans) = try!(data::read_with_spans(text, options));
+    Ok(schema.validate_with_spans(&object, &spans))
+}
+
+#[cfg(test)]
+mod tests {
+    use data;
+    use data::DataOptions;
+    use error::*;
+    use schema;
+    use schema::Schema;
+
+    fn validate(schema_text: &str, text: &str) -> Vec<String> {
+        let schema = schema::parse(schema_text).unwrap();
+        let violations = schema::validate_text(&schema, text, &DataOptions::new()).unwrap();
+        violations.iter().map(|violation| violation.to_string()).collect()
+    }
+
+    fn get_schema_error(result: Result<Schema>) -> (String, String) {
+        match result {
+            Err(Error(ErrorKind::InvalidSchema(path, message), _)) => (path, message),
+            Err(error) => panic!("{}", error),
+            Ok(_) => panic!("the schema was accepted"),
+        }
+    }
+
+    #[test]
+    fn valid_values_have_no_violations() {
+        let schema_text = "(record :name string :ports (list-of (range u16 :min 1 :max 1024)) :mode (one-of fast slow) :tag (optional symbol))";
+        assert!(validate(schema_text, "(:name \"a\" :ports (80u16 443u16) :mode fast)").is_empty());
+        assert!(validate("(tuple i32 (any-of string bool))", "(1 true)").is_empty());
+        assert!(validate("(open-record :a integer)", "(:a 5n :b 1)").is_empty());
+    }
+
+    #[test]
+    fn violations_report_paths_and_atom_spans() {
+        let schema_text = "(record :name string :ports (list-of (range u16 :min 1 :max 1024)))";
+        let violations = validate(schema_text, "(:name 5\n :ports (80u16\n         2000u16))");
+        assert_eq!(violations, vec![
+            "$.name (1:8): expected a value of type, 'string', but found, '5i32'".to_string(),
+            "$.ports[1] (3:10): the value, '2000u16', is greater than the maximum, '1024'".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn record_fields_are_checked() {
+        let violations = validate("(record :a i32 :b (optional i32))", "(:a 1 :c 2 :a 3)");
+        assert_eq!(violations, vec![
+            "$.c (1:7): the field is not allowed by the schema".to_string(),
+            "$.a (1:12): the field appears more than once".to_string(),
+        ]);
+        assert_eq!(validate("(record :a i32)", "()"), vec!["$ (1:1): the required field, ':a', is missing".to_string()]);
+    }
+
+    #[test]
+    fn values_without_recorded_spans_report_the_enclosing_list() {
+        let schema = schema::parse("(list-of i32)").unwrap();
+        let object = data::read("(1 \"x\")", &DataOptions::new()).unwrap();
+        let violations = schema.validate(&object);
+        assert_eq!(violations[0].to_string(), "$[1] (1:1): expected a value of type, 'i32', but found, '\"x\"'");
+    }
+
+    #[test]
+    fn invalid_schemas_are_rejected() {
+        assert_eq!(get_schema_error(schema::parse("(list-of nope)")).0, "$[1]");
+        assert_eq!(get_schema_error(schema::parse("(record :a i32 :a i32)")).0, "$.a");
+        assert_eq!(get_schema_error(schema::parse("(range i32 :min 1 :step 2)")).0, "$[5]");
+        let (path, message) = get_schema_error(schema::parse("(range i32 :min 10 :max 1)"));
+        assert_eq!(path, "$");
+        assert_eq!(message, "the minimum, '10', is greater than the maximum, '1'");
+    }
+}
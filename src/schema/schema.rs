@@ -0,0 +1,447 @@
+use data::DataSpans;
+use error::*;
+use lisp::ExecutionTreeObject;
+use lisp::Span;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use schema::SchemaViolation;
+
+const TYPE_NAMES: [&'static str; 25] = [
+    "any", "bigint", "bool", "bytes", "char", "f32", "f64", "float", "i8", "i16", "i32", "i64", "integer",
+    "isize", "keyword", "list", "number", "rational", "string", "symbol", "u8", "u16", "u32", "u64", "usize",
+];
+
+struct SchemaField {
+    is_optional: bool,
+    name: String,
+    node: SchemaNode,
+}
+
+enum SchemaNode {
+    AnyOf(Vec<SchemaNode>),
+    ListOf(Box<SchemaNode>),
+    OneOf(Vec<ExecutionTreeObject>),
+    Range(Box<SchemaNode>, Option<BigRational>, Option<BigRational>),
+    Record(Vec<SchemaField>, bool),
+    Tuple(Vec<SchemaNode>),
+    Type(String),
+}
+
+// A schema is written as data. A type name such as 'string', 'integer' or 'u16' matches values of
+// that type, and the forms below describe larger shapes:
+//     (any-of SCHEMA ...)                      a value matching at least one of the schemas
+//     (list-of SCHEMA)                         a list whose items all match the schema
+//     (one-of VALUE ...)                       one of the given values, such as enum symbols
+//     (range SCHEMA :min VALUE :max VALUE)     a number matching the schema within inclusive bounds
+//     (record :field SCHEMA ...)               a keyword list or map with exactly the given fields
+//     (open-record :field SCHEMA ...)          like record, but other fields are allowed
+//     (tuple SCHEMA ...)                       a list with one item per schema
+// A record field is required unless its schema is wrapped as '(optional SCHEMA)'.
+pub struct Schema {
+    root: SchemaNode,
+}
+
+impl Schema {
+    pub fn new(object: &ExecutionTreeObject) -> Result<Self> {
+        let result =
+            Schema {
+                root: try!(Self::compile(object, "$")),
+            };
+        Ok(result)
+    }
+
+    pub fn validate(&self, object: &ExecutionTreeObject) -> Vec<SchemaViolation> {
+        self.validate_with_spans(object, &DataSpans::new())
+    }
+
+    // The spans recorded by the data reader let violations on atoms point at the atom itself.
+    pub fn validate_with_spans(&self, object: &ExecutionTreeObject, spans: &DataSpans) -> Vec<SchemaViolation> {
+        let mut result = Vec::new();
+        let span = Self::get_span(object, Some(&[]), spans, None);
+        Self::check(&self.root, object, "$", Some(&[]), spans, span, &mut result);
+        result
+    }
+
+    // The indices lead to the object from the validated value, and are None inside a map, whose entries
+    // have no position.
+    fn check(node: &SchemaNode, object: &ExecutionTreeObject, path: &str, indices: Option<&[usize]>, spans: &DataSpans, span: Option<Span>, violations: &mut Vec<SchemaViolation>) {
+        match node {
+            &SchemaNode::AnyOf(ref alternatives) => {
+                for alternative in alternatives {
+                    let mut alternative_violations = Vec::new();
+                    Self::check(alternative, object, path, indices, spans, span, &mut alternative_violations);
+                    if alternative_violations.is_empty() {
+                        return;
+                    }
+                }
+                let message = format!("the value, '{}', does not match any of the alternatives", Self::describe(object));
+                violations.push(SchemaViolation::new(path.to_string(), span, message));
+            },
+            &SchemaNode::ListOf(ref item_node) => {
+                match object {
+                    &ExecutionTreeObject::Node(ref list) => {
+                        for (index, item) in list.get_objects().iter().enumerate() {
+                            let item_path = format!("{}[{}]", path, index);
+                            let item_indices = Self::get_item_indices(indices, index);
+                            let item_span = Self::get_span(item, item_indices.as_ref().map(Vec::as_slice), spans, span);
+                            Self::check(item_node, item, &item_path, item_indices.as_ref().map(Vec::as_slice), spans, item_span, violations);
+                        }
+                    },
+                    _ => {
+                        let message = format!("expected a list, but found, '{}'", Self::describe(object));
+                        violations.push(SchemaViolation::new(path.to_string(), span, message));
+                    },
+                }
+            },
+            &SchemaNode::OneOf(ref values) => {
+                if !values.contains(object) {
+                    let names: Vec<String> = values.iter().map(Self::describe).collect();
+                    let message = format!("the value, '{}', is not one of, '{}'", Self::describe(object), names.join(" "));
+                    violations.push(SchemaViolation::new(path.to_string(), span, message));
+                }
+            },
+            &SchemaNode::Range(ref value_node, ref min, ref max) => {
+                let violation_count = violations.len();
+                Self::check(value_node, object, path, indices, spans, span, violations);
+                if violations.len() != violation_count {
+                    return;
+                }
+
+                let message =
+                    match Self::to_rational(object) {
+                        Some(ref value) if min.as_ref().map_or(false, |min| value < min) => {
+                            format!("the value, '{}', is less than the minimum, '{}'", Self::describe(object), Self::describe_rational(min.as_ref().unwrap()))
+                        },
+                        Some(ref value) if max.as_ref().map_or(false, |max| value > max) => {
+                            format!("the value, '{}', is greater than the maximum, '{}'", Self::describe(object), Self::describe_rational(max.as_ref().unwrap()))
+                        },
+                        Some(_) => {
+                            return;
+                        },
+                        None => format!("the value, '{}', cannot be compared with a range", Self::describe(object)),
+                    };
+                violations.push(SchemaViolation::new(path.to_string(), span, message));
+            },
+            &SchemaNode::Record(ref fields, is_open) => {
+                Self::check_record(fields, is_open, object, path, indices, spans, span, violations);
+            },
+            &SchemaNode::Tuple(ref item_nodes) => {
+                match object {
+                    &ExecutionTreeObject::Node(ref list) if list.get_objects().len() == item_nodes.len() => {
+                        for (index, (item_node, item)) in item_nodes.iter().zip(list.get_objects()).enumerate() {
+                            let item_path = format!("{}[{}]", path, index);
+                            let item_indices = Self::get_item_indices(indices, index);
+                            let item_span = Self::get_span(item, item_indices.as_ref().map(Vec::as_slice), spans, span);
+                            Self::check(item_node, item, &item_path, item_indices.as_ref().map(Vec::as_slice), spans, item_span, violations);
+                        }
+                    },
+                    _ => {
+                        let message = format!("expected a list of length, '{}', but found, '{}'", item_nodes.len(), Self::describe(object));
+                        violations.push(SchemaViolation::new(path.to_string(), span, message));
+                    },
+                }
+            },
+            &SchemaNode::Type(ref type_name) => {
+                if !Self::matches_type(type_name, object) {
+                    let message = format!("expected a value of type, '{}', but found, '{}'", type_name, Self::describe(object));
+                    violations.push(SchemaViolation::new(path.to_string(), span, message));
+                }
+            },
+        }
+    }
+
+    fn check_record(fields: &[SchemaField], is_open: bool, object: &ExecutionTreeObject, path: &str, indices: Option<&[usize]>, spans: &DataSpans, span: Option<Span>, violations: &mut Vec<SchemaViolation>) {
+        // Each entry carries the index of its key in a keyword list, or None for a map.
+        let entries: Vec<(Option<usize>, &ExecutionTreeObject, &ExecutionTreeObject)> =
+            match object {
+                &ExecutionTreeObject::Map(ref map) => {
+                    map.sorted_entries().into_iter().map(|entry| (None, &entry.0, &entry.1)).collect()
+                },
+                &ExecutionTreeObject::Node(ref list) if list.get_objects().is_empty() || list.is_keyword_list() => {
+                    list.get_objects().chunks(2).enumerate().map(|(index, pair)| (Some(index * 2), &pair[0], &pair[1])).collect()
+                },
+                _ => {
+                    let message = format!("expected a record of keyword fields, but found, '{}'", Self::describe(object));
+                    violations.push(SchemaViolation::new(path.to_string(), span, message));
+                    return;
+                },
+            };
+
+        let mut seen_names: Vec<&str> = Vec::with_capacity(entries.len());
+        for (key_index, key, value) in entries {
+            let key_indices = key_index.and_then(|key_index| Self::get_item_indices(indices, key_index));
+            let key_span = Self::get_span(key, key_indices.as_ref().map(Vec::as_slice), spans, span);
+            let name =
+                match key {
+                    &ExecutionTreeObject::String(ref name) => name.as_str(),
+                    &ExecutionTreeObject::Symbol(ref name) if name.starts_with(':') => &name[1..],
+                    _ => {
+                        let message = format!("the key, '{}', is not a keyword", Self::describe(key));
+                        violations.push(SchemaViolation::new(path.to_string(), key_span, message));
+                        continue;
+                    },
+                };
+            let field_path = format!("{}.{}", path, name);
+            if seen_names.contains(&name) {
+                violations.push(SchemaViolation::new(field_path, key_span, "the field appears more than once".to_string()));
+                continue;
+            }
+            seen_names.push(name);
+
+            match fields.iter().find(|field| field.name == name) {
+                Some(field) => {
+                    let value_indices = key_index.and_then(|key_index| Self::get_item_indices(indices, key_index + 1));
+                    let value_span = Self::get_span(value, value_indices.as_ref().map(Vec::as_slice), spans, span);
+                    Self::check(&field.node, value, &field_path, value_indices.as_ref().map(Vec::as_slice), spans, value_span, violations);
+                },
+                None if !is_open => {
+                    violations.push(SchemaViolation::new(field_path, key_span, "the field is not allowed by the schema".to_string()));
+                },
+                None => { },
+            }
+        }
+
+        for field in fields {
+            if !field.is_optional && !seen_names.contains(&field.name.as_str()) {
+                let message = format!("the required field, ':{}', is missing", field.name);
+                violations.push(SchemaViolation::new(path.to_string(), span, message));
+            }
+        }
+    }
+
+    fn compile(object: &ExecutionTreeObject, path: &str) -> Result<SchemaNode> {
+        let list =
+            match object {
+                &ExecutionTreeObject::Symbol(ref type_name) => {
+                    if TYPE_NAMES.contains(&type_name.as_str()) {
+                        return Ok(SchemaNode::Type(type_name.clone()));
+                    } else {
+                        return Err(Self::invalid(path, format!("the type, '{}', is unknown", type_name)));
+                    }
+                },
+                &ExecutionTreeObject::Node(ref list) => list.get_objects(),
+                _ => {
+                    return Err(Self::invalid(path, format!("expected a type or a form, but found, '{}'", Self::describe(object))));
+                },
+            };
+        let head =
+            match list.first() {
+                Some(&ExecutionTreeObject::Symbol(ref head)) => head.as_str(),
+                _ => {
+                    return Err(Self::invalid(path, format!("expected a type or a form, but found, '{}'", Self::describe(object))));
+                },
+            };
+        let args = &list[1..];
+
+        match head {
+            "any-of" | "tuple" => {
+                let mut nodes = Vec::with_capacity(args.len());
+                for (index, arg) in args.iter().enumerate() {
+                    nodes.push(try!(Self::compile(arg, &format!("{}[{}]", path, index + 1))));
+                }
+                if head == "tuple" {
+                    Ok(SchemaNode::Tuple(nodes))
+                } else if nodes.is_empty() {
+                    Err(Self::invalid(path, "the form, 'any-of', needs at least one alternative".to_string()))
+                } else {
+                    Ok(SchemaNode::AnyOf(nodes))
+                }
+            },
+            "list-of" => {
+                if args.len() != 1 {
+                    return Err(Self::invalid(path, "the form, 'list-of', takes exactly one schema".to_string()));
+                }
+                let item_node = try!(Self::compile(&args[0], &format!("{}[1]", path)));
+                Ok(SchemaNode::ListOf(Box::new(item_node)))
+            },
+            "one-of" => {
+                if args.is_empty() {
+                    return Err(Self::invalid(path, "the form, 'one-of', needs at least one value".to_string()));
+                }
+                Ok(SchemaNode::OneOf(args.to_vec()))
+            },
+            "optional" => {
+                Err(Self::invalid(path, "the form, 'optional', may only wrap the schema of a record field".to_string()))
+            },
+            "range" => {
+                if args.len() % 2 != 1 {
+                    return Err(Self::invalid(path, "the form, 'range', takes a schema followed by ':min' and ':max' bounds".to_string()));
+                }
+                let value_node = try!(Self::compile(&args[0], &format!("{}[1]", path)));
+                let mut min = None;
+                let mut max = None;
+                for (index, pair) in args[1..].chunks(2).enumerate() {
+                    let bound_path = format!("{}[{}]", path, index * 2 + 3);
+                    let bound =
+                        match Self::to_rational(&pair[1]) {
+                            Some(bound) => bound,
+                            None => {
+                                return Err(Self::invalid(&bound_path, format!("the bound, '{}', is not a number", Self::describe(&pair[1]))));
+                            },
+                        };
+                    match pair[0] {
+                        ExecutionTreeObject::Symbol(ref keyword) if keyword == ":min" => min = Some(bound),
+                        ExecutionTreeObject::Symbol(ref keyword) if keyword == ":max" => max = Some(bound),
+                        ref keyword => {
+                            return Err(Self::invalid(&bound_path, format!("the bound, '{}', must be ':min' or ':max'", Self::describe(keyword))));
+                        },
+                    }
+                }
+                if let (&Some(ref min), &Some(ref max)) = (&min, &max) {
+                    if min > max {
+                        let message = format!("the minimum, '{}', is greater than the maximum, '{}'", Self::describe_rational(min), Self::describe_rational(max));
+                        return Err(Self::invalid(path, message));
+                    }
+                }
+                Ok(SchemaNode::Range(Box::new(value_node), min, max))
+            },
+            "open-record" | "record" => {
+                if args.len() % 2 != 0 {
+                    return Err(Self::invalid(path, format!("the form, '{}', takes keyword and schema pairs", head)));
+                }
+                let mut fields: Vec<SchemaField> = Vec::with_capacity(args.len() / 2);
+                for pair in args.chunks(2) {
+                    let name =
+                        match pair[0] {
+                            ExecutionTreeObject::Symbol(ref keyword) if keyword.starts_with(':') && keyword.len() > 1 => keyword[1..].to_string(),
+                            ref key => {
+                                return Err(Self::invalid(path, format!("the field name, '{}', is not a keyword", Self::describe(key))));
+                            },
+                        };
+                    let field_path = format!("{}.{}", path, name);
+                    if fields.iter().any(|field| field.name == name) {
+                        return Err(Self::invalid(&field_path, "the field is declared more than once".to_string()));
+                    }
+
+                    let (is_optional, field_schema) = try!(Self::get_field_schema(&pair[1], &field_path));
+                    let field =
+                        SchemaField {
+                            is_optional: is_optional,
+                            name: name,
+                            node: try!(Self::compile(field_schema, &field_path)),
+                        };
+                    fields.push(field);
+                }
+                Ok(SchemaNode::Record(fields, head == "open-record"))
+            },
+            _ => Err(Self::invalid(path, format!("the form, '{}', is unknown", head))),
+        }
+    }
+
+    fn describe(object: &ExecutionTreeObject) -> String {
+        match object.to_string() {
+            Ok(string) => string,
+            Err(_) => object.enum_to_string().to_string(),
+        }
+    }
+
+    fn describe_rational(value: &BigRational) -> String {
+        if value.is_integer() {
+            value.numer().to_string()
+        } else {
+            format!("{}/{}", value.numer(), value.denom())
+        }
+    }
+
+    fn get_field_schema<'a>(object: &'a ExecutionTreeObject, path: &str) -> Result<(bool, &'a ExecutionTreeObject)> {
+        if let &ExecutionTreeObject::Node(ref list) = object {
+            let objects = list.get_objects();
+            if let Some(&ExecutionTreeObject::Symbol(ref head)) = objects.first() {
+                if head == "optional" {
+                    if objects.len() != 2 {
+                        return Err(Self::invalid(path, "the form, 'optional', takes exactly one schema".to_string()));
+                    }
+                    return Ok((true, &objects[1]));
+                }
+            }
+        }
+        Ok((false, object))
+    }
+
+    fn get_item_indices(indices: Option<&[usize]>, index: usize) -> Option<Vec<usize>> {
+        indices.map(|indices| {
+            let mut result = indices.to_vec();
+            result.push(index);
+            result
+        })
+    }
+
+    // Lists carry their own spans and atoms are looked up among the recorded spans. A value with neither
+    // reports the span of the list containing it.
+    fn get_span(object: &ExecutionTreeObject, indices: Option<&[usize]>, spans: &DataSpans, parent_span: Option<Span>) -> Option<Span> {
+        match object {
+            &ExecutionTreeObject::Node(ref list) => list.get_span().cloned().or(parent_span),
+            _ => indices.and_then(|indices| spans.get_span(indices)).cloned().or(parent_span),
+        }
+    }
+
+    fn invalid(path: &str, message: String) -> Error {
+        ErrorKind::InvalidSchema(path.to_string(), message).into()
+    }
+
+    fn matches_type(type_name: &str, object: &ExecutionTreeObject) -> bool {
+        match type_name {
+            "any" => true,
+            "bigint" => matches!(object, &ExecutionTreeObject::BigInt(_)),
+            "bool" => matches!(object, &ExecutionTreeObject::Bool(_)),
+            "bytes" => matches!(object, &ExecutionTreeObject::Bytes(_)),
+            "char" => matches!(object, &ExecutionTreeObject::Char(_)),
+            "f32" => matches!(object, &ExecutionTreeObject::F32(_)),
+            "f64" => matches!(object, &ExecutionTreeObject::F64(_)),
+            "float" => matches!(object, &ExecutionTreeObject::F32(_) | &ExecutionTreeObject::F64(_)),
+            "i8" => matches!(object, &ExecutionTreeObject::I8(_)),
+            "i16" => matches!(object, &ExecutionTreeObject::I16(_)),
+            "i32" => matches!(object, &ExecutionTreeObject::I32(_)),
+            "i64" => matches!(object, &ExecutionTreeObject::I64(_)),
+            "integer" => {
+                match object {
+                    &ExecutionTreeObject::F32(_) | &ExecutionTreeObject::F64(_) | &ExecutionTreeObject::Rational(_) => false,
+                    object => Self::to_rational(object).is_some(),
+                }
+            },
+            "isize" => matches!(object, &ExecutionTreeObject::ISize(_)),
+            "keyword" => matches!(object, &ExecutionTreeObject::Symbol(ref symbol) if symbol.starts_with(':') && symbol.len() > 1),
+            "list" => matches!(object, &ExecutionTreeObject::Node(_)),
+            "number" => matches!(object, &ExecutionTreeObject::F32(_) | &ExecutionTreeObject::F64(_)) || Self::to_rational(object).is_some(),
+            "rational" => matches!(object, &ExecutionTreeObject::Rational(_)),
+            "string" => matches!(object, &ExecutionTreeObject::String(_)),
+            "symbol" => matches!(object, &ExecutionTreeObject::Symbol(ref symbol) if !symbol.starts_with(':') || symbol.len() == 1),
+            "u8" => matches!(object, &ExecutionTreeObject::U8(_)),
+            "u16" => matches!(object, &ExecutionTreeObject::U16(_)),
+            "u32" => matches!(object, &ExecutionTreeObject::U32(_)),
+            "u64" => matches!(object, &ExecutionTreeObject::U64(_)),
+            "usize" => matches!(object, &ExecutionTreeObject::USize(_)),
+            _ => false,
+        }
+    }
+
+    fn to_rational(object: &ExecutionTreeObject) -> Option<BigRational> {
+        let integer: BigInt =
+            match object {
+                &ExecutionTreeObject::BigInt(ref some) => some.clone(),
+                &ExecutionTreeObject::F32(ref some) => {
+                    return BigRational::from_float(*some);
+                },
+                &ExecutionTreeObject::F64(ref some) => {
+                    return BigRational::from_float(*some);
+                },
+                &ExecutionTreeObject::I8(ref some) => BigInt::from(*some),
+                &ExecutionTreeObject::I16(ref some) => BigInt::from(*some),
+                &ExecutionTreeObject::I32(ref some) => BigInt::from(*some),
+                &ExecutionTreeObject::I64(ref some) => BigInt::from(*some),
+                &ExecutionTreeObject::ISize(ref some) => BigInt::from(*some),
+                &ExecutionTreeObject::Rational(ref some) => {
+                    return Some(some.clone());
+                },
+                &ExecutionTreeObject::U8(ref some) => BigInt::from(*some),
+                &ExecutionTreeObject::U16(ref some) => BigInt::from(*some),
+                &ExecutionTreeObject::U32(ref some) => BigInt::from(*some),
+                &ExecutionTreeObject::U64(ref some) => BigInt::from(*some),
+                &ExecutionTreeObject::USize(ref some) => BigInt::from(*some),
+                _ => {
+                    return None;
+                },
+            };
+        Some(BigRational::from_integer(integer))
+    }
+}
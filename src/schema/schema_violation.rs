@@ -0,0 +1,37 @@
+use lisp::Span;
+
+#[derive(Clone, Debug)]
+pub struct SchemaViolation {
+    message: String,
+    path: String,
+    span: Option<Span>,
+}
+
+impl SchemaViolation {
+    pub fn new(path: String, span: Option<Span>, message: String) -> Self {
+        SchemaViolation {
+            message: message,
+            path: path,
+            span: span,
+        }
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn get_span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    pub fn to_string(&self) -> String {
+        match self.span {
+            Some(ref span) => format!("{} ({}): {}", self.path, span.to_string(), self.message),
+            None => format!("{}: {}", self.path, self.message),
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use error::*;
+use lisp::ExecutionTreeMap;
+use lisp::ExecutionTreeObject;
+use num_traits::ToPrimitive;
+use serde::de;
+use serde::de::DeserializeSeed;
+use serde::de::Deserializer;
+use serde::de::EnumAccess;
+use serde::de::MapAccess;
+use serde::de::SeqAccess;
+use serde::de::Unexpected;
+use serde::de::VariantAccess;
+use serde::de::Visitor;
+use serialization;
+use std::slice::Iter;
+
+pub struct ObjectDeserializer<'de> {
+    object: &'de ExecutionTreeObject,
+}
+
+impl <'de> ObjectDeserializer<'de> {
+    pub fn new(object: &'de ExecutionTreeObject) -> Self {
+        ObjectDeserializer {
+            object: object,
+        }
+    }
+
+    fn unexpected(&self) -> Unexpected<'de> {
+        match self.object {
+            &ExecutionTreeObject::Bool(value) => Unexpected::Bool(value),
+            &ExecutionTreeObject::Bytes(ref value) => Unexpected::Bytes(value),
+            &ExecutionTreeObject::Char(value) => Unexpected::Char(value),
+            &ExecutionTreeObject::F32(value) => Unexpected::Float(value as f64),
+            &ExecutionTreeObject::F64(value) => Unexpected::Float(value),
+            &ExecutionTreeObject::I8(value) => Unexpected::Signed(value as i64),
+            &ExecutionTreeObject::I16(value) => Unexpected::Signed(value as i64),
+            &ExecutionTreeObject::I32(value) => Unexpected::Signed(value as i64),
+            &ExecutionTreeObject::I64(value) => Unexpected::Signed(value),
+            &ExecutionTreeObject::ISize(value) => Unexpected::Signed(value as i64),
+            &ExecutionTreeObject::Map(_) => Unexpected::Map,
+            &ExecutionTreeObject::Node(ref node) if node.get_objects().is_empty() => Unexpected::Unit,
+            &ExecutionTreeObject::Node(_) => Unexpected::Seq,
+            &ExecutionTreeObject::String(ref value) => Unexpected::Str(value),
+            &ExecutionTreeObject::Symbol(ref value) => Unexpected::Str(value),
+            &ExecutionTreeObject::U8(value) => Unexpected::Unsigned(value as u64),
+            &ExecutionTreeObject::U16(value) => Unexpected::Unsigned(value as u64),
+            &ExecutionTreeObject::U32(value) => Unexpected::Unsigned(value as u64),
+            &ExecutionTreeObject::U64(value) => Unexpected::Unsigned(value),
+            &ExecutionTreeObject::USize(value) => Unexpected::Unsigned(value as u64),
+            other => Unexpected::Other(other.enum_to_string()),
+        }
+    }
+}
+
+impl <'de> Deserializer<'de> for ObjectDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<TVisitor>(self, visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        match self.object {
+            &ExecutionTreeObject::BigInt(ref value) => {
+                match (value.to_i64(), value.to_u64()) {
+                    (Some(value), _) => visitor.visit_i64(value),
+                    (_, Some(value)) => visitor.visit_u64(value),
+                    _ => {
+                        match value.to_i128() {
+                            Some(value) => visitor.visit_i128(value),
+                            None => visitor.visit_string(value.to_string()),
+                        }
+                    },
+                }
+            },
+            &ExecutionTreeObject::Bool(value) => visitor.visit_bool(value),
+            &ExecutionTreeObject::Bytes(ref value) => visitor.visit_borrowed_bytes(value),
+            &ExecutionTreeObject::Char(value) => visitor.visit_char(value),
+            &ExecutionTreeObject::F32(value) => visitor.visit_f32(value),
+            &ExecutionTreeObject::F64(value) => visitor.visit_f64(value),
+            &ExecutionTreeObject::I8(value) => visitor.visit_i8(value),
+            &ExecutionTreeObject::I16(value) => visitor.visit_i16(value),
+            &ExecutionTreeObject::I32(value) => visitor.visit_i32(value),
+            &ExecutionTreeObject::I64(value) => visitor.visit_i64(value),
+            &ExecutionTreeObject::ISize(value) => visitor.visit_i64(value as i64),
+            &ExecutionTreeObject::Map(ref map) => visitor.visit_map(ObjectMapAccess::from_map(map)),
+            &ExecutionTreeObject::Node(ref node) => {
+                if node.is_keyword_list() {
+                    visitor.visit_map(ObjectMapAccess::from_keyword_list(node.get_objects()))
+                } else {
+                    visitor.visit_seq(ObjectSeqAccess::new(node.get_objects()))
+                }
+            },
+            &ExecutionTreeObject::Rational(ref value) => {
+                match value.to_f64() {
+                    Some(value) => visitor.visit_f64(value),
+                    None => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+                }
+            },
+            &ExecutionTreeObject::String(ref value) => visitor.visit_borrowed_str(value),
+            &ExecutionTreeObject::Symbol(ref value) => visitor.visit_borrowed_str(value),
+            &ExecutionTreeObject::U8(value) => visitor.visit_u8(value),
+            &ExecutionTreeObject::U16(value) => visitor.visit_u16(value),
+            &ExecutionTreeObject::U32(value) => visitor.visit_u32(value),
+            &ExecutionTreeObject::U64(value) => visitor.visit_u64(value),
+            &ExecutionTreeObject::USize(value) => visitor.visit_u64(value as u64),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_enum<TVisitor>(self, _name: &'static str, _variants: &'static [&'static str], visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        match self.object {
+            &ExecutionTreeObject::String(ref variant) | &ExecutionTreeObject::Symbol(ref variant) => {
+                visitor.visit_enum(ObjectEnumAccess::new(variant, &[]))
+            },
+            &ExecutionTreeObject::Node(ref node) => {
+                match node.get_objects().split_first() {
+                    Some((&ExecutionTreeObject::Symbol(ref variant), rest)) => visitor.visit_enum(ObjectEnumAccess::new(variant, rest)),
+                    _ => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+                }
+            },
+            _ => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_ignored_any<TVisitor>(self, visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_map<TVisitor>(self, visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        match self.object {
+            &ExecutionTreeObject::Map(ref map) => visitor.visit_map(ObjectMapAccess::from_map(map)),
+            &ExecutionTreeObject::Node(ref node) if node.get_objects().len() % 2 == 0 => {
+                visitor.visit_map(ObjectMapAccess::from_keyword_list(node.get_objects()))
+            },
+            _ => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_newtype_struct<TVisitor>(self, _name: &'static str, visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_option<TVisitor>(self, visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        match self.object {
+            &ExecutionTreeObject::Node(ref node) if node.get_objects().is_empty() => visitor.visit_none(),
+            &ExecutionTreeObject::Node(ref node) if serialization::is_nested_nil(self.object) => {
+                visitor.visit_some(ObjectDeserializer::new(&node.get_objects()[0]))
+            },
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_seq<TVisitor>(self, visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        match self.object {
+            &ExecutionTreeObject::Node(ref node) => visitor.visit_seq(ObjectSeqAccess::new(node.get_objects())),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_struct<TVisitor>(self, _name: &'static str, _fields: &'static [&'static str], visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_tuple<TVisitor>(self, _len: usize, visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<TVisitor>(self, _name: &'static str, _len: usize, visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_unit<TVisitor>(self, visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        match self.object {
+            &ExecutionTreeObject::Node(ref node) if node.get_objects().is_empty() => visitor.visit_unit(),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_unit_struct<TVisitor>(self, _name: &'static str, visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        self.deserialize_unit(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf identifier
+    }
+}
+
+struct ObjectEnumAccess<'de> {
+    rest: &'de [ExecutionTreeObject],
+    variant: &'de str,
+}
+
+impl <'de> ObjectEnumAccess<'de> {
+    fn new(variant: &'de str, rest: &'de [ExecutionTreeObject]) -> Self {
+        ObjectEnumAccess {
+            rest: rest,
+            variant: variant,
+        }
+    }
+}
+
+impl <'de> EnumAccess<'de> for ObjectEnumAccess<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<TSeed>(self, seed: TSeed) -> Result<(TSeed::Value, Self)>
+        where TSeed: DeserializeSeed<'de> {
+        let variant = de::value::BorrowedStrDeserializer::<Error>::new(self.variant);
+        let value = try!(seed.deserialize(variant));
+        Ok((value, self))
+    }
+}
+
+impl <'de> VariantAccess<'de> for ObjectEnumAccess<'de> {
+    type Error = Error;
+
+    fn newtype_variant_seed<TSeed>(self, seed: TSeed) -> Result<TSeed::Value>
+        where TSeed: DeserializeSeed<'de> {
+        match self.rest {
+            [ref object] => {
+                seed.deserialize(ObjectDeserializer::new(object))
+                    .map_err(|error| error.push_serialization_path(format!(".{}", self.variant)))
+            },
+            _ => Err(de::Error::invalid_length(self.rest.len(), &"a single value")),
+        }
+    }
+
+    fn struct_variant<TVisitor>(self, _fields: &'static [&'static str], visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        let variant = self.variant;
+        visitor.visit_map(ObjectMapAccess::from_keyword_list(self.rest))
+            .map_err(|error| error.push_serialization_path(format!(".{}", variant)))
+    }
+
+    fn tuple_variant<TVisitor>(self, _len: usize, visitor: TVisitor) -> Result<TVisitor::Value>
+        where TVisitor: Visitor<'de> {
+        let variant = self.variant;
+        visitor.visit_seq(ObjectSeqAccess::new(self.rest))
+            .map_err(|error| error.push_serialization_path(format!(".{}", variant)))
+    }
+
+    fn unit_variant(self) -> Result<()> {
+        if self.rest.is_empty() {
+            Ok(())
+        } else {
+            Err(de::Error::invalid_length(self.rest.len(), &"no values"))
+        }
+    }
+}
+
+struct ObjectMapAccess<'de> {
+    entries: Vec<(&'de ExecutionTreeObject, &'de ExecutionTreeObject)>,
+    index: usize,
+}
+
+impl <'de> ObjectMapAccess<'de> {
+    fn from_keyword_list(objects: &'de [ExecutionTreeObject]) -> Self {
+        ObjectMapAccess {
+            entries: objects.chunks(2).map(|pair| (&pair[0], &pair[1])).collect(),
+            index: 0,
+        }
+    }
+
+    fn from_map(map: &'de ExecutionTreeMap) -> Self {
+        ObjectMapAccess {
+            entries: map.iter().map(|&(ref key, ref value)| (key, value)).collect(),
+            index: 0,
+        }
+    }
+
+    fn get_key_path(key: &ExecutionTreeObject) -> String {
+        match key {
+            &ExecutionTreeObject::String(ref key) => format!(".{}", key),
+            &ExecutionTreeObject::Symbol(ref key) => format!(".{}", key.trim_start_matches(':')),
+            key => {
+                match key.to_string() {
+                    Ok(key) => format!("[{}]", key),
+                    Err(_) => format!("[{}]", key.enum_to_string()),
+                }
+            },
+        }
+    }
+}
+
+impl <'de> MapAccess<'de> for ObjectMapAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<TSeed>(&mut self, seed: TSeed) -> Result<Option<TSeed::Value>>
+        where TSeed: DeserializeSeed<'de> {
+        match self.entries.get(self.index) {
+            Some(&(key, _)) => {
+                let result =
+                    match key {
+                        &ExecutionTreeObject::Symbol(ref symbol) => {
+                            let key = de::value::BorrowedStrDeserializer::<Error>::new(symbol.trim_start_matches(':'));
+                            seed.deserialize(key)
+                        },
+                        key => seed.deserialize(ObjectDeserializer::new(key)),
+                    };
+                result
+                    .map(Some)
+                    .map_err(|error| error.push_serialization_path(Self::get_key_path(key)))
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<TSeed>(&mut self, seed: TSeed) -> Result<TSeed::Value>
+        where TSeed: DeserializeSeed<'de> {
+        match self.entries.get(self.index) {
+            Some(&(key, value)) => {
+                self.index += 1;
+                seed.deserialize(ObjectDeserializer::new(value))
+                    .map_err(|error| error.push_serialization_path(Self::get_key_path(key)))
+            },
+            None => Err(de::Error::custom("value is missing")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len() - self.index)
+    }
+}
+
+struct ObjectSeqAccess<'de> {
+    index: usize,
+    objects: Iter<'de, ExecutionTreeObject>,
+}
+
+impl <'de> ObjectSeqAccess<'de> {
+    fn new(objects: &'de [ExecutionTreeObject]) -> Self {
+        ObjectSeqAccess {
+            index: 0,
+            objects: objects.iter(),
+        }
+    }
+}
+
+impl <'de> SeqAccess<'de> for ObjectSeqAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<TSeed>(&mut self, seed: TSeed) -> Result<Option<TSeed::Value>>
+        where TSeed: DeserializeSeed<'de> {
+        match self.objects.next() {
+            Some(object) => {
+                let index = self.index;
+                self.index += 1;
+                seed.deserialize(ObjectDeserializer::new(object))
+                    .map(Some)
+                    .map_err(|error| error.push_serialization_path(format!("[{}]", index)))
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.objects.len())
+    }
+}
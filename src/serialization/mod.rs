@@ -0,0 +1,131 @@
+mod deserializer;
+
+mod serializer;
+
+pub use serialization::deserializer::ObjectDeserializer;
+
+pub use serialization::serializer::ObjectMapSerializer;
+
+pub use serialization::serializer::ObjectSeqSerializer;
+
+pub use serialization::serializer::ObjectSerializer;
+
+use error::*;
+use lisp::ExecutionTreeObject;
+use serde::de;
+use serde::de::Deserialize;
+use serde::ser;
+use serde::ser::Serialize;
+use std::fmt::Display;
+
+pub fn from_object<'de, T>(object: &'de ExecutionTreeObject) -> Result<T>
+    where T: Deserialize<'de> {
+    T::deserialize(ObjectDeserializer::new(object))
+}
+
+pub fn to_object<T>(value: &T) -> Result<ExecutionTreeObject>
+    where T: ?Sized + Serialize {
+    value.serialize(ObjectSerializer::new())
+}
+
+// None is written as (), so a Some whose value is () or a () wrapped in single element lists gets one
+// more list around it. Reading strips that list again, which keeps Some(Vec::new()) apart from None.
+fn is_nested_nil(object: &ExecutionTreeObject) -> bool {
+    match object {
+        &ExecutionTreeObject::Node(ref node) => {
+            match &node.get_objects()[..] {
+                [] => true,
+                [ref object] => is_nested_nil(object),
+                _ => false,
+            }
+        },
+        _ => false,
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T>(message: T) -> Self
+        where T: Display {
+        ErrorKind::Serialization(String::new(), message.to_string()).into()
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T>(message: T) -> Self
+        where T: Display {
+        ErrorKind::Serialization(String::new(), message.to_string()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use error::*;
+    use lisp::AbstractSyntaxTree;
+    use lisp::ExecutionTree;
+    use lisp::ExecutionTreeObject;
+    use serde::ser;
+    use serde::ser::Serialize;
+    use serde::ser::Serializer;
+    use serialization;
+    use std::collections::BTreeMap;
+
+    struct Unserializable;
+
+    impl Serialize for Unserializable {
+        fn serialize<TSerializer>(&self, _serializer: TSerializer) -> ::std::result::Result<TSerializer::Ok, TSerializer::Error>
+            where TSerializer: Serializer {
+            Err(ser::Error::custom("cannot serialize"))
+        }
+    }
+
+    fn parse(text: &str) -> ExecutionTreeObject {
+        let program_text = format!("({})", text);
+        let ast = AbstractSyntaxTree::new(&program_text).unwrap();
+        let execution_tree = ExecutionTree::new(&ast).unwrap();
+        execution_tree.into_root().into_objects().into_iter().next().unwrap()
+    }
+
+    fn get_path(error: Error) -> String {
+        match error {
+            Error(ErrorKind::Serialization(path, _), _) => path,
+            error => panic!("{}", error),
+        }
+    }
+
+    #[test]
+    fn options_round_trip() {
+        let values = vec![None, Some(Vec::new()), Some(vec![Vec::new()]), Some(vec![vec![1i32]])];
+        for value in values {
+            let object = serialization::to_object(&value).unwrap();
+            let result: Option<Vec<Vec<i32>>> = serialization::from_object(&object).unwrap();
+            assert_eq!(result, value);
+        }
+
+        let value = Some(Some(()));
+        let object = serialization::to_object(&value).unwrap();
+        assert_eq!(object.to_string().unwrap(), "((()))");
+        let result: Option<Option<()>> = serialization::from_object(&object).unwrap();
+        assert_eq!(result, value);
+    }
+
+    #[test]
+    fn plain_values_are_not_wrapped() {
+        assert_eq!(serialization::to_object(&Some(5i32)).unwrap().to_string().unwrap(), "5i32");
+        assert_eq!(serialization::to_object(&Some(vec![1i32])).unwrap().to_string().unwrap(), "(1i32)");
+    }
+
+    #[test]
+    fn deserialization_errors_report_the_path() {
+        let object = parse("(:servers ((:port 80i32) (:port \"x\")))");
+        let error = serialization::from_object::<BTreeMap<String, Vec<BTreeMap<String, i32>>>>(&object).unwrap_err();
+        assert_eq!(get_path(error), ".servers[1].port");
+    }
+
+    #[test]
+    fn serialization_errors_report_the_path() {
+        let mut map = BTreeMap::new();
+        map.insert("values".to_string(), vec![None, Some(Unserializable)]);
+        let error = serialization::to_object(&map).unwrap_err();
+        assert_eq!(get_path(error), ".values[1]");
+    }
+}
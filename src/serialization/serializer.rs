@@ -0,0 +1,359 @@
+use error::*;
+use lisp::ExecutionTreeMap;
+use lisp::ExecutionTreeNode;
+use lisp::ExecutionTreeObject;
+use num_bigint::BigInt;
+use serde::ser;
+use serde::ser::Serialize;
+use serde::ser::SerializeMap;
+use serde::ser::SerializeSeq;
+use serde::ser::SerializeStruct;
+use serde::ser::SerializeStructVariant;
+use serde::ser::SerializeTuple;
+use serde::ser::SerializeTupleStruct;
+use serde::ser::SerializeTupleVariant;
+use serde::ser::Serializer;
+use serialization;
+
+// Sequences and tuples become lists, maps become maps, and structs become keyword lists such as
+// (:name "a" :port 80i32) so they print as readable configuration. Enum variants with data become a list
+// headed by the variant name.
+pub struct ObjectSerializer;
+
+impl ObjectSerializer {
+    pub fn new() -> Self {
+        ObjectSerializer
+    }
+}
+
+impl Serializer for ObjectSerializer {
+    type Ok = ExecutionTreeObject;
+    type Error = Error;
+    type SerializeMap = ObjectMapSerializer;
+    type SerializeSeq = ObjectSeqSerializer;
+    type SerializeStruct = ObjectSeqSerializer;
+    type SerializeStructVariant = ObjectSeqSerializer;
+    type SerializeTuple = ObjectSeqSerializer;
+    type SerializeTupleStruct = ObjectSeqSerializer;
+    type SerializeTupleVariant = ObjectSeqSerializer;
+
+    fn serialize_bool(self, value: bool) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::Bool(value))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::Bytes(value.to_vec()))
+    }
+
+    fn serialize_char(self, value: char) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::Char(value))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::F32(value))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::F64(value))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::I8(value))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::I16(value))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::I32(value))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::I64(value))
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::BigInt(BigInt::from(value)))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<ObjectMapSerializer> {
+        Ok(ObjectMapSerializer::new())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<ExecutionTreeObject>
+        where T: ?Sized + Serialize {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T) -> Result<ExecutionTreeObject>
+        where T: ?Sized + Serialize {
+        let value = try!(value.serialize(ObjectSerializer).map_err(|error| error.push_serialization_path(format!(".{}", variant))));
+        Ok(ExecutionTreeObject::Node(ExecutionTreeNode::new(vec![ExecutionTreeObject::Symbol(variant.to_string()), value])))
+    }
+
+    fn serialize_none(self) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::nil())
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ObjectSeqSerializer> {
+        Ok(ObjectSeqSerializer::new(None, len.unwrap_or(0)))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<ExecutionTreeObject>
+        where T: ?Sized + Serialize {
+        let value = try!(value.serialize(self));
+        if serialization::is_nested_nil(&value) {
+            Ok(ExecutionTreeObject::Node(ExecutionTreeNode::new(vec![value])))
+        } else {
+            Ok(value)
+        }
+    }
+
+    fn serialize_str(self, value: &str) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::String(value.to_string()))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<ObjectSeqSerializer> {
+        Ok(ObjectSeqSerializer::new(None, len * 2))
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<ObjectSeqSerializer> {
+        Ok(ObjectSeqSerializer::new(Some(variant), len * 2 + 1))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ObjectSeqSerializer> {
+        Ok(ObjectSeqSerializer::new(None, len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ObjectSeqSerializer> {
+        Ok(ObjectSeqSerializer::new(None, len))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<ObjectSeqSerializer> {
+        Ok(ObjectSeqSerializer::new(Some(variant), len + 1))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::U8(value))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::U16(value))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::U32(value))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::U64(value))
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::BigInt(BigInt::from(value)))
+    }
+
+    fn serialize_unit(self) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::nil())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::nil())
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::Symbol(variant.to_string()))
+    }
+}
+
+pub struct ObjectMapSerializer {
+    key: Option<ExecutionTreeObject>,
+    map: ExecutionTreeMap,
+}
+
+impl ObjectMapSerializer {
+    fn new() -> Self {
+        ObjectMapSerializer {
+            key: None,
+            map: ExecutionTreeMap::new(),
+        }
+    }
+}
+
+impl SerializeMap for ObjectMapSerializer {
+    type Ok = ExecutionTreeObject;
+    type Error = Error;
+
+    fn end(self) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::Map(self.map))
+    }
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+        where T: ?Sized + Serialize {
+        self.key = Some(try!(key.serialize(ObjectSerializer)));
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+        where T: ?Sized + Serialize {
+        let key =
+            match self.key.take() {
+                Some(key) => key,
+                None => {
+                    return Err(ser::Error::custom("value serialized before its key"));
+                },
+            };
+        let value =
+            match value.serialize(ObjectSerializer) {
+                Ok(value) => value,
+                Err(error) => {
+                    let path =
+                        match key {
+                            ExecutionTreeObject::String(ref key) => format!(".{}", key),
+                            ref key => format!("[{}]", try!(key.to_string())),
+                        };
+                    return Err(error.push_serialization_path(path));
+                },
+            };
+        self.map.insert(key, value);
+        Ok(())
+    }
+}
+
+pub struct ObjectSeqSerializer {
+    objects: Vec<ExecutionTreeObject>,
+    variant: Option<&'static str>,
+}
+
+impl ObjectSeqSerializer {
+    fn new(variant: Option<&'static str>, len: usize) -> Self {
+        let mut objects = Vec::with_capacity(len);
+        if let Some(variant) = variant {
+            objects.push(ExecutionTreeObject::Symbol(variant.to_string()));
+        }
+        ObjectSeqSerializer {
+            objects: objects,
+            variant: variant,
+        }
+    }
+
+    fn end_node(self) -> Result<ExecutionTreeObject> {
+        Ok(ExecutionTreeObject::Node(ExecutionTreeNode::new(self.objects)))
+    }
+
+    fn push_element<T>(&mut self, value: &T) -> Result<()>
+        where T: ?Sized + Serialize {
+        let index = if self.variant.is_some() { self.objects.len() - 1 } else { self.objects.len() };
+        match value.serialize(ObjectSerializer) {
+            Ok(value) => {
+                self.objects.push(value);
+                Ok(())
+            },
+            Err(error) => Err(self.push_variant_path(error.push_serialization_path(format!("[{}]", index)))),
+        }
+    }
+
+    fn push_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+        where T: ?Sized + Serialize {
+        match value.serialize(ObjectSerializer) {
+            Ok(value) => {
+                self.objects.push(ExecutionTreeObject::Symbol(format!(":{}", key)));
+                self.objects.push(value);
+                Ok(())
+            },
+            Err(error) => Err(self.push_variant_path(error.push_serialization_path(format!(".{}", key)))),
+        }
+    }
+
+    fn push_variant_path(&self, error: Error) -> Error {
+        match self.variant {
+            Some(variant) => error.push_serialization_path(format!(".{}", variant)),
+            None => error,
+        }
+    }
+}
+
+impl SerializeSeq for ObjectSeqSerializer {
+    type Ok = ExecutionTreeObject;
+    type Error = Error;
+
+    fn end(self) -> Result<ExecutionTreeObject> {
+        self.end_node()
+    }
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+        where T: ?Sized + Serialize {
+        self.push_element(value)
+    }
+}
+
+impl SerializeStruct for ObjectSeqSerializer {
+    type Ok = ExecutionTreeObject;
+    type Error = Error;
+
+    fn end(self) -> Result<ExecutionTreeObject> {
+        self.end_node()
+    }
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+        where T: ?Sized + Serialize {
+        self.push_field(key, value)
+    }
+}
+
+impl SerializeStructVariant for ObjectSeqSerializer {
+    type Ok = ExecutionTreeObject;
+    type Error = Error;
+
+    fn end(self) -> Result<ExecutionTreeObject> {
+        self.end_node()
+    }
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+        where T: ?Sized + Serialize {
+        self.push_field(key, value)
+    }
+}
+
+impl SerializeTuple for ObjectSeqSerializer {
+    type Ok = ExecutionTreeObject;
+    type Error = Error;
+
+    fn end(self) -> Result<ExecutionTreeObject> {
+        self.end_node()
+    }
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+        where T: ?Sized + Serialize {
+        self.push_element(value)
+    }
+}
+
+impl SerializeTupleStruct for ObjectSeqSerializer {
+    type Ok = ExecutionTreeObject;
+    type Error = Error;
+
+    fn end(self) -> Result<ExecutionTreeObject> {
+        self.end_node()
+    }
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+        where T: ?Sized + Serialize {
+        self.push_element(value)
+    }
+}
+
+impl SerializeTupleVariant for ObjectSeqSerializer {
+    type Ok = ExecutionTreeObject;
+    type Error = Error;
+
+    fn end(self) -> Result<ExecutionTreeObject> {
+        self.end_node()
+    }
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+        where T: ?Sized + Serialize {
+        self.push_element(value)
+    }
+}
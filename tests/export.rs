@@ -0,0 +1,63 @@
+#![cfg(feature = "export")]
+
+extern crate simplisp;
+
+use simplisp::Environment;
+use simplisp::export;
+
+/// Adds two numbers.
+#[export]
+pub fn add_numbers(left: i64, right: i64) -> i64 {
+    left + right
+}
+
+#[export(name = "greet")]
+pub fn greeting(name: String, punctuation: Option<String>) -> String {
+    format!("hello {}{}", name, punctuation.unwrap_or_default())
+}
+
+pub struct Counter;
+
+#[export]
+impl Counter {
+    pub fn count_args(args: Vec<i64>) -> usize {
+        args.len()
+    }
+
+    #[lisp(name = "counter-zero")]
+    pub fn zero() -> i64 {
+        0
+    }
+}
+
+#[export]
+pub mod strings {
+    pub fn shout(text: String) -> String {
+        text.to_uppercase()
+    }
+}
+
+fn execute(environment: &mut Environment<()>, source: &str) -> String {
+    unsafe { environment.parse_and_execute(&(), source) }.unwrap()
+}
+
+#[test]
+fn exported_functions_are_registered() {
+    let mut environment = Environment::<()>::new();
+    add_numbers::register(environment.get_global_frame());
+    greeting::register(environment.get_global_frame());
+    assert_eq!(execute(&mut environment, "(add-numbers 1i64 2i64)"), "3i64");
+    assert_eq!(execute(&mut environment, "(greet \"lisp\")"), "\"hello lisp\"");
+    assert_eq!(execute(&mut environment, "(greet \"lisp\" \"!\")"), "\"hello lisp!\"");
+    assert_eq!(environment.get_doc("add-numbers").map(|doc| doc.as_str()), Some("Adds two numbers."));
+}
+
+#[test]
+fn exported_impl_blocks_and_modules_are_registered() {
+    let mut environment = Environment::<()>::new();
+    Counter::register(environment.get_global_frame());
+    strings::register(environment.get_global_frame());
+    assert_eq!(execute(&mut environment, "(count-args 1i64 2i64 3i64)"), "3usize");
+    assert_eq!(execute(&mut environment, "(counter-zero)"), "0i64");
+    assert_eq!(execute(&mut environment, "(shout \"quiet\")"), "\"QUIET\"");
+}